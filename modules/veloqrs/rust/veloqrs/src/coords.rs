@@ -57,6 +57,58 @@ pub fn decode(buf: &[u8]) -> Vec<crate::GpsPoint> {
     points
 }
 
+/// Delta + zigzag-varint encoding for a single numeric series (HR, power,
+/// altitude, ...), the columnar counterpart of `encode`.
+///
+/// Wire format:
+///   - Header: value_count as varint
+///   - Each value: (zigzag(delta) + 1) as varint, where delta is taken
+///     against the previous present value scaled by `scale`
+///   - A literal 0 marks a missing sample (NaN); it does not move the
+///     delta base, so a dropout costs one byte and keeps later deltas small.
+pub fn encode_series(values: &[f64], scale: f64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(4 + values.len() * 2);
+    write_varint(&mut buf, values.len() as u64);
+
+    let mut prev: i64 = 0;
+    for &v in values {
+        if !v.is_finite() {
+            write_varint(&mut buf, 0);
+            continue;
+        }
+        let scaled = (v * scale).round() as i64;
+        let delta = scaled - prev;
+        write_varint(&mut buf, (((delta << 1) ^ (delta >> 63)) as u64) + 1);
+        prev = scaled;
+    }
+
+    buf
+}
+
+/// Inverse of `encode_series`. Gaps decode as NaN.
+pub fn decode_series(buf: &[u8], scale: f64) -> Vec<f64> {
+    let mut pos = 0;
+    let count = read_varint(buf, &mut pos) as usize;
+    let mut values = Vec::with_capacity(count);
+
+    let mut cur: i64 = 0;
+    for _ in 0..count {
+        if pos >= buf.len() {
+            break;
+        }
+        let v = read_varint(buf, &mut pos);
+        if v == 0 {
+            values.push(f64::NAN);
+            continue;
+        }
+        let z = v - 1;
+        cur += ((z >> 1) as i64) ^ -((z & 1) as i64);
+        values.push(cur as f64 / scale);
+    }
+
+    values
+}
+
 fn write_varint(buf: &mut Vec<u8>, mut v: u64) {
     loop {
         let byte = (v & 0x7F) as u8;
@@ -148,4 +200,40 @@ mod tests {
         let ratio = flat_f64_size as f64 / encoded.len() as f64;
         assert!(ratio > 3.0, "compression ratio {} should be > 3x", ratio);
     }
+
+    #[test]
+    fn series_round_trip_with_gaps() {
+        let values = vec![142.0, 143.0, f64::NAN, f64::NAN, 141.0, 150.0, 0.0];
+        let encoded = encode_series(&values, 1.0);
+        let decoded = decode_series(&encoded, 1.0);
+
+        assert_eq!(decoded.len(), values.len());
+        for (orig, dec) in values.iter().zip(decoded.iter()) {
+            if orig.is_nan() {
+                assert!(dec.is_nan());
+            } else {
+                assert_eq!(orig, dec);
+            }
+        }
+    }
+
+    #[test]
+    fn series_round_trip_scaled() {
+        let values: Vec<f64> = (0..3600)
+            .map(|i| 412.3 + (i as f64 * 0.01).sin() * 25.0)
+            .collect();
+        let encoded = encode_series(&values, 10.0);
+        let decoded = decode_series(&encoded, 10.0);
+
+        assert_eq!(decoded.len(), 3600);
+        for (orig, dec) in values.iter().zip(decoded.iter()) {
+            assert!((orig - dec).abs() <= 0.05 + 1e-9);
+        }
+        // Small deltas at 0.1 m resolution fit in a single byte each
+        assert!(
+            encoded.len() < 3600 * 2,
+            "encoded size {} should be < 7200",
+            encoded.len()
+        );
+    }
 }
@@ -238,6 +238,289 @@ pub struct FfiPaceTrend {
     pub previous_date: Option<i64>,
 }
 
+/// Critical power model fitted from best efforts. `model` is "2p" (CP + W'/t)
+/// or "3p" (Morton, adds Pmax); `source` is "curve" when the efforts came
+/// from the stored power curve, "streams" when from watts streams.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiCriticalPowerFit {
+    pub sport_type: String,
+    pub model: String,
+    /// Critical power in watts
+    pub cp: f64,
+    /// W' in joules
+    pub w_prime: f64,
+    /// Maximal instantaneous power in watts (3-parameter model only)
+    pub p_max: Option<f64>,
+    pub r2: f64,
+    pub source: String,
+    /// Number of best-effort durations the fit used
+    pub effort_count: u32,
+    /// Snapshot date (Unix timestamp seconds, start of day)
+    pub date: i64,
+}
+
+/// Critical power trend, analogous to `FfiFtpTrend`, with the modelled
+/// power-duration curve of the latest snapshot.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiCpTrend {
+    /// Most recent CP in watts
+    pub latest_cp: Option<f64>,
+    pub latest_w_prime: Option<f64>,
+    pub latest_p_max: Option<f64>,
+    pub latest_r2: Option<f64>,
+    /// "2p" or "3p"
+    pub latest_model: Option<String>,
+    /// Date of most recent snapshot (Unix timestamp seconds)
+    pub latest_date: Option<i64>,
+    /// Previous different CP in watts
+    pub previous_cp: Option<f64>,
+    pub previous_w_prime: Option<f64>,
+    /// Date of previous snapshot (Unix timestamp seconds)
+    pub previous_date: Option<i64>,
+    /// Modelled power-duration curve: durations (seconds) and watts
+    pub model_secs: Vec<i64>,
+    pub model_watts: Vec<f64>,
+}
+
+/// Distances and durations scanned for stream best efforts.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiBestEffortTargets {
+    /// Fastest-time targets in metres
+    pub distances_meters: Vec<f64>,
+    /// Best mean power / heart rate targets in seconds
+    pub durations_secs: Vec<u32>,
+}
+
+/// One activity's best effort over a target, from its stored streams.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiBestEffort {
+    pub activity_id: String,
+    pub sport_type: String,
+    /// "distance", "power" or "heartrate"
+    pub effort_type: String,
+    /// Metres for distance efforts, seconds otherwise
+    pub target: f64,
+    /// Display label, e.g. "5 km" or "20 min power"
+    pub label: String,
+    /// Elapsed seconds for distance efforts, mean watts / bpm otherwise
+    pub value: f64,
+    /// Seconds from the activity start to the start of the effort
+    pub start_offset: f64,
+    /// Activity date (Unix timestamp seconds)
+    pub date: i64,
+}
+
+/// Best efforts over one target for a sport, across three windows.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiBestEffortRecord {
+    pub effort_type: String,
+    pub target: f64,
+    pub label: String,
+    pub all_time: Option<FfiBestEffort>,
+    /// Best since January 1st
+    pub season: Option<FfiBestEffort>,
+    pub last_90_days: Option<FfiBestEffort>,
+}
+
+/// Seconds per zone (Z1 first) computed locally from one activity's streams
+/// and the athlete's zones. Empty where zones or the stream are missing.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiActivityZoneTimes {
+    pub activity_id: String,
+    pub hr_secs: Vec<f64>,
+    pub power_secs: Vec<f64>,
+    pub pace_secs: Vec<f64>,
+}
+
+/// Power curve stored by sync for one sport and window.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiPowerCurve {
+    pub sport_type: String,
+    /// intervals.icu `curves` window: "s0", "42d" or "all"
+    pub window: String,
+    pub secs: Vec<i64>,
+    pub watts: Vec<f64>,
+    pub activity_ids: Vec<String>,
+    /// When the curve was fetched (Unix timestamp seconds)
+    pub fetched_at: i64,
+}
+
+/// Pace curve stored by sync for one sport and window.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiPaceCurve {
+    pub sport_type: String,
+    /// intervals.icu `curves` window: "s0", "42d" or "all"
+    pub window: String,
+    /// Distances in meters
+    pub distances: Vec<f64>,
+    /// Best time in seconds for each distance
+    pub times: Vec<f64>,
+    /// Pace in m/s for each distance
+    pub pace: Vec<f64>,
+    pub activity_ids: Vec<String>,
+    /// Critical speed in m/s
+    pub critical_speed: Option<f64>,
+    pub d_prime: Option<f64>,
+    pub r2: Option<f64>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub days: Option<i64>,
+    /// When the curve was fetched (Unix timestamp seconds)
+    pub fetched_at: i64,
+}
+
+/// Two stored windows of a curve sampled at the same durations.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiCurveComparison {
+    /// "power" or "pace"
+    pub kind: String,
+    pub sport_type: String,
+    pub window_a: String,
+    pub window_b: String,
+    /// Sample durations in seconds
+    pub durations: Vec<i64>,
+    /// Watts (power) or m/s (pace) per duration; None where not covered
+    pub values_a: Vec<Option<f64>>,
+    pub values_b: Vec<Option<f64>>,
+}
+
+/// Predicted time on a route or section for today's fitness.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiFinishTimePrediction {
+    /// Predicted time in seconds
+    pub predicted_time: f64,
+    /// Half-width of the ~80% interval in seconds ("12:34 ± 20s")
+    pub margin: f64,
+    pub lower_time: f64,
+    pub upper_time: f64,
+    /// Predicted average speed in m/s
+    pub predicted_speed: f64,
+    /// Typical effort distance in meters
+    pub distance_meters: f64,
+    /// Past efforts the prediction is based on
+    pub effort_count: u32,
+    /// Recency-weighted ratio applied for fitness change since the efforts
+    /// (1.0 = no FTP / critical speed history)
+    pub fitness_adjustment: f64,
+}
+
+/// One equal-length split of a ghost comparison.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiGhostSplit {
+    /// Split bounds in meters along the ghost's distance
+    pub start_distance: f64,
+    pub end_distance: f64,
+    /// Seconds each effort took over the split
+    pub effort_time: f64,
+    pub ghost_time: f64,
+    /// effort_time - ghost_time (negative = time won)
+    pub time_delta: f64,
+}
+
+/// Gap between an effort and a ghost effort on the same section.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiGhostComparison {
+    /// Sample positions in meters along the ghost's distance
+    pub distances: Vec<f64>,
+    /// Seconds behind the ghost at each sample (negative = ahead)
+    pub gaps: Vec<f64>,
+    /// Total elapsed seconds of each effort
+    pub effort_time: f64,
+    pub ghost_time: f64,
+    pub splits: Vec<FfiGhostSplit>,
+    /// Index into `splits` where the most time was won, if any was
+    pub best_split: Option<u32>,
+    /// Index into `splits` where the most time was lost, if any was
+    pub worst_split: Option<u32>,
+}
+
+/// An intermediate split point on a section polyline.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiSectionCheckpoint {
+    /// Order along the section, from 0
+    pub position: u32,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Distance along the section polyline in meters when placed
+    pub distance_meters: f64,
+}
+
+/// Split times of one traversal of a section.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiEffortSplits {
+    pub activity_id: String,
+    pub activity_name: String,
+    /// Unix timestamp
+    pub activity_date: i64,
+    /// "same" or "reverse"
+    pub direction: String,
+    /// Seconds per split in section order (checkpoints + 1 entries), also
+    /// for reverse traversals
+    pub split_times: Vec<f64>,
+    pub total_time: f64,
+}
+
+/// Best splits over all efforts in one direction.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiSplitBests {
+    /// Fastest time on each split in seconds
+    pub best_splits: Vec<f64>,
+    /// Activity that set each best split
+    pub best_split_activity_ids: Vec<String>,
+    /// Best possible time: the sum of `best_splits`
+    pub sum_of_best: f64,
+    /// Fastest actual time over the whole section
+    pub best_time: f64,
+    pub best_time_activity_id: String,
+    pub effort_count: u32,
+}
+
+/// Checkpoint split table for a section. Complements
+/// `FfiSectionPerformanceResult` with intermediate times.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiSectionSplitTable {
+    pub section_id: String,
+    pub checkpoints: Vec<FfiSectionCheckpoint>,
+    /// Efforts that passed every checkpoint, oldest first
+    pub efforts: Vec<FfiEffortSplits>,
+    /// Bests over forward/same direction efforts
+    pub forward: Option<FfiSplitBests>,
+    /// Bests over reverse direction efforts
+    pub reverse: Option<FfiSplitBests>,
+}
+
+/// One fixed-length stretch of a gradient profile.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiGradientSegment {
+    /// Meters from the start
+    pub start_distance: f64,
+    pub end_distance: f64,
+    /// Meters above sea level
+    pub start_elevation: f64,
+    pub end_elevation: f64,
+    /// Percent
+    pub gradient: f64,
+}
+
+/// Elevation profile and climb category of a section or route.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiClimbProfile {
+    pub distance_meters: f64,
+    /// Total ascent in meters
+    pub total_ascent: f64,
+    /// Net gradient start to finish, percent
+    pub avg_gradient: f64,
+    /// Steepest profile segment, percent
+    pub max_gradient: f64,
+    /// "cat4", "cat3", "cat2", "cat1" or "hc"; None if no categorised climb
+    pub climb_category: Option<String>,
+    /// Length and average gradient (%) of the hardest climb
+    pub climb_distance_meters: Option<f64>,
+    pub climb_gradient: Option<f64>,
+    /// Gradient per ~100 m, in order
+    pub segments: Vec<FfiGradientSegment>,
+}
+
 /// Summary card batch data: combines period stats, FTP trend, and pace trends.
 /// Reduces Home screen FFI calls from 5 to 1.
 #[derive(Debug, Clone, uniffi::Record)]
@@ -329,6 +612,24 @@ pub struct FfiRouteGroup {
     pub best_pace: Option<f64>,
     #[serde(default)]
     pub best_activity_id: Option<String>,
+    /// Route shape ("loop", "out_and_back", "point_to_point", "lollipop"),
+    /// None until classified
+    #[serde(default)]
+    pub shape: Option<String>,
+    /// Classification confidence (0.0-1.0)
+    #[serde(default)]
+    pub shape_confidence: Option<f64>,
+    /// Total ascent in meters, None until the climb profile is computed
+    #[serde(default)]
+    pub total_ascent: Option<f64>,
+    /// Net and steepest gradient, percent
+    #[serde(default)]
+    pub avg_gradient: Option<f64>,
+    #[serde(default)]
+    pub max_gradient: Option<f64>,
+    /// "cat4", "cat3", "cat2", "cat1" or "hc"; None if not a categorised climb
+    #[serde(default)]
+    pub climb_category: Option<String>,
 }
 
 impl From<tracematch::RouteGroup> for FfiRouteGroup {
@@ -344,6 +645,12 @@ impl From<tracematch::RouteGroup> for FfiRouteGroup {
             avg_time: g.avg_time,
             best_pace: g.best_pace,
             best_activity_id: g.best_activity_id,
+            shape: None,
+            shape_confidence: None,
+            total_ascent: None,
+            avg_gradient: None,
+            max_gradient: None,
+            climb_category: None,
         }
     }
 }
@@ -718,6 +1025,17 @@ pub struct FfiSection {
     // Visibility state
     pub disabled: bool,
     pub superseded_by: Option<String>,
+    /// Total ascent in meters, None until the climb profile is computed
+    #[serde(default)]
+    pub total_ascent: Option<f64>,
+    /// Net and steepest gradient, percent
+    #[serde(default)]
+    pub avg_gradient: Option<f64>,
+    #[serde(default)]
+    pub max_gradient: Option<f64>,
+    /// "cat4", "cat3", "cat2", "cat1" or "hc"; None if not a categorised climb
+    #[serde(default)]
+    pub climb_category: Option<String>,
 }
 
 impl From<crate::sections::Section> for FfiSection {
@@ -748,6 +1066,10 @@ impl From<crate::sections::Section> for FfiSection {
             end_index: s.end_index,
             disabled: s.disabled,
             superseded_by: s.superseded_by,
+            total_ascent: None,
+            avg_gradient: None,
+            max_gradient: None,
+            climb_category: None,
         }
     }
 }
@@ -776,6 +1098,8 @@ pub struct FfiSectionLap {
     pub start_index: u32,
     /// End index in the activity's GPS track
     pub end_index: u32,
+    /// Power / HR / cadence / climbing metrics from the activity's streams
+    pub metrics: FfiSectionEffortMetrics,
 }
 
 impl From<crate::SectionLap> for FfiSectionLap {
@@ -789,6 +1113,43 @@ impl From<crate::SectionLap> for FfiSectionLap {
             direction: l.direction,
             start_index: l.start_index,
             end_index: l.end_index,
+            metrics: l.metrics.into(),
+        }
+    }
+}
+
+/// Section effort metrics for FFI.
+/// Per-traversal power, HR, cadence and climbing figures; None when the
+/// activity has no stream for that metric.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, uniffi::Record)]
+#[serde(rename_all = "camelCase")]
+pub struct FfiSectionEffortMetrics {
+    /// Average heart rate in bpm
+    pub avg_hr: Option<f64>,
+    /// Average power in watts
+    pub avg_power: Option<f64>,
+    /// Normalized power in watts
+    pub normalized_power: Option<f64>,
+    /// Average power per kg of body weight
+    pub watts_per_kg: Option<f64>,
+    /// Average cadence in rpm / spm, zeros excluded
+    pub avg_cadence: Option<f64>,
+    /// Output:HR decoupling between the two halves, in percent
+    pub hr_drift: Option<f64>,
+    /// Vertical ascent rate in meters per hour
+    pub vam: Option<f64>,
+}
+
+impl From<crate::SectionEffortMetrics> for FfiSectionEffortMetrics {
+    fn from(m: crate::SectionEffortMetrics) -> Self {
+        Self {
+            avg_hr: m.avg_hr,
+            avg_power: m.avg_power,
+            normalized_power: m.normalized_power,
+            watts_per_kg: m.watts_per_kg,
+            avg_cadence: m.avg_cadence,
+            hr_drift: m.hr_drift,
+            vam: m.vam,
         }
     }
 }
@@ -818,6 +1179,8 @@ pub struct FfiSectionPerformanceRecord {
     pub direction: String,
     /// Section distance in meters
     pub section_distance: f64,
+    /// Effort metrics of the best lap
+    pub metrics: FfiSectionEffortMetrics,
 }
 
 impl From<crate::SectionPerformanceRecord> for FfiSectionPerformanceRecord {
@@ -834,6 +1197,7 @@ impl From<crate::SectionPerformanceRecord> for FfiSectionPerformanceRecord {
             avg_pace: r.avg_pace,
             direction: r.direction,
             section_distance: r.section_distance,
+            metrics: r.metrics.into(),
         }
     }
 }
@@ -1020,6 +1384,48 @@ impl From<crate::RoutePerformanceResult> for FfiRoutePerformanceResult {
     }
 }
 
+/// A stretch of an activity that left a route's consensus line.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiDeviationSegment {
+    /// First off-route point index in the activity track
+    pub start_index: u32,
+    /// Last off-route point index (inclusive)
+    pub end_index: u32,
+    /// Largest distance from the consensus line in meters
+    pub max_offset_meters: f64,
+    /// Distance ridden beyond the consensus between the rejoin points, in meters
+    pub extra_distance_meters: f64,
+}
+
+/// Off-route analysis of one activity against a route's consensus line.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiRouteDeviation {
+    pub group_id: String,
+    pub activity_id: String,
+    pub threshold_meters: f64,
+    /// Distance from the consensus line for every track point, in meters
+    pub offsets: Vec<f64>,
+    /// Runs of points beyond the threshold, in track order
+    pub segments: Vec<FfiDeviationSegment>,
+}
+
+/// A suggested ride built from known routes and sections.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiRouteSuggestion {
+    /// Route groups ridden, in order (the same route twice for repeats)
+    pub route_ids: Vec<String>,
+    /// Sections swapped in as detours
+    pub section_ids: Vec<String>,
+    pub distance_meters: f64,
+    pub elevation_gain_meters: f64,
+    /// Share of the distance on often-ridden routes and sections (0.0-1.0)
+    pub familiarity: f64,
+    /// Ranking score (0.0-1.0): distance match and familiarity
+    pub score: f64,
+    /// Full polyline, exportable with `export_course`
+    pub points: Vec<FfiGpsPoint>,
+}
+
 // ============================================================================
 // Heatmap Types
 // ============================================================================
@@ -1359,6 +1765,43 @@ pub struct FfiWellnessRow {
     pub motivation: Option<i32>,
 }
 
+/// Stored sensor streams for one activity, decoded from the columnar store.
+/// Every non-empty series has one sample per `time` entry; missing samples
+/// are NaN. Empty vectors mean the activity has no such stream.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiActivityStreams {
+    pub time: Vec<i64>,
+    pub heartrate: Vec<f64>,
+    pub watts: Vec<f64>,
+    pub cadence: Vec<f64>,
+    pub altitude: Vec<f64>,
+    pub velocity_smooth: Vec<f64>,
+    pub distance: Vec<f64>,
+    pub grade_smooth: Vec<f64>,
+    pub temp: Vec<f64>,
+    pub wbal: Vec<f64>,
+    /// Grade-adjusted pace in min/km
+    pub gap: Vec<f64>,
+}
+
+impl From<crate::net::types::ParsedStreams> for FfiActivityStreams {
+    fn from(s: crate::net::types::ParsedStreams) -> Self {
+        Self {
+            time: s.time,
+            heartrate: s.heartrate,
+            watts: s.watts,
+            cadence: s.cadence,
+            altitude: s.altitude,
+            velocity_smooth: s.velocity_smooth,
+            distance: s.distance,
+            grade_smooth: s.grade_smooth,
+            temp: s.temp,
+            wbal: s.wbal,
+            gap: s.gap,
+        }
+    }
+}
+
 /// Sparkline payload for the SummaryCard: rounded integer arrays, oldest
 /// first, forward-filled where needed so renderers produce continuous lines.
 /// Empty arrays mean "not enough data" (TS renders `undefined` / skips).
@@ -1382,6 +1825,40 @@ pub struct FfiHrvTrend {
     pub sparkline: Vec<f64>,
 }
 
+/// One day of the local fitness model. `server_ctl` / `server_atl` are the
+/// intervals.icu values stored in wellness for the day, when synced.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiFitnessModelDay {
+    /// Local date, YYYY-MM-DD
+    pub date: String,
+    /// Training load for the day (summed activity load, or the planned load)
+    pub load: f64,
+    /// Fitness
+    pub ctl: f64,
+    /// Fatigue
+    pub atl: f64,
+    /// Form: ctl - atl
+    pub tsb: f64,
+    pub server_ctl: Option<f64>,
+    pub server_atl: Option<f64>,
+}
+
+/// Local CTL/ATL/TSB model: modeled history up to today plus a projection
+/// over planned loads. The errors are the mean absolute difference to the
+/// server values over the modeled days; None when the server reported none.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiFitnessModel {
+    /// Time constants used, in days
+    pub ctl_time_constant: f64,
+    pub atl_time_constant: f64,
+    /// Oldest first, ending today
+    pub history: Vec<FfiFitnessModelDay>,
+    /// One day per planned load, starting tomorrow
+    pub projection: Vec<FfiFitnessModelDay>,
+    pub ctl_error: Option<f64>,
+    pub atl_error: Option<f64>,
+}
+
 /// Ranked sections for one sport, paired with the sport label. One element
 /// per input sport in `get_ranked_sections_batch`.
 #[derive(Debug, Clone, uniffi::Record)]
@@ -1461,6 +1938,41 @@ pub struct FfiRecentPR {
     pub days_ago: u32,
 }
 
+/// An effort behind a race prediction. `source` is "section", "route" or
+/// "critical_speed"; `source_id` is the section or route id. Critical speed
+/// appears as its one-hour equivalent distance.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiRaceEffort {
+    pub source: String,
+    pub source_id: Option<String>,
+    pub activity_id: Option<String>,
+    pub distance_meters: f64,
+    pub time_secs: f64,
+    /// Unix timestamp of the effort / snapshot
+    pub date: i64,
+    /// Race time this effort predicts on its own, in seconds
+    pub predicted_time: f64,
+    /// Recency x distance-proximity weight (0-1)
+    pub weight: f64,
+}
+
+/// Predicted running race time. `label` is "5k", "10k", "half" or
+/// "marathon"; `method` is "riegel" or "critical_speed" (the method of the
+/// highest-weighted effort); `confidence` is "high", "medium" or "low".
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiRacePrediction {
+    pub label: String,
+    pub distance_meters: f64,
+    /// Predicted time in seconds
+    pub predicted_time: f64,
+    pub method: String,
+    pub confidence: String,
+    /// 0-1 score behind `confidence`
+    pub confidence_score: f64,
+    /// Efforts averaged into the prediction, highest weight first
+    pub efforts: Vec<FfiRaceEffort>,
+}
+
 /// Batch insights data: combines period stats, trends, patterns, and recent PRs.
 /// Reduces Insights hook FFI calls from 13-16 to 1.
 #[derive(Debug, Clone, uniffi::Record)]
@@ -1483,6 +1995,8 @@ pub struct FfiInsightsData {
     pub today_pattern: Option<FfiActivityPattern>,
     /// Up to 3 recent section PRs (best times set in last 7 days)
     pub recent_prs: Vec<FfiRecentPR>,
+    /// Predicted running race times (5k to marathon)
+    pub race_predictions: Vec<FfiRacePrediction>,
 }
 
 // ============================================================================
@@ -1598,6 +2112,7 @@ mod tests {
             direction: "forward".to_string(),
             start_index: 0,
             end_index: 100,
+            metrics: Default::default(),
         };
         let ffi_lap = FfiSectionLap::from(lap);
         assert_eq!(ffi_lap.id, "lap_1");
@@ -1618,6 +2133,7 @@ mod tests {
             direction: "forward".to_string(),
             start_index: 0,
             end_index: 100,
+            metrics: Default::default(),
         };
         let record = crate::SectionPerformanceRecord {
             activity_id: "act_123".to_string(),
@@ -1631,12 +2147,19 @@ mod tests {
             avg_pace: 8.3,
             direction: "forward".to_string(),
             section_distance: 1000.0,
+            metrics: crate::SectionEffortMetrics {
+                avg_power: Some(250.0),
+                normalized_power: Some(262.0),
+                ..Default::default()
+            },
         };
         let ffi_record = FfiSectionPerformanceRecord::from(record);
         assert_eq!(ffi_record.activity_id, "act_123");
         assert_eq!(ffi_record.activity_name, "Morning Ride");
         assert_eq!(ffi_record.laps.len(), 1);
         assert_eq!(ffi_record.best_time, 120.5);
+        assert_eq!(ffi_record.metrics.normalized_power, Some(262.0));
+        assert_eq!(ffi_record.metrics.avg_hr, None);
     }
 
     #[test]
@@ -1941,12 +2464,14 @@ pub struct FfiActivityRouteHighlight {
 #[derive(Debug, Clone, uniffi::Record)]
 pub struct FfiActivityIndicator {
     pub activity_id: String,
-    /// "section_pr", "route_pr", "section_trend", "route_trend"
+    /// "section_pr", "route_pr", "section_trend", "route_trend",
+    /// "effort_pr", "effort_season_pr"
     pub indicator_type: String,
-    /// section_id or route_id
+    /// section_id or route_id; "{sport}:{effort_type}:{target}" for efforts
     pub target_id: String,
     pub target_name: String,
     pub direction: String,
+    /// Seconds; mean watts / bpm for power and heart rate efforts
     pub lap_time: f64,
     /// -1=declining, 0=stable, 1=improving
     pub trend: i8,
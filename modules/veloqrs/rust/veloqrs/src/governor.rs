@@ -3,10 +3,10 @@
 //!
 //! This module owns the transport-agnostic *policy* seams - retry backoff,
 //! `Authorization` header formatting, and rate-limit-header parsing. They are
-//! pure functions so they can be unit-tested without a network. The richer
-//! policy (a live budget cell, priority lanes, a per-pool reserve) is layered
-//! on top by the rate-limit follow-up plan; this module ships the seams it
-//! plugs into.
+//! pure functions so they can be unit-tested without a network. The
+//! budget-aware `BudgetPolicy` (a live budget cell fed from `X-RateLimit-*`, a
+//! reserve held for interactive work, per-lane pacing) plugs into the
+//! `Policy` seam.
 
 use once_cell::sync::Lazy;
 use std::sync::Arc;
@@ -102,8 +102,8 @@ pub fn parse_rate_headers(
     }
 }
 
-/// Request priority lane. The baseline policy ignores it; the rate-limit plan
-/// uses it to reserve headroom for interactive work over backfill.
+/// Request priority lane. `NoopPolicy` ignores it; `BudgetPolicy` uses it to
+/// reserve headroom for interactive work over backfill.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Lane {
     /// A user is waiting on this (a tapped screen, an upload).
@@ -112,10 +112,8 @@ pub enum Lane {
     Backfill,
 }
 
-/// Policy layered on top of the baseline pace. The baseline is an identity
-/// no-op (`NoopPolicy`); the rate-limit follow-up plan supplies a budget-aware
-/// implementation (a live budget cell from `X-RateLimit-*`, a reserve, and
-/// per-lane pacing). This is the seam the policy plugs into.
+/// Policy layered on top of the baseline pace. `NoopPolicy` is the identity;
+/// `BudgetPolicy` is the budget-aware implementation the process runs with.
 pub trait Policy: Send + Sync {
     /// Extra delay to add before dispatching a request in `lane`. Baseline: zero.
     fn pace(&self, lane: Lane) -> Duration;
@@ -133,16 +131,163 @@ impl Policy for NoopPolicy {
     fn observe(&self, _budget: &RateBudget) {}
 }
 
+/// Share of the 15-minute budget held back for `Lane::Interactive`.
+const RESERVE_15M: f64 = 0.2;
+
+/// Share of the daily budget held back for `Lane::Interactive`.
+const RESERVE_DAILY: f64 = 0.1;
+
+/// Backfill starts slowing once headroom drops below this multiple of the
+/// reserve, reaching `MAX_BACKFILL_PACE` just above the reserve.
+const SLOWDOWN_FACTOR: f64 = 2.0;
+
+/// Largest per-request delay while backfill is merely slowed.
+const MAX_BACKFILL_PACE: Duration = Duration::from_secs(2);
+
+/// Backfill delay once the 15-minute reserve is reached. Each paused request
+/// doubles as a probe: its response refreshes the budget after the reset.
+const BACKFILL_PAUSE_15M: Duration = Duration::from_secs(60);
+
+/// Backfill delay once the daily reserve is reached.
+const BACKFILL_PAUSE_DAILY: Duration = Duration::from_secs(300);
+
+/// Upper bound on how long a `Retry-After` pauses backfill.
+const MAX_BACKFILL_RETRY_PAUSE: Duration = Duration::from_secs(15 * 60);
+
+/// Observations older than their window no longer describe the budget.
+const WINDOW_15M: Duration = Duration::from_secs(15 * 60);
+const WINDOW_DAILY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The live budget cell behind `BudgetPolicy`.
+#[derive(Default)]
+struct BudgetCell {
+    budget: RateBudget,
+    observed_at: Option<Instant>,
+    paused_until: Option<Instant>,
+}
+
+/// Budget-aware policy. Tracks the latest `X-RateLimit-*` snapshot and keeps
+/// a reserve of both windows for `Lane::Interactive`: interactive requests are
+/// never paced, while backfill slows as headroom shrinks toward the reserve and
+/// pauses once it is reached (or while a `Retry-After` is in force).
+pub struct BudgetPolicy {
+    cell: std::sync::Mutex<BudgetCell>,
+}
+
+impl Default for BudgetPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BudgetPolicy {
+    pub fn new() -> Self {
+        Self {
+            cell: std::sync::Mutex::new(BudgetCell::default()),
+        }
+    }
+
+    /// The most recently observed budget.
+    pub fn budget(&self) -> RateBudget {
+        self.cell.lock().unwrap_or_else(|e| e.into_inner()).budget
+    }
+
+    fn backfill_pace(&self, now: Instant) -> Duration {
+        let cell = self.cell.lock().unwrap_or_else(|e| e.into_inner());
+        let mut pace = cell
+            .paused_until
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+        if let Some(at) = cell.observed_at {
+            let age = now.saturating_duration_since(at);
+            let b = &cell.budget;
+            if age < WINDOW_15M {
+                pace = pace.max(window_pace(
+                    b.remaining_15m,
+                    b.limit_15m,
+                    RESERVE_15M,
+                    BACKFILL_PAUSE_15M,
+                ));
+            }
+            if age < WINDOW_DAILY {
+                pace = pace.max(window_pace(
+                    b.remaining_daily,
+                    b.limit_daily,
+                    RESERVE_DAILY,
+                    BACKFILL_PAUSE_DAILY,
+                ));
+            }
+        }
+        pace
+    }
+}
+
+/// Backfill pace for one window: zero with ample headroom, a linear slowdown
+/// as `remaining` nears the reserve, and `pause` at or below it. Unknown
+/// values impose nothing.
+fn window_pace(
+    remaining: Option<u32>,
+    limit: Option<u32>,
+    reserve_frac: f64,
+    pause: Duration,
+) -> Duration {
+    let (Some(remaining), Some(limit)) = (remaining, limit) else {
+        return Duration::ZERO;
+    };
+    if limit == 0 {
+        return Duration::ZERO;
+    }
+    let reserve = limit as f64 * reserve_frac;
+    let remaining = remaining as f64;
+    if remaining <= reserve {
+        return pause;
+    }
+    let slow_at = reserve * SLOWDOWN_FACTOR;
+    if remaining >= slow_at {
+        return Duration::ZERO;
+    }
+    MAX_BACKFILL_PACE.mul_f64((slow_at - remaining) / (slow_at - reserve))
+}
+
+impl Policy for BudgetPolicy {
+    fn pace(&self, lane: Lane) -> Duration {
+        match lane {
+            Lane::Interactive => Duration::ZERO,
+            Lane::Backfill => self.backfill_pace(Instant::now()),
+        }
+    }
+
+    fn observe(&self, budget: &RateBudget) {
+        let now = Instant::now();
+        let mut cell = self.cell.lock().unwrap_or_else(|e| e.into_inner());
+        if budget.remaining_15m.is_some() || budget.remaining_daily.is_some() {
+            let b = &mut cell.budget;
+            b.limit_15m = budget.limit_15m.or(b.limit_15m);
+            b.limit_daily = budget.limit_daily.or(b.limit_daily);
+            b.remaining_15m = budget.remaining_15m.or(b.remaining_15m);
+            b.remaining_daily = budget.remaining_daily.or(b.remaining_daily);
+            cell.observed_at = Some(now);
+        }
+        if let Some(secs) = budget.retry_after_secs {
+            let pause = Duration::from_secs(secs).min(MAX_BACKFILL_RETRY_PAUSE);
+            cell.budget.retry_after_secs = Some(secs);
+            cell.paused_until = Some(now + pause);
+        }
+    }
+}
+
 /// The process-wide dispatch choke point. Every outbound intervals.icu request
 /// acquires a slot here first, so one shared limiter governs the whole process
 /// rather than per-call pacers that can collectively exceed the per-IP cap.
 ///
 /// The baseline paces at a fixed `min_interval` (≤8 req/s, under the 10 req/s
-/// per-IP hard limit) plus whatever the `Policy` adds. Scheduling holds a brief
+/// per-IP hard limit). A request the `Policy` paces first waits its delay on a
+/// separate paced gate, so a slowed or paused backfill never pushes back the
+/// shared slot that interactive requests take. Scheduling holds a brief
 /// non-async lock; the wait happens outside the lock so it never blocks others.
 pub struct Governor {
     min_interval: Duration,
     next_at: std::sync::Mutex<Option<Instant>>,
+    paced_next_at: std::sync::Mutex<Option<Instant>>,
     policy: Box<dyn Policy>,
 }
 
@@ -153,6 +298,7 @@ impl Governor {
         Self {
             min_interval: Duration::from_secs_f64(1.0 / per_sec),
             next_at: std::sync::Mutex::new(None),
+            paced_next_at: std::sync::Mutex::new(None),
             policy,
         }
     }
@@ -160,12 +306,27 @@ impl Governor {
     /// Acquire a dispatch slot for `lane`, awaiting until the shared pace allows.
     /// Never holds the scheduling lock across the await.
     pub async fn acquire(&self, lane: Lane) {
-        let interval = self.min_interval + self.policy.pace(lane);
+        let pace = self.policy.pace(lane);
+        if !pace.is_zero() {
+            // Paced requests queue behind each other, `pace` apart, before they
+            // compete for the shared slot.
+            let released = {
+                let mut next = self.paced_next_at.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                let released = (*next).map_or(now, |t| t.max(now)) + pace;
+                *next = Some(released);
+                released
+            };
+            let now = Instant::now();
+            if released > now {
+                tokio::time::sleep(released - now).await;
+            }
+        }
         let scheduled = {
             let mut next = self.next_at.lock().unwrap_or_else(|e| e.into_inner());
             let now = Instant::now();
             let scheduled = (*next).map_or(now, |t| t.max(now));
-            *next = Some(scheduled + interval);
+            *next = Some(scheduled + self.min_interval);
             scheduled
         };
         let now = Instant::now();
@@ -183,12 +344,15 @@ impl Governor {
 /// Largest sustained dispatch rate, under intervals.icu's 10 req/s per-IP cap.
 const MAX_DISPATCH_PER_SEC: u32 = 8;
 
-/// The shared process-wide governor. Ships with the baseline no-op policy; the
-/// rate-limit plan replaces the policy with a budget-aware one. Held in an `Arc`
-/// so transports clone a handle to the same limiter (and tests can inject a
-/// fast local one for isolation).
-pub static GOVERNOR: Lazy<Arc<Governor>> =
-    Lazy::new(|| Arc::new(Governor::new(MAX_DISPATCH_PER_SEC, Box::new(NoopPolicy))));
+/// The shared process-wide governor, running the budget-aware policy. Held in
+/// an `Arc` so transports clone a handle to the same limiter (and tests can
+/// inject a fast local one for isolation).
+pub static GOVERNOR: Lazy<Arc<Governor>> = Lazy::new(|| {
+    Arc::new(Governor::new(
+        MAX_DISPATCH_PER_SEC,
+        Box::new(BudgetPolicy::new()),
+    ))
+});
 
 #[cfg(test)]
 mod tests {
@@ -330,4 +494,119 @@ mod tests {
         gov.observe(&RateBudget::default());
         assert_eq!(counter.load(Ordering::Relaxed), 1);
     }
+
+    fn budget(remaining_15m: u32, remaining_daily: u32) -> RateBudget {
+        RateBudget {
+            limit_15m: Some(1000),
+            limit_daily: Some(10000),
+            remaining_15m: Some(remaining_15m),
+            remaining_daily: Some(remaining_daily),
+            retry_after_secs: None,
+        }
+    }
+
+    #[test]
+    fn budget_policy_leaves_ample_headroom_unpaced() {
+        let p = BudgetPolicy::new();
+        assert_eq!(
+            p.pace(Lane::Backfill),
+            Duration::ZERO,
+            "nothing observed yet"
+        );
+        p.observe(&budget(900, 9000));
+        assert_eq!(p.pace(Lane::Backfill), Duration::ZERO);
+        assert_eq!(p.pace(Lane::Interactive), Duration::ZERO);
+    }
+
+    #[test]
+    fn budget_policy_slows_backfill_near_reserve() {
+        let p = BudgetPolicy::new();
+        // 15m reserve is 200; slowing starts below 400.
+        p.observe(&budget(300, 9000));
+        let pace = p.pace(Lane::Backfill);
+        assert!(
+            pace > Duration::ZERO && pace < MAX_BACKFILL_PACE,
+            "{:?}",
+            pace
+        );
+        p.observe(&budget(250, 9000));
+        assert!(
+            p.pace(Lane::Backfill) > pace,
+            "less headroom, slower backfill"
+        );
+        assert_eq!(p.pace(Lane::Interactive), Duration::ZERO);
+    }
+
+    #[test]
+    fn budget_policy_pauses_backfill_at_reserve() {
+        let p = BudgetPolicy::new();
+        p.observe(&budget(150, 9000));
+        assert_eq!(p.pace(Lane::Backfill), BACKFILL_PAUSE_15M);
+        p.observe(&budget(900, 500));
+        assert_eq!(p.pace(Lane::Backfill), BACKFILL_PAUSE_DAILY);
+        assert_eq!(p.pace(Lane::Interactive), Duration::ZERO);
+    }
+
+    #[test]
+    fn budget_policy_honours_retry_after_for_backfill() {
+        let p = BudgetPolicy::new();
+        p.observe(&RateBudget {
+            retry_after_secs: Some(30),
+            ..RateBudget::default()
+        });
+        let pace = p.pace(Lane::Backfill);
+        assert!(pace > Duration::from_secs(25) && pace <= Duration::from_secs(30));
+        assert_eq!(p.pace(Lane::Interactive), Duration::ZERO);
+    }
+
+    #[test]
+    fn budget_policy_ignores_stale_windows() {
+        let p = BudgetPolicy::new();
+        p.observe(&budget(100, 9000));
+        let later = Instant::now() + WINDOW_15M + Duration::from_secs(1);
+        assert_eq!(
+            p.backfill_pace(later),
+            Duration::ZERO,
+            "15m window has reset"
+        );
+    }
+
+    #[test]
+    fn budget_policy_keeps_last_known_fields() {
+        let p = BudgetPolicy::new();
+        p.observe(&budget(900, 9000));
+        p.observe(&parse_rate_headers(None, Some("850"), None));
+        let b = p.budget();
+        assert_eq!(b.remaining_15m, Some(850));
+        assert_eq!(b.remaining_daily, Some(9000));
+        assert_eq!(b.limit_15m, Some(1000));
+    }
+
+    #[test]
+    fn paced_backfill_does_not_delay_interactive() {
+        struct SlowBackfill;
+        impl Policy for SlowBackfill {
+            fn pace(&self, lane: Lane) -> Duration {
+                match lane {
+                    Lane::Interactive => Duration::ZERO,
+                    Lane::Backfill => Duration::from_secs(5),
+                }
+            }
+            fn observe(&self, _budget: &RateBudget) {}
+        }
+        crate::runtime::block_on(async {
+            let gov = Arc::new(Governor::new(1000, Box::new(SlowBackfill)));
+            let g = gov.clone();
+            let backfill = crate::runtime::spawn(async move { g.acquire(Lane::Backfill).await });
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let start = Instant::now();
+            gov.acquire(Lane::Interactive).await;
+            assert!(
+                start.elapsed() < Duration::from_secs(1),
+                "interactive waited behind paused backfill: {:?}",
+                start.elapsed()
+            );
+            backfill.abort();
+        });
+    }
 }
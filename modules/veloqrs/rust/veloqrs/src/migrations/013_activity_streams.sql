@@ -0,0 +1,13 @@
+-- Migration 013: Columnar store for full activity streams
+-- One row per (activity, stream type). `data` is a delta-varint series
+-- (coords::encode_series) at fixed-point `scale`; NaN gaps are preserved.
+-- No foreign key to activities: indoor activities have streams but no GPS row.
+
+CREATE TABLE IF NOT EXISTS activity_streams (
+    activity_id TEXT NOT NULL,
+    stream_type TEXT NOT NULL,
+    scale REAL NOT NULL,
+    point_count INTEGER NOT NULL,
+    data BLOB NOT NULL,
+    PRIMARY KEY (activity_id, stream_type)
+);
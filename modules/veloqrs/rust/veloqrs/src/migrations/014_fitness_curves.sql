@@ -0,0 +1,21 @@
+-- Migration 014: Power and pace curves persisted per sport and window
+-- `curve_window` is the intervals.icu `curves` parameter (s0, 42d, all).
+-- Series are delta-varint blobs (coords::encode_series); pace curves store
+-- distance/time pairs and derive pace on read.
+
+CREATE TABLE IF NOT EXISTS fitness_curves (
+    kind TEXT NOT NULL,
+    sport_type TEXT NOT NULL,
+    curve_window TEXT NOT NULL,
+    x_data BLOB NOT NULL,
+    y_data BLOB NOT NULL,
+    activity_ids TEXT,
+    critical_speed REAL,
+    d_prime REAL,
+    r2 REAL,
+    start_date TEXT,
+    end_date TEXT,
+    days INTEGER,
+    fetched_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, sport_type, curve_window)
+);
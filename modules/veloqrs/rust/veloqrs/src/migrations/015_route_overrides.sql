@@ -0,0 +1,9 @@
+-- Migration 015: User route merges and splits
+-- Pins an activity to a user-defined route key. Grouping passes rebuild the
+-- algorithm's groups so activities sharing a key form one route.
+
+CREATE TABLE IF NOT EXISTS route_overrides (
+    activity_id TEXT PRIMARY KEY,
+    route_key TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_route_overrides_key ON route_overrides(route_key);
@@ -0,0 +1,7 @@
+-- Migration 016: Route shape classification
+-- Loop / out-and-back / point-to-point / lollipop, classified from the
+-- consensus polyline. NULL until classified; carried across regrouping
+-- while a group's membership is unchanged.
+
+ALTER TABLE route_groups ADD COLUMN shape TEXT;
+ALTER TABLE route_groups ADD COLUMN shape_confidence REAL;
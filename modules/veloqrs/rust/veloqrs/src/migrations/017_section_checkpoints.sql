@@ -0,0 +1,13 @@
+-- Migration 017: Section checkpoints
+-- Intermediate split points on a section polyline, in section order. The
+-- position is stored as coordinates so checkpoints survive trims and
+-- recalibrations of the polyline; distance is along the polyline when placed.
+
+CREATE TABLE IF NOT EXISTS section_checkpoints (
+    section_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    latitude REAL NOT NULL,
+    longitude REAL NOT NULL,
+    distance_meters REAL NOT NULL,
+    PRIMARY KEY (section_id, position)
+);
@@ -0,0 +1,18 @@
+-- Migration 018: Climb profiles for sections and routes
+-- Total ascent, gradients and climb category (cat4 / cat3 / cat2 / cat1 / hc)
+-- of a section polyline or route consensus, plus the elevation samples behind
+-- the gradient profile. source_key fingerprints the polyline the row was
+-- computed from; NULL metrics mean no elevation data was available.
+
+CREATE TABLE IF NOT EXISTS climb_profiles (
+    target_type TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    source_key TEXT NOT NULL,
+    profile_distance REAL NOT NULL,
+    total_ascent REAL,
+    avg_gradient REAL,
+    max_gradient REAL,
+    climb_category TEXT,
+    samples_blob BLOB,
+    PRIMARY KEY (target_type, target_id)
+);
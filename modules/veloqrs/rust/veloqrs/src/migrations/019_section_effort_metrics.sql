@@ -0,0 +1,13 @@
+-- Migration 019: Per-traversal effort metrics on section_activities
+-- Average / normalized power, W/kg, cadence, HR drift and VAM computed from
+-- the stored activity streams over each portion's index range (avg_hr from
+-- M12 is filled by the same pass). metrics_computed marks rows the pass has
+-- seen; it is reset when an activity's streams are replaced.
+
+ALTER TABLE section_activities ADD COLUMN avg_power REAL;
+ALTER TABLE section_activities ADD COLUMN normalized_power REAL;
+ALTER TABLE section_activities ADD COLUMN watts_per_kg REAL;
+ALTER TABLE section_activities ADD COLUMN avg_cadence REAL;
+ALTER TABLE section_activities ADD COLUMN hr_drift REAL;
+ALTER TABLE section_activities ADD COLUMN vam REAL;
+ALTER TABLE section_activities ADD COLUMN metrics_computed INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,18 @@
+-- Migration 020: Critical power model history for cycling
+-- CP / W' (and Pmax for the 3-parameter model) fitted locally from stored
+-- power curves or watts streams, one snapshot per day and sport. The
+-- counterpart of pace_history's critical speed / D' for running.
+
+CREATE TABLE IF NOT EXISTS cp_history (
+    date INTEGER NOT NULL,
+    sport_type TEXT NOT NULL,
+    model TEXT NOT NULL,
+    cp REAL NOT NULL,
+    w_prime REAL NOT NULL,
+    p_max REAL,
+    r2 REAL,
+    source TEXT NOT NULL,
+    effort_count INTEGER NOT NULL,
+    PRIMARY KEY (date, sport_type)
+);
+CREATE INDEX IF NOT EXISTS idx_cp_history_sport_date ON cp_history(sport_type, date DESC);
@@ -0,0 +1,49 @@
+-- Migration 021: Stream best efforts
+-- Fastest time over fixed distances and best mean power / heart rate over
+-- fixed durations, one row per activity and target, computed from
+-- activity_streams. best_effort_scans records which target set each
+-- activity was last scanned with, so a changed set or replaced streams only
+-- rescan what is stale.
+-- activity_indicators is rebuilt to admit the effort_* types (SQLite cannot
+-- alter a CHECK constraint in place).
+
+CREATE TABLE IF NOT EXISTS activity_best_efforts (
+    activity_id TEXT NOT NULL,
+    effort_type TEXT NOT NULL CHECK(effort_type IN ('distance', 'power', 'heartrate')),
+    -- metres for distance, seconds for power / heartrate
+    target REAL NOT NULL,
+    -- elapsed seconds for distance, mean watts / bpm otherwise
+    value REAL NOT NULL,
+    start_offset REAL NOT NULL,
+    sport_type TEXT NOT NULL,
+    date INTEGER NOT NULL,
+    PRIMARY KEY (activity_id, effort_type, target)
+);
+CREATE INDEX IF NOT EXISTS idx_best_efforts_sport
+    ON activity_best_efforts(sport_type, effort_type, target, date);
+
+CREATE TABLE IF NOT EXISTS best_effort_scans (
+    activity_id TEXT PRIMARY KEY,
+    targets_key TEXT NOT NULL
+);
+
+CREATE TABLE activity_indicators_new (
+    activity_id TEXT NOT NULL,
+    indicator_type TEXT NOT NULL CHECK(indicator_type IN (
+        'section_pr', 'route_pr', 'section_trend', 'route_trend',
+        'effort_pr', 'effort_season_pr'
+    )),
+    target_id TEXT NOT NULL,
+    target_name TEXT NOT NULL DEFAULT '',
+    direction TEXT NOT NULL DEFAULT 'same',
+    lap_time REAL,
+    trend INTEGER NOT NULL DEFAULT 0,
+    computed_at INTEGER NOT NULL,
+    PRIMARY KEY (activity_id, indicator_type, target_id, direction)
+);
+INSERT INTO activity_indicators_new SELECT * FROM activity_indicators;
+DROP TABLE activity_indicators;
+ALTER TABLE activity_indicators_new RENAME TO activity_indicators;
+
+CREATE INDEX IF NOT EXISTS idx_activity_indicators_activity ON activity_indicators(activity_id);
+CREATE INDEX IF NOT EXISTS idx_activity_indicators_target ON activity_indicators(target_id);
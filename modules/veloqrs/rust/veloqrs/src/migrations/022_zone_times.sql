@@ -0,0 +1,16 @@
+-- Migration 022: Local time-in-zone per activity
+-- Seconds in each HR / power / pace zone (0-based) computed from the stored
+-- activity streams and the zones in the cached sport settings, preferred over
+-- the server's icu_zone_times columns where present. zones_computed marks
+-- activities the pass has seen; it is reset when streams are replaced and
+-- for every activity when the sport settings change.
+
+CREATE TABLE IF NOT EXISTS activity_zone_times (
+    activity_id TEXT NOT NULL,
+    zone_type TEXT NOT NULL CHECK(zone_type IN ('hr', 'power', 'pace')),
+    zone INTEGER NOT NULL,
+    secs REAL NOT NULL,
+    PRIMARY KEY (activity_id, zone_type, zone)
+);
+
+ALTER TABLE activity_metrics ADD COLUMN zones_computed INTEGER NOT NULL DEFAULT 0;
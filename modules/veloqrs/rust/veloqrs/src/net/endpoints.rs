@@ -38,7 +38,7 @@ pub async fn fetch_activities(
     } else {
         ACTIVITY_FIELDS.to_string()
     };
-    t.get_json(
+    t.get_json_fresh(
         &format!("/athlete/{}/activities", athlete_id),
         &[("oldest", oldest), ("newest", newest), ("fields", &fields)],
         lane,
@@ -46,6 +46,25 @@ pub async fn fetch_activities(
     .await
 }
 
+/// `GET /athlete/{id}/activities` with only the ids - what the server still
+/// lists in a window.
+pub async fn fetch_activity_ids(
+    t: &Transport,
+    athlete_id: &str,
+    oldest: &str,
+    newest: &str,
+    lane: Lane,
+) -> Result<Vec<String>, NetError> {
+    let acts: Vec<ActivityRecord> = t
+        .get_json_fresh(
+            &format!("/athlete/{}/activities", athlete_id),
+            &[("oldest", oldest), ("newest", newest), ("fields", "id")],
+            lane,
+        )
+        .await?;
+    Ok(acts.into_iter().map(|a| a.id).collect())
+}
+
 /// `GET /activity/{id}` - full activity detail.
 pub async fn fetch_activity(
     t: &Transport,
@@ -64,7 +83,7 @@ pub async fn fetch_oldest_activity_date(
     lane: Lane,
 ) -> Result<Option<String>, NetError> {
     let acts: Vec<ActivityRecord> = t
-        .get_json(
+        .get_json_fresh(
             &format!("/athlete/{}/activities", athlete_id),
             &[
                 ("oldest", "2000-01-01"),
@@ -12,6 +12,8 @@
 pub mod transport;
 pub use transport::{NetError, Transport};
 
+pub mod oauth;
+
 pub mod types;
 
 pub mod endpoints;
@@ -0,0 +1,188 @@
+//! OAuth access-token refresh.
+//!
+//! Holds the current access/refresh token pair and exchanges the refresh token
+//! at a configurable token endpoint (the `oauth-proxy` worker, which keeps the
+//! client secret server-side). The transport calls `refresh` after a 401 and
+//! replays the request with the new token.
+//!
+//! Refreshes are de-duplicated: every caller passes the token generation its
+//! failed request was sent with. The first caller to take the lock performs
+//! the exchange; the rest find the generation already advanced and reuse the
+//! new token instead of spending the (possibly single-use) refresh token again.
+
+use super::transport::NetError;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Token pair plus a counter bumped on every successful refresh.
+#[derive(Debug, Clone)]
+pub struct OAuthTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub generation: u64,
+}
+
+/// Token endpoint response. Providers that rotate refresh tokens return a new
+/// one; otherwise the current one stays valid.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+/// Shared refresher for one OAuth session.
+pub struct TokenRefresher {
+    client: Client,
+    endpoint: String,
+    tokens: tokio::sync::Mutex<OAuthTokens>,
+    /// Lock-free copy of `tokens` for header construction; updated together
+    /// with `tokens` while the async lock is held.
+    current: std::sync::Mutex<OAuthTokens>,
+}
+
+impl TokenRefresher {
+    pub fn new(
+        endpoint: impl Into<String>,
+        access_token: String,
+        refresh_token: String,
+    ) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("failed to build HTTP client: {}", e))?;
+        let tokens = OAuthTokens {
+            access_token,
+            refresh_token,
+            generation: 0,
+        };
+        Ok(Self {
+            client,
+            endpoint: endpoint.into(),
+            tokens: tokio::sync::Mutex::new(tokens.clone()),
+            current: std::sync::Mutex::new(tokens),
+        })
+    }
+
+    /// The token pair requests should currently be sent with.
+    pub fn current(&self) -> OAuthTokens {
+        self.current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Refresh the access token unless another caller already did since
+    /// `seen_generation`. A rejected refresh token (4xx) is `Unauthorized`;
+    /// network and server failures keep their own classification so an
+    /// offline device is not mistaken for an expired session.
+    pub async fn refresh(&self, seen_generation: u64) -> Result<OAuthTokens, NetError> {
+        let mut tokens = self.tokens.lock().await;
+        if tokens.generation != seen_generation {
+            return Ok(tokens.clone());
+        }
+
+        let resp = self
+            .client
+            .post(&self.endpoint)
+            .json(&serde_json::json!({
+                "grant_type": "refresh_token",
+                "refresh_token": tokens.refresh_token,
+            }))
+            .send()
+            .await
+            .map_err(|e| NetError::Transport(e.to_string()))?;
+        let status = resp.status();
+        if status.is_client_error() {
+            log::warn!(
+                "tracematch: [OAuth] Refresh rejected by token endpoint ({})",
+                status
+            );
+            return Err(NetError::Unauthorized);
+        }
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(NetError::Http {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        let fresh: TokenResponse = resp
+            .json()
+            .await
+            .map_err(|e| NetError::Decode(e.to_string()))?;
+
+        tokens.access_token = fresh.access_token;
+        if let Some(rotated) = fresh.refresh_token {
+            tokens.refresh_token = rotated;
+        }
+        tokens.generation += 1;
+        *self.current.lock().unwrap_or_else(|e| e.into_inner()) = tokens.clone();
+        log::info!(
+            "tracematch: [OAuth] Access token refreshed (generation {})",
+            tokens.generation
+        );
+        Ok(tokens.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    #[test]
+    fn refresh_rotates_tokens_and_bumps_generation() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/oauth/refresh")
+                .json_body(json!({"grant_type": "refresh_token", "refresh_token": "r0"}));
+            then.status(200)
+                .json_body(json!({"access_token": "a1", "refresh_token": "r1"}));
+        });
+        let r =
+            TokenRefresher::new(server.url("/oauth/refresh"), "a0".into(), "r0".into()).unwrap();
+        let got = crate::runtime::block_on(r.refresh(0)).unwrap();
+        mock.assert();
+        assert_eq!(got.access_token, "a1");
+        assert_eq!(got.refresh_token, "r1");
+        assert_eq!(got.generation, 1);
+        assert_eq!(r.current().access_token, "a1");
+
+        // A caller that saw generation 0 reuses the refreshed pair.
+        let again = crate::runtime::block_on(r.refresh(0)).unwrap();
+        assert_eq!(again.generation, 1);
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn rejected_refresh_is_unauthorized() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/oauth/refresh");
+            then.status(400)
+                .json_body(json!({"error": "invalid_grant"}));
+        });
+        let r =
+            TokenRefresher::new(server.url("/oauth/refresh"), "a0".into(), "r0".into()).unwrap();
+        let res = crate::runtime::block_on(r.refresh(0));
+        assert!(matches!(res, Err(NetError::Unauthorized)));
+        assert_eq!(r.current().access_token, "a0");
+    }
+
+    #[test]
+    fn server_error_is_not_unauthorized() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/oauth/refresh");
+            then.status(502);
+        });
+        let r =
+            TokenRefresher::new(server.url("/oauth/refresh"), "a0".into(), "r0".into()).unwrap();
+        let res = crate::runtime::block_on(r.refresh(0));
+        assert!(matches!(res, Err(NetError::Http { status: 502, .. })));
+    }
+}
@@ -0,0 +1,196 @@
+//! Conditional-GET response cache.
+//!
+//! Stores the last successful JSON body per request URL together with its
+//! `ETag` / `Last-Modified` validators. The transport revalidates with
+//! `If-None-Match` / `If-Modified-Since` and reuses the body on `304`, so
+//! rarely-changing payloads (athlete, sport settings, curves) cost no body
+//! download. Read-only detail endpoints are also answered from here when the
+//! network is unreachable, so cold starts still render offline.
+//!
+//! Lives in its own SQLite file next to the engine database: the cache is
+//! disposable, needs no migrations, and never contends with the engine lock.
+
+use once_cell::sync::Lazy;
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult, params};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// File name of the cache database, created beside the engine database.
+const CACHE_FILE_NAME: &str = "veloq_http_cache.db";
+
+/// Bodies larger than this are not cached (activity lists over long windows,
+/// full streams). Those are persisted by the engine already.
+const MAX_CACHED_BODY: usize = 512 * 1024;
+
+/// Entries kept after each insert; least recently used rows are evicted.
+const MAX_ENTRIES: i64 = 512;
+
+/// A cached response body with the validators it was served with.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// SQLite-backed store of cached responses, keyed by full request URL.
+pub struct ResponseCache {
+    db: Mutex<Connection>,
+}
+
+/// Process-wide cache picked up by `Transport::new`. Installed once the app
+/// supplies its database location (`VeloqEngine::create`).
+static SHARED: Lazy<RwLock<Option<Arc<ResponseCache>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Open the shared cache beside `db_path`. Failure only disables caching.
+pub fn install_shared(db_path: &str) {
+    let path = std::path::Path::new(db_path)
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .join(CACHE_FILE_NAME);
+    match ResponseCache::open(&path.to_string_lossy()) {
+        Ok(cache) => {
+            *SHARED.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(cache));
+        }
+        Err(e) => log::warn!(
+            "tracematch: [HttpCache] Failed to open {:?}, caching disabled: {}",
+            path,
+            e
+        ),
+    }
+}
+
+/// The shared cache, if one has been installed.
+pub fn shared() -> Option<Arc<ResponseCache>> {
+    SHARED.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+impl ResponseCache {
+    /// Open (or create) a cache database at `path`.
+    pub fn open(path: &str) -> SqlResult<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// A throwaway in-memory cache (tests).
+    pub fn in_memory() -> SqlResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> SqlResult<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS http_cache (
+                url TEXT PRIMARY KEY,
+                etag TEXT,
+                last_modified TEXT,
+                body BLOB NOT NULL,
+                stored_at INTEGER NOT NULL
+            )",
+        )?;
+        Ok(Self {
+            db: Mutex::new(conn),
+        })
+    }
+
+    pub(crate) fn get(&self, url: &str) -> Option<CachedResponse> {
+        let db = self.db.lock().unwrap_or_else(|e| e.into_inner());
+        db.query_row(
+            "SELECT etag, last_modified, body FROM http_cache WHERE url = ?",
+            params![url],
+            |row| {
+                Ok(CachedResponse {
+                    etag: row.get(0)?,
+                    last_modified: row.get(1)?,
+                    body: row.get(2)?,
+                })
+            },
+        )
+        .optional()
+        .unwrap_or_else(|e| {
+            log::warn!("tracematch: [HttpCache] Read failed for {}: {}", url, e);
+            None
+        })
+    }
+
+    /// Store a fresh body. Oversized bodies are skipped and any stale entry
+    /// for the URL dropped so it is never revalidated against old validators.
+    pub(crate) fn put(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+        body: &[u8],
+    ) {
+        let db = self.db.lock().unwrap_or_else(|e| e.into_inner());
+        let res = if body.len() > MAX_CACHED_BODY {
+            db.execute("DELETE FROM http_cache WHERE url = ?", params![url])
+                .map(|_| ())
+        } else {
+            db.execute(
+                "INSERT OR REPLACE INTO http_cache (url, etag, last_modified, body, stored_at)
+                 VALUES (?, ?, ?, ?, strftime('%s', 'now'))",
+                params![url, etag, last_modified, body],
+            )
+            .and_then(|_| {
+                db.execute(
+                    "DELETE FROM http_cache WHERE url NOT IN (
+                        SELECT url FROM http_cache ORDER BY stored_at DESC LIMIT ?
+                     )",
+                    params![MAX_ENTRIES],
+                )
+            })
+            .map(|_| ())
+        };
+        if let Err(e) = res {
+            log::warn!("tracematch: [HttpCache] Write failed for {}: {}", url, e);
+        }
+    }
+
+    /// Mark an entry as just revalidated (keeps it out of LRU eviction).
+    pub(crate) fn touch(&self, url: &str) {
+        let db = self.db.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = db.execute(
+            "UPDATE http_cache SET stored_at = strftime('%s', 'now') WHERE url = ?",
+            params![url],
+        ) {
+            log::warn!("tracematch: [HttpCache] Touch failed for {}: {}", url, e);
+        }
+    }
+
+    /// Drop every entry (logout / account switch).
+    pub fn clear(&self) {
+        let db = self.db.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = db.execute("DELETE FROM http_cache", []) {
+            log::warn!("tracematch: [HttpCache] Clear failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put_get_and_clear() {
+        let cache = ResponseCache::in_memory().unwrap();
+        assert!(cache.get("https://x/a").is_none());
+        cache.put("https://x/a", Some("\"v1\""), None, b"{}");
+        let hit = cache.get("https://x/a").unwrap();
+        assert_eq!(hit.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(hit.last_modified, None);
+        assert_eq!(hit.body, b"{}");
+        cache.clear();
+        assert!(cache.get("https://x/a").is_none());
+    }
+
+    #[test]
+    fn oversized_body_evicts_entry() {
+        let cache = ResponseCache::in_memory().unwrap();
+        cache.put("https://x/a", Some("\"v1\""), None, b"[]");
+        cache.put(
+            "https://x/a",
+            Some("\"v2\""),
+            None,
+            &vec![b' '; MAX_CACHED_BODY + 1],
+        );
+        assert!(cache.get("https://x/a").is_none());
+    }
+}
@@ -0,0 +1,791 @@
+//! The single pooled transport for intervals.icu.
+//!
+//! Every request passes through the shared `Governor` (dispatch pace + policy
+//! hook) and a unified retry loop that honours `Retry-After`. The auth header is
+//! built once from the credential the transport holds - callers never pass an
+//! `auth_header` per request. JSON GETs are revalidated against the response
+//! cache (`cache`) when one is attached. With an OAuth refresher attached, a 401
+//! triggers one token refresh and a transparent replay of the request.
+//!
+//! For demo mode and deterministic tests the transport can instead serve
+//! recorded responses (`replay::Fixtures`), or capture live ones
+//! (`replay::Recorder`).
+
+use super::oauth::TokenRefresher;
+use crate::governor::{self, AuthMethod, Governor, Lane, RateBudget};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, Url};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod cache;
+pub use cache::ResponseCache;
+
+pub mod replay;
+pub use replay::{Fixtures, Recorder};
+
+/// Retries for transient failures (429 / 5xx / transport). Matches the prior
+/// axios client (`maxRetries = 3`).
+const MAX_RETRIES: u32 = 3;
+
+/// Read-only detail endpoints that may be answered from the response cache
+/// when the network is down, as `/`-separated patterns where `*` matches one
+/// path segment. Lists and streams are left out: an old copy of those would
+/// look like deletions or finished downloads to the sync job.
+const OFFLINE_FALLBACK_PATHS: &[&str] = &[
+    "/athlete/*",
+    "/athlete/*/sport-settings",
+    "/activity/*",
+    "/activity/*/intervals",
+];
+
+/// Whether `path` matches one of `OFFLINE_FALLBACK_PATHS`.
+fn serves_offline(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').collect();
+    OFFLINE_FALLBACK_PATHS.iter().any(|pattern| {
+        let parts: Vec<&str> = pattern.split('/').collect();
+        parts.len() == segments.len()
+            && parts
+                .iter()
+                .zip(&segments)
+                .all(|(p, s)| *p == *s || (*p == "*" && !s.is_empty()))
+    })
+}
+
+/// A response body, flagged when it is a cached copy served because the
+/// network was unreachable.
+struct Fetched {
+    body: Vec<u8>,
+    stale: bool,
+}
+
+/// A failed request, classified so the service can react (e.g. `Unauthorized`
+/// drives the `authExpired` status).
+#[derive(Debug)]
+pub enum NetError {
+    /// 401 - credentials rejected; the service emits `authExpired`.
+    Unauthorized,
+    /// 429 after exhausting retries.
+    RateLimited,
+    /// Any other non-success HTTP status.
+    Http { status: u16, body: String },
+    /// Network / timeout failure after retries.
+    Transport(String),
+    /// Body did not deserialize into the expected shape.
+    Decode(String),
+    /// The network was unreachable and only a cached copy was available,
+    /// which the caller refused (see `Transport::get_json_fresh`).
+    Stale(String),
+}
+
+impl std::fmt::Display for NetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetError::Unauthorized => write!(f, "unauthorized (401)"),
+            NetError::RateLimited => write!(f, "rate limited (429) after retries"),
+            NetError::Http { status, body } => write!(f, "HTTP {}: {}", status, body),
+            NetError::Transport(e) => write!(f, "transport error: {}", e),
+            NetError::Decode(e) => write!(f, "decode error: {}", e),
+            NetError::Stale(path) => write!(f, "offline, only a cached copy of {}", path),
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+/// Pooled HTTP transport bound to one base URL and one credential.
+pub struct Transport {
+    client: Client,
+    base_url: String,
+    auth_header: String,
+    governor: Arc<Governor>,
+    cache: Option<Arc<ResponseCache>>,
+    refresher: Option<Arc<TokenRefresher>>,
+    replay: Option<Arc<Fixtures>>,
+    recorder: Option<Arc<Recorder>>,
+}
+
+impl Transport {
+    /// Build a transport on the shared process governor and response cache.
+    pub fn new(base_url: impl Into<String>, auth: AuthMethod<'_>) -> Result<Self, String> {
+        let transport = Self::with_governor(base_url, auth, governor::GOVERNOR.clone())?;
+        Ok(match cache::shared() {
+            Some(c) => transport.with_cache(c),
+            None => transport,
+        })
+    }
+
+    /// Build a transport on a specific governor (used by tests for an isolated,
+    /// fast-paced limiter).
+    pub fn with_governor(
+        base_url: impl Into<String>,
+        auth: AuthMethod<'_>,
+        governor: Arc<Governor>,
+    ) -> Result<Self, String> {
+        let client = Client::builder()
+            .pool_max_idle_per_host(16)
+            .pool_idle_timeout(Duration::from_secs(60))
+            .tcp_keepalive(Duration::from_secs(30))
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("failed to build HTTP client: {}", e))?;
+        Ok(Self {
+            client,
+            base_url: base_url.into(),
+            auth_header: governor::format_auth_header(auth),
+            governor,
+            cache: None,
+            refresher: None,
+            replay: None,
+            recorder: None,
+        })
+    }
+
+    /// Attach a response cache for conditional JSON GETs.
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Attach an OAuth refresher. Its current access token replaces the
+    /// credential passed at construction.
+    pub fn with_refresher(mut self, refresher: Arc<TokenRefresher>) -> Self {
+        self.refresher = Some(refresher);
+        self
+    }
+
+    /// Serve every request from recorded fixtures instead of the network.
+    /// Unrecorded paths fail with a 404.
+    pub fn with_replay(mut self, fixtures: Arc<Fixtures>) -> Self {
+        self.replay = Some(fixtures);
+        self
+    }
+
+    /// Whether requests are served from recorded fixtures (demo mode).
+    pub fn is_replay(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Record every successful live response body into `recorder`.
+    pub fn with_recorder(mut self, recorder: Arc<Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Authorization header for the next dispatch, plus the token generation
+    /// it was built from when an OAuth refresher is attached.
+    fn current_auth(&self) -> (String, Option<u64>) {
+        match &self.refresher {
+            Some(r) => {
+                let tokens = r.current();
+                (
+                    governor::format_auth_header(AuthMethod::Bearer(&tokens.access_token)),
+                    Some(tokens.generation),
+                )
+            }
+            None => (self.auth_header.clone(), None),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// GET a path and deserialize the JSON body into `T`. Revalidated against
+    /// the response cache, which also answers for `OFFLINE_FALLBACK_PATHS`
+    /// when the network is down.
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        lane: Lane,
+    ) -> Result<T, NetError> {
+        let fetched = self.fetch(path, query, lane, true).await?;
+        serde_json::from_slice::<T>(&fetched.body).map_err(|e| NetError::Decode(e.to_string()))
+    }
+
+    /// Like `get_json`, but fails with `NetError::Stale` instead of returning
+    /// a cached copy when the network is down. For responses the caller acts
+    /// on destructively (reconcile, watermarks, cursors).
+    pub async fn get_json_fresh<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        lane: Lane,
+    ) -> Result<T, NetError> {
+        let fetched = self.fetch(path, query, lane, true).await?;
+        if fetched.stale {
+            return Err(NetError::Stale(path.to_string()));
+        }
+        serde_json::from_slice::<T>(&fetched.body).map_err(|e| NetError::Decode(e.to_string()))
+    }
+
+    /// GET a path and return the raw response bytes (e.g. a FIT file). Never
+    /// cached: binary payloads are large and persisted by their callers.
+    pub async fn get_bytes(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        lane: Lane,
+    ) -> Result<Vec<u8>, NetError> {
+        self.fetch(path, query, lane, false).await.map(|f| f.body)
+    }
+
+    async fn fetch(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        lane: Lane,
+        cacheable: bool,
+    ) -> Result<Fetched, NetError> {
+        if let Some(fixtures) = &self.replay {
+            return fixtures
+                .lookup(path, query)
+                .map(|body| Fetched {
+                    body: body.to_vec(),
+                    stale: false,
+                })
+                .ok_or_else(|| NetError::Http {
+                    status: 404,
+                    body: format!("no fixture recorded for {}", path),
+                });
+        }
+        let fetched = self.fetch_live(path, query, lane, cacheable).await?;
+        if let (Some(recorder), false) = (&self.recorder, fetched.stale) {
+            recorder
+                .record(path, query, &fetched.body)
+                .map_err(NetError::Transport)?;
+        }
+        Ok(fetched)
+    }
+
+    async fn fetch_live(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        lane: Lane,
+        cacheable: bool,
+    ) -> Result<Fetched, NetError> {
+        // The full URL (query included) is also the cache key.
+        let mut url =
+            Url::parse(&self.url(path)).map_err(|e| NetError::Transport(e.to_string()))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        let cache = self.cache.as_ref().filter(|_| cacheable);
+        let cached = cache.and_then(|c| c.get(url.as_str()));
+        let mut attempt = 0u32;
+        let mut refreshed = false;
+        loop {
+            // Single shared choke point: pace every dispatch.
+            self.governor.acquire(lane).await;
+
+            let (auth_header, generation) = self.current_auth();
+            let mut req = self
+                .client
+                .get(url.clone())
+                .header("Authorization", auth_header);
+            if let Some(hit) = &cached {
+                if let Some(etag) = &hit.etag {
+                    req = req.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(modified) = &hit.last_modified {
+                    req = req.header(IF_MODIFIED_SINCE, modified);
+                }
+            }
+            let send = req.send().await;
+
+            match send {
+                Ok(resp) => {
+                    let status = resp.status();
+                    let budget = parse_budget(resp.headers());
+                    self.governor.observe(&budget);
+
+                    if status == reqwest::StatusCode::NOT_MODIFIED {
+                        if let Some(hit) = cached {
+                            if let Some(c) = cache {
+                                c.touch(url.as_str());
+                            }
+                            return Ok(Fetched {
+                                body: hit.body,
+                                stale: false,
+                            });
+                        }
+                    }
+                    if status.is_success() {
+                        let header = |name: reqwest::header::HeaderName| {
+                            resp.headers()
+                                .get(name)
+                                .and_then(|v| v.to_str().ok())
+                                .map(str::to_string)
+                        };
+                        let etag = header(ETAG);
+                        let last_modified = header(LAST_MODIFIED);
+                        let body = resp
+                            .bytes()
+                            .await
+                            .map(|b| b.to_vec())
+                            .map_err(|e| NetError::Transport(e.to_string()))?;
+                        if let Some(c) = cache {
+                            c.put(
+                                url.as_str(),
+                                etag.as_deref(),
+                                last_modified.as_deref(),
+                                &body,
+                            );
+                        }
+                        return Ok(Fetched { body, stale: false });
+                    }
+                    if status == reqwest::StatusCode::UNAUTHORIZED {
+                        // One refresh per request: a second 401 with a fresh
+                        // token means the session itself is gone.
+                        if let (Some(r), Some(seen), false) =
+                            (&self.refresher, generation, refreshed)
+                        {
+                            r.refresh(seen).await?;
+                            refreshed = true;
+                            continue;
+                        }
+                        return Err(NetError::Unauthorized);
+                    }
+                    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                        attempt += 1;
+                        if attempt > MAX_RETRIES {
+                            return Err(NetError::RateLimited);
+                        }
+                        let wait = governor::decide_backoff(budget.retry_after_secs, attempt, true);
+                        tokio::time::sleep(wait).await;
+                        continue;
+                    }
+                    if status.is_server_error() && attempt < MAX_RETRIES {
+                        attempt += 1;
+                        let wait = governor::decide_backoff(None, attempt, false);
+                        tokio::time::sleep(wait).await;
+                        continue;
+                    }
+                    let code = status.as_u16();
+                    let body = resp.text().await.unwrap_or_default();
+                    return Err(NetError::Http { status: code, body });
+                }
+                Err(e) => {
+                    attempt += 1;
+                    if attempt > MAX_RETRIES {
+                        // Offline: for read-only detail screens the last good
+                        // body beats an error screen.
+                        if let Some(hit) = cached.filter(|_| serves_offline(path)) {
+                            log::info!("tracematch: [Transport] Offline, serving cached {}", path);
+                            return Ok(Fetched {
+                                body: hit.body,
+                                stale: true,
+                            });
+                        }
+                        return Err(NetError::Transport(e.to_string()));
+                    }
+                    let wait = governor::decide_backoff(None, attempt, false);
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+    }
+}
+
+/// Extract intervals.icu rate headers (it sends `Retry-After` on 429; the
+/// `X-RateLimit-*` headers are parsed if/when the server adds them).
+fn parse_budget(headers: &reqwest::header::HeaderMap) -> RateBudget {
+    let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    governor::parse_rate_headers(
+        get("x-ratelimit-limit"),
+        get("x-ratelimit-remaining"),
+        get("retry-after"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governor::NoopPolicy;
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    fn fast_transport(base: String, auth: AuthMethod<'_>) -> Transport {
+        // A throwaway 1000 req/s governor keeps request-path tests fast and
+        // isolated from the shared 8 req/s process governor.
+        let gov = Arc::new(Governor::new(1000, Box::new(NoopPolicy)));
+        Transport::with_governor(base, auth, gov).unwrap()
+    }
+
+    #[test]
+    fn sends_basic_auth_and_parses_json() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1").header(
+                "authorization",
+                &governor::format_auth_header(AuthMethod::ApiKey("secret")),
+            );
+            then.status(200).json_body(json!({"id": "i1", "name": "x"}));
+        });
+        let t = fast_transport(server.base_url(), AuthMethod::ApiKey("secret"));
+        let got: serde_json::Value =
+            crate::runtime::block_on(t.get_json("/athlete/i1", &[], Lane::Interactive)).unwrap();
+        mock.assert();
+        assert_eq!(got["id"], "i1");
+    }
+
+    #[test]
+    fn sends_bearer_auth() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/me")
+                .header("authorization", "Bearer tok123");
+            then.status(200).json_body(json!({"id": "i1"}));
+        });
+        let t = fast_transport(server.base_url(), AuthMethod::Bearer("tok123"));
+        let _: serde_json::Value =
+            crate::runtime::block_on(t.get_json("/athlete/me", &[], Lane::Interactive)).unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn forwards_query_params() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("oldest", "2026-01-01")
+                .query_param("newest", "2026-06-26");
+            then.status(200).json_body(json!([]));
+        });
+        let t = fast_transport(server.base_url(), AuthMethod::ApiKey("k"));
+        let _: serde_json::Value = crate::runtime::block_on(t.get_json(
+            "/athlete/i1/activities",
+            &[("oldest", "2026-01-01"), ("newest", "2026-06-26")],
+            Lane::Backfill,
+        ))
+        .unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn unauthorized_maps_to_error() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/x");
+            then.status(401);
+        });
+        let t = fast_transport(server.base_url(), AuthMethod::ApiKey("k"));
+        let res: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/x", &[], Lane::Interactive));
+        assert!(matches!(res, Err(NetError::Unauthorized)));
+        // 401 is terminal: credentials won't improve on retry, so dispatch once.
+        assert_eq!(mock.hits(), 1);
+    }
+
+    #[test]
+    fn retries_429_then_gives_up_and_honours_retry_after() {
+        let server = MockServer::start();
+        // Retry-After: 0 keeps the test fast while still exercising the header path.
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/x");
+            then.status(429).header("retry-after", "0");
+        });
+        let t = fast_transport(server.base_url(), AuthMethod::ApiKey("k"));
+        let res: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/x", &[], Lane::Backfill));
+        assert!(matches!(res, Err(NetError::RateLimited)));
+        // 1 initial dispatch + MAX_RETRIES retries.
+        assert_eq!(mock.hits(), (MAX_RETRIES + 1) as usize);
+    }
+
+    #[test]
+    fn retries_5xx_then_returns_http_error() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/y");
+            then.status(503);
+        });
+        let t = fast_transport(server.base_url(), AuthMethod::ApiKey("k"));
+        let res: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/y", &[], Lane::Interactive));
+        // A 5xx is retried with exponential backoff, then surfaced as Http once
+        // the retry budget is exhausted (httpmock 0.7 can't sequence a transient
+        // 503-then-200, so the give-up path is what we can assert deterministically).
+        assert!(matches!(res, Err(NetError::Http { status: 503, .. })));
+        assert_eq!(mock.hits(), (MAX_RETRIES + 1) as usize);
+    }
+
+    #[test]
+    fn decode_error_on_bad_shape() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/z");
+            then.status(200).body("not json");
+        });
+        let t = fast_transport(server.base_url(), AuthMethod::ApiKey("k"));
+        let res: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/z", &[], Lane::Interactive));
+        assert!(matches!(res, Err(NetError::Decode(_))));
+    }
+
+    fn oauth_transport(api: &MockServer, token: &MockServer) -> Transport {
+        let refresher =
+            TokenRefresher::new(token.url("/oauth/refresh"), "old".into(), "r0".into()).unwrap();
+        fast_transport(api.base_url(), AuthMethod::Bearer("old"))
+            .with_refresher(Arc::new(refresher))
+    }
+
+    fn mock_api_accepting_new_token(
+        server: &MockServer,
+    ) -> (httpmock::Mock<'_>, httpmock::Mock<'_>) {
+        // Registered first so requests carrying the new token match it.
+        let ok = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1")
+                .header("authorization", "Bearer new");
+            then.status(200).json_body(json!({"id": "i1"}));
+        });
+        let rejected = server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1");
+            then.status(401);
+        });
+        (ok, rejected)
+    }
+
+    #[test]
+    fn refreshes_token_on_401_and_replays() {
+        let api = MockServer::start();
+        let token = MockServer::start();
+        let (ok, rejected) = mock_api_accepting_new_token(&api);
+        let refresh = token.mock(|when, then| {
+            when.method(POST).path("/oauth/refresh");
+            then.status(200).json_body(json!({"access_token": "new"}));
+        });
+        let t = oauth_transport(&api, &token);
+        let got: serde_json::Value =
+            crate::runtime::block_on(t.get_json("/athlete/i1", &[], Lane::Interactive)).unwrap();
+        assert_eq!(got["id"], "i1");
+        rejected.assert_hits(1);
+        refresh.assert_hits(1);
+        ok.assert_hits(1);
+    }
+
+    #[test]
+    fn failed_refresh_surfaces_unauthorized() {
+        let api = MockServer::start();
+        let token = MockServer::start();
+        let (ok, _rejected) = mock_api_accepting_new_token(&api);
+        token.mock(|when, then| {
+            when.method(POST).path("/oauth/refresh");
+            then.status(401);
+        });
+        let t = oauth_transport(&api, &token);
+        let res: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/athlete/i1", &[], Lane::Interactive));
+        assert!(matches!(res, Err(NetError::Unauthorized)));
+        ok.assert_hits(0);
+    }
+
+    #[test]
+    fn concurrent_401s_share_one_refresh() {
+        let api = MockServer::start();
+        let token = MockServer::start();
+        let (ok, _rejected) = mock_api_accepting_new_token(&api);
+        let refresh = token.mock(|when, then| {
+            when.method(POST).path("/oauth/refresh");
+            // Slow enough that every request hits its 401 before it lands.
+            then.status(200)
+                .delay(Duration::from_millis(200))
+                .json_body(json!({"access_token": "new", "refresh_token": "r1"}));
+        });
+        let t = oauth_transport(&api, &token);
+        let results = crate::runtime::block_on(futures::future::join_all(
+            (0..4).map(|_| t.get_json::<serde_json::Value>("/athlete/i1", &[], Lane::Interactive)),
+        ));
+        assert!(results.iter().all(|r| r.is_ok()));
+        refresh.assert_hits(1);
+        ok.assert_hits(4);
+    }
+
+    #[test]
+    fn replay_serves_fixtures_and_404s_the_rest() {
+        let dir = tempfile::TempDir::new().unwrap();
+        Recorder::new(dir.path().to_str().unwrap())
+            .unwrap()
+            .record("/athlete/i1", &[], br#"{"id": "i1"}"#)
+            .unwrap();
+        let fixtures = Fixtures::open(dir.path().to_str().unwrap()).unwrap();
+        // Nothing listens on the discard port: a live dispatch would fail.
+        let t = fast_transport("http://127.0.0.1:9".into(), AuthMethod::ApiKey("k"))
+            .with_replay(Arc::new(fixtures));
+        let got: serde_json::Value =
+            crate::runtime::block_on(t.get_json("/athlete/i1", &[], Lane::Interactive)).unwrap();
+        assert_eq!(got["id"], "i1");
+        let miss: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/athlete/i2", &[], Lane::Interactive));
+        assert!(matches!(miss, Err(NetError::Http { status: 404, .. })));
+    }
+
+    fn cached_transport(base: String) -> (Transport, Arc<ResponseCache>) {
+        let cache = Arc::new(ResponseCache::in_memory().unwrap());
+        let t = fast_transport(base, AuthMethod::ApiKey("k")).with_cache(cache.clone());
+        (t, cache)
+    }
+
+    #[test]
+    fn revalidates_with_etag_and_serves_304_from_cache() {
+        let server = MockServer::start();
+        // Registered first so the conditional request matches it.
+        let not_modified = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1")
+                .header("if-none-match", "\"v1\"");
+            then.status(304);
+        });
+        let fresh = server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1");
+            then.status(200)
+                .header("etag", "\"v1\"")
+                .json_body(json!({"id": "i1"}));
+        });
+        let (t, _cache) = cached_transport(server.base_url());
+        for _ in 0..2 {
+            let got: serde_json::Value =
+                crate::runtime::block_on(t.get_json("/athlete/i1", &[], Lane::Interactive))
+                    .unwrap();
+            assert_eq!(got["id"], "i1");
+        }
+        fresh.assert_hits(1);
+        not_modified.assert_hits(1);
+    }
+
+    #[test]
+    fn revalidates_with_last_modified() {
+        let server = MockServer::start();
+        let not_modified = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/sport-settings")
+                .header("if-modified-since", "Wed, 01 Jul 2026 10:00:00 GMT");
+            then.status(304);
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/sport-settings");
+            then.status(200)
+                .header("last-modified", "Wed, 01 Jul 2026 10:00:00 GMT")
+                .json_body(json!([]));
+        });
+        let (t, _cache) = cached_transport(server.base_url());
+        for _ in 0..2 {
+            let _: serde_json::Value = crate::runtime::block_on(t.get_json(
+                "/athlete/i1/sport-settings",
+                &[],
+                Lane::Interactive,
+            ))
+            .unwrap();
+        }
+        not_modified.assert_hits(1);
+    }
+
+    #[test]
+    fn serves_cached_body_when_offline() {
+        // Nothing listens on the discard port, so every dispatch fails to connect.
+        let base = "http://127.0.0.1:9".to_string();
+        let (t, cache) = cached_transport(base.clone());
+        cache.put(
+            &format!("{}/athlete/i1?fields=id", base),
+            None,
+            None,
+            br#"{"id": "i1"}"#,
+        );
+        let got: serde_json::Value = crate::runtime::block_on(t.get_json(
+            "/athlete/i1",
+            &[("fields", "id")],
+            Lane::Interactive,
+        ))
+        .unwrap();
+        assert_eq!(got["id"], "i1");
+
+        let miss: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/athlete/i2", &[], Lane::Interactive));
+        assert!(matches!(miss, Err(NetError::Transport(_))));
+
+        // Callers that act on the body destructively refuse the cached copy.
+        let fresh: Result<serde_json::Value, _> = crate::runtime::block_on(t.get_json_fresh(
+            "/athlete/i1",
+            &[("fields", "id")],
+            Lane::Interactive,
+        ));
+        assert!(matches!(fresh, Err(NetError::Stale(_))));
+    }
+
+    #[test]
+    fn lists_are_never_served_stale() {
+        let base = "http://127.0.0.1:9".to_string();
+        let (t, cache) = cached_transport(base.clone());
+        cache.put(
+            &format!("{}/athlete/i1/activities", base),
+            None,
+            None,
+            b"[]",
+        );
+        let got: Result<serde_json::Value, _> =
+            crate::runtime::block_on(t.get_json("/athlete/i1/activities", &[], Lane::Interactive));
+        assert!(matches!(got, Err(NetError::Transport(_))));
+    }
+
+    #[test]
+    fn offline_allowlist_matches_detail_paths_only() {
+        assert!(serves_offline("/athlete/i1"));
+        assert!(serves_offline("/athlete/i1/sport-settings"));
+        assert!(serves_offline("/activity/a1"));
+        assert!(serves_offline("/activity/a1/intervals"));
+        assert!(!serves_offline("/athlete/i1/activities"));
+        assert!(!serves_offline("/athlete/i1/wellness"));
+        assert!(!serves_offline("/activity/a1/streams.json"));
+        assert!(!serves_offline("/athlete/"));
+    }
+
+    #[test]
+    fn raw_bytes_bypass_the_cache() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/activity/a1/file");
+            then.status(200).header("etag", "\"f1\"").body("FIT");
+        });
+        let (t, cache) = cached_transport(server.base_url());
+        let body =
+            crate::runtime::block_on(t.get_bytes("/activity/a1/file", &[], Lane::Interactive))
+                .unwrap();
+        assert_eq!(body, b"FIT");
+        assert!(
+            cache
+                .get(&format!("{}/activity/a1/file", server.base_url()))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn each_dispatch_passes_through_the_governor() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/p");
+            then.status(200).json_body(json!({"ok": true}));
+        });
+        // A deliberately slow 4 req/s governor (250ms/slot): the second request
+        // can only fire after waiting a slot, which proves the transport acquires
+        // the shared governor before dispatch rather than sending immediately.
+        let gov = Arc::new(Governor::new(4, Box::new(NoopPolicy)));
+        let t = Transport::with_governor(server.base_url(), AuthMethod::ApiKey("k"), gov).unwrap();
+        let start = std::time::Instant::now();
+        let _: serde_json::Value =
+            crate::runtime::block_on(t.get_json("/p", &[], Lane::Interactive)).unwrap();
+        let _: serde_json::Value =
+            crate::runtime::block_on(t.get_json("/p", &[], Lane::Interactive)).unwrap();
+        assert!(
+            start.elapsed() >= Duration::from_millis(240),
+            "second dispatch was not paced by the governor: {:?}",
+            start.elapsed()
+        );
+        assert_eq!(mock.hits(), 2);
+    }
+}
@@ -0,0 +1,305 @@
+//! Record/replay backend for the transport.
+//!
+//! `Recorder` captures response bodies from live traffic into a fixture
+//! directory; `Fixtures` serves them back without touching the network. Only
+//! the request path, query and response body are written - never request
+//! headers - so recordings carry no credentials. A fixture set can be read
+//! from the directory itself or from a ZIP archive of it (the demo athlete
+//! bundled with the app).
+//!
+//! Each request is keyed by its path (leading `/` dropped) plus the query
+//! with keys sorted: `athlete/i1/activities?newest=2026-06-26&oldest=2026-01-01`.
+//! Keys with a long field selection run past the 255-byte file name limit,
+//! so a response is stored as two files named by a hash of the key: the body
+//! in `<hash>` and the key itself in `<hash>.key`. Replay matches the exact
+//! key first and otherwise falls back to any recording of the same path, so
+//! date-windowed requests (`oldest` / `newest` move with the clock) still
+//! resolve in demo mode.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Canonical request key: path without the leading slash, then `?` and the
+/// query pairs sorted by key (stable for callers that order params freely).
+fn request_key(path: &str, query: &[(&str, &str)]) -> String {
+    let path = path.trim_start_matches('/');
+    if query.is_empty() {
+        return path.to_string();
+    }
+    let mut pairs: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    pairs.sort();
+    format!("{}?{}", path, pairs.join("&"))
+}
+
+/// Path portion of a key (everything before `?`).
+fn key_path(key: &str) -> &str {
+    key.split_once('?').map_or(key, |(p, _)| p)
+}
+
+/// Suffix of the sidecar file holding a recording's full key.
+const KEY_SUFFIX: &str = ".key";
+
+/// Body file name of a key: its 64-bit FNV-1a hash in hex. Spelled out
+/// rather than `DefaultHasher` so names stay valid across Rust releases.
+fn file_name(key: &str) -> String {
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// A loaded fixture set, held in memory.
+#[derive(Debug, Default)]
+pub struct Fixtures {
+    exact: HashMap<String, Vec<u8>>,
+    /// Path-only fallback. Built in sorted key order so the chosen recording
+    /// is deterministic regardless of directory listing order.
+    by_path: HashMap<String, String>,
+}
+
+impl Fixtures {
+    /// Load from a fixture directory, or from a ZIP archive of one.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let p = Path::new(path);
+        if p.is_dir() {
+            Self::from_dir(p)
+        } else {
+            Self::from_archive(p)
+        }
+    }
+
+    /// Load every fixture file in `dir` (not recursive).
+    pub fn from_dir(dir: &Path) -> Result<Self, String> {
+        let entries =
+            std::fs::read_dir(dir).map_err(|e| format!("Failed to read fixtures: {}", e))?;
+        let mut files = HashMap::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read fixtures: {}", e))?;
+            if !entry.path().is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let body = std::fs::read(entry.path())
+                .map_err(|e| format!("Failed to read fixture {}: {}", name, e))?;
+            files.insert(name, body);
+        }
+        Ok(Self::from_files(files))
+    }
+
+    /// Load every file entry of a ZIP archive. Directory prefixes inside the
+    /// archive are ignored, so zipping the fixture directory itself works.
+    pub fn from_archive(path: &Path) -> Result<Self, String> {
+        let file =
+            std::fs::File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| format!("Invalid fixture archive: {}", e))?;
+        let mut files = HashMap::new();
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| format!("Invalid fixture archive: {}", e))?;
+            if !entry.is_file() {
+                continue;
+            }
+            let name = entry
+                .name()
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let mut body = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut body)
+                .map_err(|e| format!("Failed to read fixture {}: {}", name, e))?;
+            files.insert(name, body);
+        }
+        Ok(Self::from_files(files))
+    }
+
+    /// Pair every `<hash>.key` file with its `<hash>` body. Keys without a
+    /// body (an interrupted recording) and stray files are skipped.
+    fn from_files(mut files: HashMap<String, Vec<u8>>) -> Self {
+        let keys: Vec<(String, String)> = files
+            .iter()
+            .filter_map(|(name, key)| {
+                let stem = name.strip_suffix(KEY_SUFFIX)?;
+                let key = String::from_utf8(key.clone()).ok()?;
+                Some((stem.to_string(), key))
+            })
+            .collect();
+        let mut fixtures = Self::default();
+        for (stem, key) in keys {
+            if let Some(body) = files.remove(&stem) {
+                fixtures.exact.insert(key, body);
+            }
+        }
+        fixtures.index();
+        fixtures
+    }
+
+    fn index(&mut self) {
+        let mut keys: Vec<&String> = self.exact.keys().collect();
+        keys.sort();
+        self.by_path = keys
+            .into_iter()
+            .map(|k| (key_path(k).to_string(), k.clone()))
+            .collect();
+    }
+
+    /// Number of recorded responses.
+    pub fn len(&self) -> usize {
+        self.exact.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exact.is_empty()
+    }
+
+    /// Recorded body for a request: exact path + query, else the same path.
+    pub(crate) fn lookup(&self, path: &str, query: &[(&str, &str)]) -> Option<&[u8]> {
+        let key = request_key(path, query);
+        self.exact
+            .get(&key)
+            .or_else(|| {
+                self.by_path
+                    .get(key_path(&key))
+                    .and_then(|k| self.exact.get(k))
+            })
+            .map(Vec::as_slice)
+    }
+}
+
+/// Writes successful live responses into a fixture directory.
+#[derive(Debug)]
+pub struct Recorder {
+    dir: PathBuf,
+}
+
+impl Recorder {
+    /// Record into `dir`, creating it if needed.
+    pub fn new(dir: &str) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create recording dir: {}", e))?;
+        Ok(Self {
+            dir: PathBuf::from(dir),
+        })
+    }
+
+    /// Persist one response body, then its key. The transport fails the
+    /// request on an error, so a recording session never ends up silently
+    /// missing a response.
+    pub(crate) fn record(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        body: &[u8],
+    ) -> Result<(), String> {
+        let key = request_key(path, query);
+        let name = file_name(&key);
+        std::fs::write(self.dir.join(&name), body)
+            .and_then(|()| std::fs::write(self.dir.join(name + KEY_SUFFIX), &key))
+            .map_err(|e| format!("Failed to record {}: {}", key, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn key_sorts_query_and_names_files_by_hash() {
+        let key = request_key(
+            "/athlete/i1/activities",
+            &[("oldest", "2026-01-01"), ("newest", "2026-06-26")],
+        );
+        assert_eq!(
+            key,
+            "athlete/i1/activities?newest=2026-06-26&oldest=2026-01-01"
+        );
+        let name = file_name(&key);
+        assert_eq!(name.len(), 16);
+        assert!(name.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_ne!(name, file_name("athlete/i1/activities"));
+        // FNV-1a test vector: names must not change between releases
+        assert_eq!(file_name("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn recorded_dir_replays_exact_then_by_path() {
+        let dir = TempDir::new().unwrap();
+        let rec = Recorder::new(dir.path().to_str().unwrap()).unwrap();
+        rec.record("/athlete/i1", &[], br#"{"id":"i1"}"#).unwrap();
+        rec.record(
+            "/athlete/i1/activities",
+            &[("oldest", "2026-01-01"), ("newest", "2026-01-31")],
+            b"[1]",
+        )
+        .unwrap();
+
+        let fx = Fixtures::open(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(fx.len(), 2);
+        assert_eq!(fx.lookup("/athlete/i1", &[]), Some(&br#"{"id":"i1"}"#[..]));
+        assert_eq!(
+            fx.lookup(
+                "/athlete/i1/activities",
+                &[("newest", "2026-01-31"), ("oldest", "2026-01-01")]
+            ),
+            Some(&b"[1]"[..])
+        );
+        // A different window still resolves to the recorded list.
+        assert_eq!(
+            fx.lookup("/athlete/i1/activities", &[("oldest", "2026-10-01")]),
+            Some(&b"[1]"[..])
+        );
+        assert_eq!(fx.lookup("/athlete/i1/wellness", &[]), None);
+    }
+
+    #[test]
+    fn long_keys_record_and_replay() {
+        let dir = TempDir::new().unwrap();
+        let rec = Recorder::new(dir.path().to_str().unwrap()).unwrap();
+        let fields = ["icu_training_load"; 30].join(",");
+        let query = [("fields", fields.as_str()), ("oldest", "2026-01-01")];
+        assert!(request_key("/athlete/i1/activities", &query).len() > 255);
+        rec.record("/athlete/i1/activities", &query, b"[1]")
+            .unwrap();
+
+        let fx = Fixtures::open(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(
+            fx.lookup("/athlete/i1/activities", &query),
+            Some(&b"[1]"[..])
+        );
+    }
+
+    #[test]
+    fn failed_write_is_reported() {
+        let dir = TempDir::new().unwrap();
+        let rec = Recorder::new(dir.path().join("rec").to_str().unwrap()).unwrap();
+        std::fs::remove_dir(dir.path().join("rec")).unwrap();
+        assert!(rec.record("/athlete/i1", &[], b"{}").is_err());
+    }
+
+    #[test]
+    fn loads_zipped_fixture_directory() {
+        let dir = TempDir::new().unwrap();
+        let archive = dir.path().join("demo.zip");
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            let name = file_name("athlete/i1");
+            zip.add_directory("demo/", options).unwrap();
+            zip.start_file(format!("demo/{}", name), options).unwrap();
+            zip.write_all(br#"{"id":"i1"}"#).unwrap();
+            zip.start_file(format!("demo/{}{}", name, KEY_SUFFIX), options)
+                .unwrap();
+            zip.write_all(b"athlete/i1").unwrap();
+            zip.finish().unwrap();
+        }
+        let fx = Fixtures::open(archive.to_str().unwrap()).unwrap();
+        assert_eq!(fx.len(), 1);
+        assert_eq!(fx.lookup("/athlete/i1", &[]), Some(&br#"{"id":"i1"}"#[..]));
+    }
+}
@@ -70,6 +70,21 @@ pub struct ActivityRecord {
     pub description: Option<String>,
     #[serde(default)]
     pub device_name: Option<String>,
+    /// Power zone seconds, `[{id, secs}]`. Only present with `includeStats`.
+    #[serde(default)]
+    pub icu_zone_times: Option<Vec<ZoneTimeDto>>,
+    /// HR zone seconds as a plain array. Only present with `includeStats`.
+    #[serde(default)]
+    pub icu_hr_zone_times: Option<Vec<f64>>,
+}
+
+/// One power-zone bucket in `icu_zone_times` (`id` is "Z1".."Z7" or "SS").
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneTimeDto {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub secs: f64,
 }
 
 /// The base field list the activities request asks for (matches `intervals.ts`).
@@ -242,6 +257,23 @@ pub struct WellnessRecord {
     pub steps: Option<f64>,
     #[serde(default)]
     pub vo2max: Option<f64>,
+    /// Load-derived fitness, used when `ctl` is unset.
+    #[serde(default, rename = "ctlLoad")]
+    pub ctl_load: Option<f64>,
+    /// Load-derived fatigue, used when `atl` is unset.
+    #[serde(default, rename = "atlLoad")]
+    pub atl_load: Option<f64>,
+    /// Self-reported 1-4 scales.
+    #[serde(default)]
+    pub soreness: Option<f64>,
+    #[serde(default)]
+    pub fatigue: Option<f64>,
+    #[serde(default)]
+    pub stress: Option<f64>,
+    #[serde(default)]
+    pub mood: Option<f64>,
+    #[serde(default)]
+    pub motivation: Option<f64>,
 }
 
 // ===========================================================================
@@ -430,6 +462,21 @@ mod tests {
         assert_eq!(acts[1].distance, Some(5000.0));
     }
 
+    #[test]
+    fn parses_activity_zone_times() {
+        let body = json!({
+            "id": "a1",
+            "icu_zone_times": [{"id": "Z1", "secs": 600}, {"id": "Z2", "secs": 1200}],
+            "icu_hr_zone_times": [300, 600, 1200, 900, 100]
+        });
+        let a: ActivityRecord = serde_json::from_value(body).unwrap();
+        let power = a.icu_zone_times.unwrap();
+        assert_eq!(power.len(), 2);
+        assert_eq!(power[1].id, "Z2");
+        assert_eq!(power[1].secs, 1200.0);
+        assert_eq!(a.icu_hr_zone_times.unwrap().len(), 5);
+    }
+
     #[test]
     fn oldest_date_is_min_start() {
         let acts: Vec<ActivityRecord> = serde_json::from_value(json!([
@@ -122,6 +122,20 @@ impl ActivityManager {
         })
     }
 
+    /// Full sensor streams stored by sync, or None if not downloaded yet.
+    fn get_streams(
+        &self,
+        activity_id: String,
+    ) -> Result<Option<crate::FfiActivityStreams>, VeloqError> {
+        with_engine(|e| {
+            e.get_activity_streams(&activity_id)
+                .map(|s| s.map(crate::FfiActivityStreams::from))
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
     fn remove(&self, activity_id: String) -> Result<(), VeloqError> {
         with_engine(|e| {
             e.remove_activity(&activity_id)
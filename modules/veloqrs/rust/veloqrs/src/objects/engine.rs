@@ -25,6 +25,7 @@ impl VeloqEngine {
         if !already {
             info!("[VeloqEngine] Initializing at {}", db_path);
             crate::persistence::persistent_engine_ffi::persistent_engine_init(db_path.clone());
+            crate::net::transport::cache::install_shared(&db_path);
         }
 
         Arc::new(Self { db_path })
@@ -215,4 +216,53 @@ impl VeloqEngine {
                 .map_err(|msg| VeloqError::Database { msg })
         })?
     }
+
+    /// Export a route's consensus polyline as a course for a head unit.
+    /// `format` is "gpx", "gpx_route" or "fit".
+    fn export_route_course(
+        &self,
+        route_id: String,
+        format: String,
+        dest_path: String,
+    ) -> Result<crate::persistence::export::CourseExportResult, VeloqError> {
+        with_engine(|e| {
+            e.export_route_course(&route_id, &format, &dest_path)
+                .map_err(|msg| VeloqError::Database { msg })
+        })?
+    }
+
+    /// Export a section polyline as a course for a head unit.
+    /// `format` is "gpx", "gpx_route" or "fit".
+    fn export_section_course(
+        &self,
+        section_id: String,
+        format: String,
+        dest_path: String,
+    ) -> Result<crate::persistence::export::CourseExportResult, VeloqError> {
+        with_engine(|e| {
+            e.export_section_course(&section_id, &format, &dest_path)
+                .map_err(|msg| VeloqError::Database { msg })
+        })?
+    }
+
+    /// Export a polyline, such as a route suggestion, as a course.
+    /// `format` is "gpx", "gpx_route" or "fit".
+    fn export_course(
+        &self,
+        name: String,
+        sport_type: String,
+        points: Vec<crate::FfiGpsPoint>,
+        format: String,
+        dest_path: String,
+    ) -> Result<crate::persistence::export::CourseExportResult, VeloqError> {
+        let points: Vec<crate::GpsPoint> = points.into_iter().map(Into::into).collect();
+        crate::persistence::export::export_course_points(
+            &name,
+            &sport_type,
+            &points,
+            &format,
+            &dest_path,
+        )
+        .map_err(|msg| VeloqError::Database { msg })
+    }
 }
@@ -102,6 +102,19 @@ impl FitnessManager {
         with_engine(|e| e.get_zone_distribution(&sport_type, &zone_type))
     }
 
+    /// Time-in-zone computed locally from one activity's streams.
+    fn get_activity_zone_times(
+        &self,
+        activity_id: String,
+    ) -> Result<crate::FfiActivityZoneTimes, VeloqError> {
+        with_engine(|e| {
+            e.get_activity_zone_times(&activity_id)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
     fn get_ftp_trend(&self) -> Result<crate::FfiFtpTrend, VeloqError> {
         with_engine(|e| e.get_ftp_trend())
     }
@@ -123,6 +136,184 @@ impl FitnessManager {
         with_engine(|e| e.get_pace_trend(&sport_type))
     }
 
+    /// Fit 2- / 3-parameter critical power models for a sport over a curve
+    /// window ("42d", "s0", "all") and store the result as today's
+    /// snapshot. None when there are too few best efforts.
+    fn fit_critical_power(
+        &self,
+        sport_type: String,
+        window: String,
+    ) -> Result<Option<crate::FfiCriticalPowerFit>, VeloqError> {
+        with_engine(|e| {
+            e.fit_critical_power(&sport_type, &window)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    fn get_cp_trend(&self, sport_type: String) -> Result<crate::FfiCpTrend, VeloqError> {
+        with_engine(|e| e.get_cp_trend(&sport_type))
+    }
+
+    /// Predicted 5k / 10k / half / marathon times from critical speed and
+    /// running best efforts. Empty when there is nothing to predict from.
+    fn get_race_predictions(&self) -> Result<Vec<crate::FfiRacePrediction>, VeloqError> {
+        with_engine(|e| e.get_race_predictions())
+    }
+
+    /// Distances (metres) and durations (seconds) scanned for best efforts.
+    fn get_best_effort_targets(&self) -> Result<crate::FfiBestEffortTargets, VeloqError> {
+        with_engine(|e| {
+            e.get_best_effort_targets()
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// Replace the best-effort targets and rescan stored streams against
+    /// them. Returns the number of activities rescanned.
+    fn set_best_effort_targets(
+        &self,
+        distances_meters: Vec<f64>,
+        durations_secs: Vec<u32>,
+    ) -> Result<u32, VeloqError> {
+        with_engine(|e| {
+            e.set_best_effort_targets(&distances_meters, &durations_secs)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// All-time, season and 90-day stream best efforts for a sport.
+    fn get_best_effort_records(
+        &self,
+        sport_type: String,
+    ) -> Result<Vec<crate::FfiBestEffortRecord>, VeloqError> {
+        with_engine(|e| {
+            e.get_best_effort_records(&sport_type)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// Best efforts found in one activity's streams.
+    fn get_activity_best_efforts(
+        &self,
+        activity_id: String,
+    ) -> Result<Vec<crate::FfiBestEffort>, VeloqError> {
+        with_engine(|e| {
+            e.get_activity_best_efforts(&activity_id)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// Power curve stored by the last sync, or None if never fetched.
+    fn get_power_curve(
+        &self,
+        sport_type: String,
+        window: String,
+    ) -> Result<Option<crate::FfiPowerCurve>, VeloqError> {
+        with_engine(|e| {
+            e.get_power_curve(&sport_type, &window)
+                .map(|c| {
+                    c.map(|(curve, fetched_at)| crate::FfiPowerCurve {
+                        sport_type: sport_type.clone(),
+                        window: window.clone(),
+                        secs: curve.secs,
+                        watts: curve.watts,
+                        activity_ids: curve.activity_ids.unwrap_or_default(),
+                        fetched_at,
+                    })
+                })
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// Pace curve stored by the last sync, or None if never fetched.
+    fn get_pace_curve(
+        &self,
+        sport_type: String,
+        window: String,
+    ) -> Result<Option<crate::FfiPaceCurve>, VeloqError> {
+        with_engine(|e| {
+            e.get_pace_curve(&sport_type, &window)
+                .map(|c| {
+                    c.map(|(curve, fetched_at)| crate::FfiPaceCurve {
+                        sport_type: sport_type.clone(),
+                        window: window.clone(),
+                        distances: curve.distances,
+                        times: curve.times,
+                        pace: curve.pace,
+                        activity_ids: curve.activity_ids.unwrap_or_default(),
+                        critical_speed: curve.critical_speed,
+                        d_prime: curve.d_prime,
+                        r2: curve.r2,
+                        start_date: curve.start_date,
+                        end_date: curve.end_date,
+                        days: curve.days,
+                        fetched_at,
+                    })
+                })
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// Best average power (W) held for `secs` in the stored curve.
+    fn get_best_power(
+        &self,
+        sport_type: String,
+        window: String,
+        secs: f64,
+    ) -> Result<Option<f64>, VeloqError> {
+        with_engine(|e| {
+            e.best_power_at(&sport_type, &window, secs)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// Best average speed (m/s) held for `secs` in the stored pace curve.
+    fn get_best_pace(
+        &self,
+        sport_type: String,
+        window: String,
+        secs: f64,
+    ) -> Result<Option<f64>, VeloqError> {
+        with_engine(|e| {
+            e.best_pace_at(&sport_type, &window, secs)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
+    /// Compare two stored windows of a "power" or "pace" curve.
+    fn compare_curve_windows(
+        &self,
+        kind: String,
+        sport_type: String,
+        window_a: String,
+        window_b: String,
+    ) -> Result<crate::FfiCurveComparison, VeloqError> {
+        with_engine(|e| {
+            e.compare_curve_windows(&kind, &sport_type, &window_a, &window_b)
+                .map_err(|e| VeloqError::Database {
+                    msg: format!("{}", e),
+                })
+        })?
+    }
+
     fn get_available_sport_types(&self) -> Result<Vec<String>, VeloqError> {
         with_engine(|e| e.get_available_sport_types())
     }
@@ -216,6 +407,25 @@ impl FitnessManager {
         })?
     }
 
+    /// Local CTL/ATL/TSB model over stored training loads, reconciled
+    /// against the synced wellness values, with a projection over
+    /// `planned_loads` (one per day from tomorrow). Time constants default
+    /// to 42 / 7 days.
+    fn get_fitness_model(
+        &self,
+        ctl_days: Option<f64>,
+        atl_days: Option<f64>,
+        history_days: u32,
+        planned_loads: Vec<f64>,
+    ) -> Result<crate::FfiFitnessModel, VeloqError> {
+        with_engine(|e| {
+            e.get_fitness_model(ctl_days, atl_days, history_days, &planned_loads)
+                .map_err(|err| VeloqError::Database {
+                    msg: format!("{}", err),
+                })
+        })?
+    }
+
     /// HRV trend (label + averages + sparkline) over the trailing `days`
     /// window. Returns `None` when there are <5 valid HRV days. TS maps
     /// the returned label to an i18n key and renders.
@@ -328,6 +538,7 @@ impl FitnessManager {
             // Trends
             let ftp_trend = e.get_ftp_trend();
             let run_pace_trend = e.get_pace_trend("Run");
+            let race_predictions = e.get_race_predictions();
 
             // Activity patterns
             let all_patterns =
@@ -390,6 +601,7 @@ impl FitnessManager {
                 all_patterns,
                 today_pattern,
                 recent_prs,
+                race_predictions,
             }
         })
     }
@@ -417,6 +629,7 @@ impl FitnessManager {
             let today_period = e.get_period_stats(today_start, now_ts);
             let ftp_trend = e.get_ftp_trend();
             let run_pace_trend = e.get_pace_trend("Run");
+            let race_predictions = e.get_race_predictions();
             let all_patterns =
                 crate::patterns::compute_activity_patterns(&e.db, &e.activity_metrics);
             let today_pattern = crate::patterns::get_pattern_for_today(&e.db, &e.activity_metrics);
@@ -477,6 +690,7 @@ impl FitnessManager {
                 all_patterns,
                 today_pattern,
                 recent_prs,
+                race_predictions,
             };
 
             // === Summary card data (reuses period stats + trends from insights) ===
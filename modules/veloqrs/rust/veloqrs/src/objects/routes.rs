@@ -1,6 +1,42 @@
 use super::error::{VeloqError, with_engine};
+use crate::persistence::PersistentRouteEngine;
+use crate::persistence::climbs::StoredClimb;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Stored shape classifications and climb metrics, keyed by group id.
+struct StoredDerivations {
+    shapes: HashMap<String, (String, f64)>,
+    climbs: HashMap<String, StoredClimb>,
+}
+
+impl StoredDerivations {
+    fn load(engine: &PersistentRouteEngine) -> Result<Self, VeloqError> {
+        let db_err = |e: rusqlite::Error| VeloqError::Database {
+            msg: format!("{}", e),
+        };
+        Ok(Self {
+            shapes: engine.get_route_shapes().map_err(db_err)?,
+            climbs: engine.get_route_climbs().map_err(db_err)?,
+        })
+    }
+
+    /// Attach a group's stored shape and climb metrics, where it has them.
+    fn apply(&self, mut group: crate::FfiRouteGroup) -> crate::FfiRouteGroup {
+        if let Some((shape, confidence)) = self.shapes.get(&group.group_id) {
+            group.shape = Some(shape.clone());
+            group.shape_confidence = Some(*confidence);
+        }
+        if let Some(climb) = self.climbs.get(&group.group_id) {
+            group.total_ascent = climb.total_ascent;
+            group.avg_gradient = climb.avg_gradient;
+            group.max_gradient = climb.max_gradient;
+            group.climb_category = climb.climb_category.clone();
+        }
+        group
+    }
+}
+
 #[derive(uniffi::Object)]
 pub struct RouteManager {
     pub(crate) _private: (),
@@ -15,16 +51,23 @@ impl RouteManager {
 
     fn get_all(&self) -> Result<Vec<crate::FfiRouteGroup>, VeloqError> {
         with_engine(|e| {
-            e.get_groups()
-                .iter()
-                .cloned()
-                .map(crate::FfiRouteGroup::from)
-                .collect()
-        })
+            let groups: Vec<_> = e.get_groups().to_vec();
+            let stored = StoredDerivations::load(e)?;
+            Ok(groups
+                .into_iter()
+                .map(|g| stored.apply(crate::FfiRouteGroup::from(g)))
+                .collect())
+        })?
     }
 
     fn get_by_id(&self, group_id: String) -> Result<Option<crate::FfiRouteGroup>, VeloqError> {
-        with_engine(|e| e.get_group_by_id(&group_id).map(crate::FfiRouteGroup::from))
+        with_engine(|e| {
+            let Some(group) = e.get_group_by_id(&group_id) else {
+                return Ok(None);
+            };
+            let stored = StoredDerivations::load(e)?;
+            Ok(Some(stored.apply(crate::FfiRouteGroup::from(group))))
+        })?
     }
 
     fn get_summaries(&self) -> Result<Vec<crate::GroupSummary>, VeloqError> {
@@ -41,15 +84,26 @@ impl RouteManager {
     /// Filtered + sorted group summaries. Pushes the activity-count threshold
     /// and sort key into Rust so the hook stops re-iterating in TS.
     /// `sort_key` accepts "count" or "name"; anything else maps to "count".
+    /// `shape` keeps only routes of that shape ("loop", "out_and_back",
+    /// "point_to_point", "lollipop").
+    /// `climbs_only` keeps routes containing a categorised climb.
     fn get_filtered_summaries(
         &self,
         min_activities: u32,
         sort_key: String,
+        shape: Option<String>,
+        climbs_only: bool,
     ) -> Result<crate::FfiGroupSummariesResult, VeloqError> {
         with_engine(|e| {
             let total_count = e.get_group_count();
             let mut summaries = e.get_group_summaries();
             summaries.retain(|g| g.activity_count >= min_activities);
+            if let Some(shape) = &shape {
+                summaries.retain(|g| g.shape.as_ref() == Some(shape));
+            }
+            if climbs_only {
+                summaries.retain(|g| g.climb_category.is_some());
+            }
             match sort_key.as_str() {
                 "name" => summaries.sort_by(|a, b| a.group_id.cmp(&b.group_id)),
                 _ => summaries.sort_by(|a, b| b.activity_count.cmp(&a.activity_count)),
@@ -75,6 +129,32 @@ impl RouteManager {
         })
     }
 
+    /// Where an activity strayed from the route's consensus line: per-point
+    /// offsets plus the runs further than `threshold_meters` from it. A
+    /// negative or non-finite threshold is rejected.
+    fn get_route_deviation(
+        &self,
+        group_id: String,
+        activity_id: String,
+        threshold_meters: f64,
+    ) -> Result<Option<crate::FfiRouteDeviation>, VeloqError> {
+        if !crate::persistence::route_deviation::valid_threshold(threshold_meters) {
+            return Err(VeloqError::ParseError {
+                msg: format!("invalid deviation threshold: {}", threshold_meters),
+            });
+        }
+        with_engine(|e| e.get_route_deviation(&group_id, &activity_id, threshold_meters))
+    }
+
+    /// Gradient profile, ascent and climb category of a route's consensus
+    /// line. None without elevation data.
+    fn get_climb_profile(
+        &self,
+        group_id: String,
+    ) -> Result<Option<crate::FfiClimbProfile>, VeloqError> {
+        with_engine(|e| e.get_route_climb_profile(&group_id))
+    }
+
     fn get_performances(
         &self,
         group_id: String,
@@ -91,6 +171,15 @@ impl RouteManager {
         })
     }
 
+    /// Predicted time on a route today, from recency-weighted past efforts
+    /// adjusted for the current FTP / critical speed. None without efforts.
+    fn predict_time(
+        &self,
+        route_id: String,
+    ) -> Result<Option<crate::FfiFinishTimePrediction>, VeloqError> {
+        with_engine(|e| e.predict_route_time(&route_id))
+    }
+
     fn get_screen_data(
         &self,
         group_limit: u32,
@@ -195,4 +284,48 @@ impl RouteManager {
             Ok(())
         })?
     }
+
+    /// Suggest rides of about `target_distance_meters` (± `tolerance_meters`)
+    /// starting and finishing near the given point, stitched together from
+    /// known routes and sections. Best first.
+    fn suggest_routes(
+        &self,
+        start_lat: f64,
+        start_lng: f64,
+        sport_type: String,
+        target_distance_meters: f64,
+        tolerance_meters: f64,
+    ) -> Result<Vec<crate::FfiRouteSuggestion>, VeloqError> {
+        with_engine(|e| {
+            e.suggest_routes(
+                start_lat,
+                start_lng,
+                &sport_type,
+                target_distance_meters,
+                tolerance_meters,
+            )
+        })
+    }
+
+    /// Merge route groups into one. The override is persisted and survives
+    /// regrouping; the first route's name carries over. Returns the new route ID.
+    fn merge_routes(&self, route_ids: Vec<String>) -> Result<String, VeloqError> {
+        with_engine(|e| {
+            e.merge_routes(&route_ids)
+                .map_err(|e| VeloqError::Database { msg: e })
+        })?
+    }
+
+    /// Move activities out of a route into a new one. The override is
+    /// persisted and survives regrouping. Returns the new route ID.
+    fn split_route(
+        &self,
+        route_id: String,
+        activity_ids: Vec<String>,
+    ) -> Result<String, VeloqError> {
+        with_engine(|e| {
+            e.split_route(&route_id, &activity_ids)
+                .map_err(|e| VeloqError::Database { msg: e })
+        })?
+    }
 }
@@ -1,7 +1,33 @@
 use super::error::{VeloqError, with_engine, with_engine_read};
+use crate::persistence::PersistentRouteEngine;
 use crate::sections::SectionType;
 use std::sync::Arc;
 
+/// Convert sections for FFI, attaching their stored climb metrics.
+fn with_climbs(
+    engine: &PersistentRouteEngine,
+    sections: Vec<crate::sections::Section>,
+) -> Result<Vec<crate::FfiSection>, VeloqError> {
+    let climbs = engine
+        .get_section_climbs()
+        .map_err(|e| VeloqError::Database {
+            msg: format!("{}", e),
+        })?;
+    Ok(sections
+        .into_iter()
+        .map(|s| {
+            let mut section = crate::FfiSection::from(s);
+            if let Some(climb) = climbs.get(&section.id) {
+                section.total_ascent = climb.total_ascent;
+                section.avg_gradient = climb.avg_gradient;
+                section.max_gradient = climb.max_gradient;
+                section.climb_category = climb.climb_category.clone();
+            }
+            section
+        })
+        .collect())
+}
+
 #[derive(uniffi::Object)]
 pub struct SectionManager {
     pub(crate) _private: (),
@@ -45,21 +71,11 @@ impl SectionManager {
         section_type: Option<String>,
     ) -> Result<Vec<crate::FfiSection>, VeloqError> {
         let st = section_type.as_deref().and_then(SectionType::from_str);
-        with_engine(|e| {
-            e.get_sections_by_type(st)
-                .into_iter()
-                .map(crate::FfiSection::from)
-                .collect()
-        })
+        with_engine(|e| with_climbs(e, e.get_sections_by_type(st)))?
     }
 
     fn get_for_activity(&self, activity_id: String) -> Result<Vec<crate::FfiSection>, VeloqError> {
-        with_engine(|e| {
-            e.get_sections_for_activity(&activity_id)
-                .into_iter()
-                .map(crate::FfiSection::from)
-                .collect()
-        })
+        with_engine(|e| with_climbs(e, e.get_sections_for_activity(&activity_id)))?
     }
 
     fn get_by_id(
@@ -137,12 +153,13 @@ impl SectionManager {
     /// Filtered + sorted section summaries. Pushes the visit-count threshold
     /// and sort key into Rust so TS stops re-iterating the summaries list.
     /// `sort_key` accepts "visits", "distance", "name"; anything else maps to
-    /// the default ("visits").
+    /// the default ("visits"). `climbs_only` keeps categorised climbs.
     fn get_filtered_summaries(
         &self,
         sport_type: Option<String>,
         min_visits: u32,
         sort_key: String,
+        climbs_only: bool,
     ) -> Result<crate::FfiSectionSummariesResult, VeloqError> {
         with_engine(|e| {
             let total_count = e.get_section_count();
@@ -151,6 +168,9 @@ impl SectionManager {
                 None => e.get_section_summaries(),
             };
             summaries.retain(|s| s.visit_count >= min_visits);
+            if climbs_only {
+                summaries.retain(|s| s.climb_category.is_some());
+            }
             match sort_key.as_str() {
                 "distance" => summaries.sort_by(|a, b| {
                     b.distance_meters
@@ -223,6 +243,81 @@ impl SectionManager {
         })
     }
 
+    /// Predicted time on a section today, from recency-weighted past efforts
+    /// adjusted for the current FTP / critical speed. None without efforts.
+    fn predict_time(
+        &self,
+        section_id: String,
+    ) -> Result<Option<crate::FfiFinishTimePrediction>, VeloqError> {
+        with_engine(|e| e.predict_section_time(&section_id))
+    }
+
+    /// Ghost comparison of two traversals of a section: the gap to `ghost`
+    /// every `step_meters`, plus per-split time won or lost. None if either
+    /// activity has no GPS track or time stream.
+    fn compare_efforts(
+        &self,
+        effort: crate::FfiSectionPortion,
+        ghost: crate::FfiSectionPortion,
+        step_meters: f64,
+    ) -> Result<Option<crate::FfiGhostComparison>, VeloqError> {
+        with_engine(|e| e.compare_section_efforts(&effort, &ghost, step_meters))
+    }
+
+    /// Gradient profile, ascent and climb category of a section. None
+    /// without elevation data.
+    fn get_climb_profile(
+        &self,
+        section_id: String,
+    ) -> Result<Option<crate::FfiClimbProfile>, VeloqError> {
+        with_engine(|e| e.get_section_climb_profile(&section_id))
+    }
+
+    /// Checkpoints on a section, in section order.
+    fn get_checkpoints(
+        &self,
+        section_id: String,
+    ) -> Result<Vec<crate::FfiSectionCheckpoint>, VeloqError> {
+        with_engine(|e| e.get_section_checkpoints(&section_id))
+    }
+
+    /// Place checkpoints at section polyline indices, replacing any existing
+    /// ones. An empty list clears them.
+    fn set_checkpoints(
+        &self,
+        section_id: String,
+        polyline_indices: Vec<u32>,
+    ) -> Result<Vec<crate::FfiSectionCheckpoint>, VeloqError> {
+        with_engine(|e| {
+            e.set_section_checkpoints(&section_id, &polyline_indices)
+                .map_err(|e| VeloqError::Database { msg: e })
+        })?
+    }
+
+    /// Generate `count` evenly spaced checkpoints by `mode` "distance" or
+    /// "elevation", replacing any existing ones.
+    fn generate_checkpoints(
+        &self,
+        section_id: String,
+        count: u32,
+        mode: String,
+    ) -> Result<Vec<crate::FfiSectionCheckpoint>, VeloqError> {
+        with_engine(|e| {
+            e.generate_section_checkpoints(&section_id, count, &mode)
+                .map_err(|e| VeloqError::Database { msg: e })
+        })?
+    }
+
+    /// Per-effort checkpoint splits with best splits and sum of best.
+    /// Filtered by sport like `get_performances`.
+    fn get_split_table(
+        &self,
+        section_id: String,
+        sport_type: Option<String>,
+    ) -> Result<Option<crate::FfiSectionSplitTable>, VeloqError> {
+        with_engine(|e| e.get_section_split_table(&section_id, sport_type.as_deref()))
+    }
+
     /// Tier 3.2: batched section-performance fetch. Returns one entry per
     /// requested section_id (in input order). Saves N FFI round-trips when
     /// the caller (Insights, Routes list) needs perfs for many sections in
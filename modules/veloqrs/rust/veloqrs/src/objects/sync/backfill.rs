@@ -0,0 +1,333 @@
+//! Historical backfill: walks the activity history backwards a month at a
+//! time on `Lane::Backfill`, so the governor's reserve keeps interactive
+//! screens responsive while years of history download.
+//!
+//! The cursor (oldest day covered) is persisted in `settings` after every
+//! month, so a killed app resumes where it stopped instead of starting over.
+//! The walk is bounded by the athlete's oldest activity, cached so a resumed
+//! run knows its fixed month total for `get_sync_status` without a full
+//! history pull. Once the cursor reaches the cached bound it is fetched again,
+//! since history imported later may reach further back.
+
+use super::{
+    INITIAL_SYNC_DAYS, SyncAbort, SyncMode, SyncService, SyncStore, derive_from_streams,
+    engine_call, reconcile_window, settle, sync_page,
+};
+use crate::governor::Lane;
+use crate::net::endpoints;
+use crate::net::transport::Transport;
+use crate::persistence::settings_keys;
+use chrono::{Datelike, Days, Local, NaiveDate};
+
+/// The backfill job. Free function over `&SyncService` like `perform_sync`.
+pub(crate) async fn perform_backfill<S: SyncStore>(
+    svc: &SyncService,
+    transport: Transport,
+    athlete_id: String,
+    store: &S,
+) {
+    settle(
+        svc,
+        SyncMode::Backfill,
+        run_backfill(svc, &transport, &athlete_id, store).await,
+    );
+}
+
+async fn run_backfill<S: SyncStore>(
+    svc: &SyncService,
+    t: &Transport,
+    athlete_id: &str,
+    store: &S,
+) -> Result<(), SyncAbort> {
+    let today = Local::now().date_naive();
+    let (cursor, cached_oldest) = engine_call(store, |e| {
+        Ok((
+            e.get_setting(settings_keys::SYNC_BACKFILL_CURSOR)?,
+            e.get_setting(settings_keys::SYNC_BACKFILL_OLDEST)?,
+        ))
+    })?;
+    // Without a cursor, start below the window the incremental sync covers.
+    let cursor = cursor
+        .as_deref()
+        .and_then(parse_day)
+        .unwrap_or_else(|| today - Days::new(INITIAL_SYNC_DAYS));
+
+    let oldest = match cached_oldest.as_deref().and_then(parse_day) {
+        Some(d) if cursor > d => d,
+        _ => {
+            let today_str = today.format("%Y-%m-%d").to_string();
+            let found =
+                endpoints::fetch_oldest_activity_date(t, athlete_id, &today_str, Lane::Backfill)
+                    .await?;
+            let Some(d) = found.as_deref().and_then(parse_day) else {
+                log::info!("tracematch: [Backfill] No activity history");
+                return Ok(());
+            };
+            let value = d.format("%Y-%m-%d").to_string();
+            engine_call(store, |e| {
+                e.set_setting(settings_keys::SYNC_BACKFILL_OLDEST, &value)
+            })?;
+            d
+        }
+    };
+
+    let pages = month_pages(cursor, oldest);
+    svc.add_total(SyncMode::Backfill, pages.len() as u32);
+    // Once a month has a failed stream download the cursor stops moving, so
+    // the next run walks back over that month and retries it.
+    let mut held = false;
+    for (start, end) in pages {
+        if svc.is_cancelled(SyncMode::Backfill) {
+            return Err(SyncAbort::Cancelled);
+        }
+        let oldest_str = start.format("%Y-%m-%d").to_string();
+        let newest_str = end.format("%Y-%m-%d").to_string();
+        let records = endpoints::fetch_activities(
+            t,
+            athlete_id,
+            &oldest_str,
+            &newest_str,
+            true,
+            Lane::Backfill,
+        )
+        .await?;
+        reconcile_window(t, store, start, end, records.iter().map(|r| r.id.as_str()))?;
+        let page = sync_page(svc, t, store, &records, SyncMode::Backfill).await?;
+        held |= page.oldest_failed.is_some();
+        if !held {
+            engine_call(store, |e| {
+                e.set_setting(settings_keys::SYNC_BACKFILL_CURSOR, &oldest_str)
+            })?;
+        }
+        svc.advance(SyncMode::Backfill, 1);
+        log::info!(
+            "tracematch: [Backfill] {}..{}: {} activities",
+            oldest_str,
+            newest_str,
+            records.len()
+        );
+    }
+    derive_from_streams(store)
+}
+
+/// Calendar-month pages walking backwards from the day before `cursor` down to
+/// the month containing `oldest`. The first page may be partial (it ends at
+/// the cursor); the rest are whole months.
+fn month_pages(cursor: NaiveDate, oldest: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut pages = Vec::new();
+    let mut upper = cursor;
+    while upper > oldest {
+        let end = upper - Days::new(1);
+        let start = end.with_day(1).unwrap_or(end);
+        pages.push((start, end));
+        upper = start;
+    }
+    pages
+}
+
+/// The date part of a `YYYY-MM-DD[THH:MM:SS]` string.
+fn parse_day(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{test_store, transport_to};
+    use super::*;
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    fn day(s: &str) -> NaiveDate {
+        parse_day(s).unwrap()
+    }
+
+    #[test]
+    fn month_pages_walk_back_to_oldest_month() {
+        let pages = month_pages(day("2026-03-15"), day("2025-12-10"));
+        assert_eq!(
+            pages,
+            vec![
+                (day("2026-03-01"), day("2026-03-14")),
+                (day("2026-02-01"), day("2026-02-28")),
+                (day("2026-01-01"), day("2026-01-31")),
+                (day("2025-12-01"), day("2025-12-31")),
+            ]
+        );
+        assert!(month_pages(day("2025-12-01"), day("2025-12-10")).is_empty());
+    }
+
+    #[test]
+    fn backfill_persists_cursor_and_reports_months() {
+        let server = MockServer::start();
+        let list = server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([]));
+        });
+        let store = test_store();
+        {
+            let engine = store.lock().unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_BACKFILL_CURSOR, "2026-03-01")
+                .unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_BACKFILL_OLDEST, "2025-12-10")
+                .unwrap();
+        }
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Backfill));
+        crate::runtime::block_on(perform_backfill(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        list.assert_hits(3);
+        let s = svc.snapshot();
+        assert_eq!(s.mode, "backfill");
+        assert_eq!(s.state, "idle");
+        assert_eq!((s.completed, s.total), (3, 3));
+        assert_eq!(
+            store
+                .lock()
+                .unwrap()
+                .get_setting(settings_keys::SYNC_BACKFILL_CURSOR)
+                .unwrap()
+                .as_deref(),
+            Some("2025-12-01")
+        );
+    }
+
+    #[test]
+    fn failed_month_leaves_cursor_at_last_completed() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("oldest", "2026-02-01");
+            then.status(200).json_body(json!([]));
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("oldest", "2026-01-01");
+            then.status(404);
+        });
+        let store = test_store();
+        {
+            let engine = store.lock().unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_BACKFILL_CURSOR, "2026-03-01")
+                .unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_BACKFILL_OLDEST, "2025-12-10")
+                .unwrap();
+        }
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Backfill));
+        crate::runtime::block_on(perform_backfill(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        let s = svc.snapshot();
+        assert!(s.last_error.is_some());
+        assert_eq!(s.completed, 1);
+        assert_eq!(
+            store
+                .lock()
+                .unwrap()
+                .get_setting(settings_keys::SYNC_BACKFILL_CURSOR)
+                .unwrap()
+                .as_deref(),
+            Some("2026-02-01")
+        );
+    }
+
+    #[test]
+    fn finished_backfill_rechecks_the_oldest_activity() {
+        let server = MockServer::start();
+        let index = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("fields", "id,start_date_local");
+            then.status(200).json_body(json!([
+                {"id": "imported", "start_date_local": "2025-10-05T08:00:00"}
+            ]));
+        });
+        let list = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("newest", "2025-11-30");
+            then.status(200).json_body(json!([]));
+        });
+        let older = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("newest", "2025-10-31");
+            then.status(200).json_body(json!([]));
+        });
+        let store = test_store();
+        {
+            let engine = store.lock().unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_BACKFILL_CURSOR, "2025-12-01")
+                .unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_BACKFILL_OLDEST, "2025-12-10")
+                .unwrap();
+        }
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Backfill));
+        crate::runtime::block_on(perform_backfill(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        index.assert_hits(1);
+        list.assert_hits(1);
+        older.assert_hits(1);
+        assert!(svc.snapshot().last_error.is_none());
+        let engine = store.lock().unwrap();
+        assert_eq!(
+            engine
+                .get_setting(settings_keys::SYNC_BACKFILL_OLDEST)
+                .unwrap()
+                .as_deref(),
+            Some("2025-10-05")
+        );
+        assert_eq!(
+            engine
+                .get_setting(settings_keys::SYNC_BACKFILL_CURSOR)
+                .unwrap()
+                .as_deref(),
+            Some("2025-10-01")
+        );
+    }
+
+    #[test]
+    fn empty_history_finishes_without_pages() {
+        let server = MockServer::start();
+        let index = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("fields", "id,start_date_local");
+            then.status(200).json_body(json!([]));
+        });
+        let store = test_store();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Backfill));
+        crate::runtime::block_on(perform_backfill(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        index.assert_hits(1);
+        let s = svc.snapshot();
+        assert_eq!(s.state, "idle");
+        assert_eq!(s.total, 0);
+        assert!(s.last_error.is_none());
+    }
+}
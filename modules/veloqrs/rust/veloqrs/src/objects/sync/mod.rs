@@ -0,0 +1,2375 @@
+//! The intervals.icu sync service - the single first-class FFI contract for all
+//! network I/O.
+//!
+//! TypeScript holds no axios client and constructs no per-call auth header. It
+//! sets credentials once, issues commands (`sync_now`, `start_backfill`,
+//! `cancel`), and reads a status snapshot (`get_sync_status`). The service owns
+//! a `Transport`, runs work on the shared `ASYNC_RUNTIME`, and never blocks the
+//! JS thread: commands return instantly after posting to the runtime; results
+//! surface through status.
+//!
+//! This is the command + status boundary. The contract (commands + status) is
+//! identical whether the underlying transport is a true async FFI future or this
+//! instant-return-plus-status form, so a later async-FFI swap is invisible to TS.
+
+use super::error::{VeloqError, with_engine};
+use crate::FfiActivityMetrics;
+use crate::governor::{AuthMethod, Lane};
+use crate::net::endpoints;
+use crate::net::oauth::{OAuthTokens, TokenRefresher};
+use crate::net::transport::{Fixtures, NetError, Recorder, Transport};
+use crate::net::types::{ActivityRecord, ParsedStreams, WellnessRecord};
+use crate::persistence::curves::{CURVE_KIND_PACE, CURVE_KIND_POWER, CURVE_WINDOWS};
+use crate::persistence::settings_keys;
+use crate::persistence::wellness::WellnessRow;
+use crate::persistence::{
+    DEMO_ENGINE_SWAP, DemoEngineSwap, PERSISTENT_ENGINE, PersistentRouteEngine,
+};
+use chrono::{Days, Local, NaiveDate, NaiveDateTime};
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::{Mutex, RwLock};
+use tracematch::GpsPoint;
+
+mod backfill;
+
+use backfill::perform_backfill;
+
+const INTERVALS_BASE_URL: &str = "https://intervals.icu/api/v1";
+
+/// How far back the first sync reaches when no watermark exists yet. Matches
+/// the default timeline range the app shows (`SyncDateRangeStore`).
+const INITIAL_SYNC_DAYS: u64 = 90;
+
+/// Default days before the watermark each sync re-lists. Activities uploaded
+/// late (a watch synced days after the ride) keep their own date, which the
+/// watermark may already have passed.
+const DEFAULT_ACTIVITIES_OVERLAP_DAYS: u64 = 3;
+
+/// Default trailing days each sync re-lists (ids only) to purge activities
+/// deleted on the server after the watermark passed them.
+const DEFAULT_RECONCILE_DAYS: u64 = 60;
+
+/// Width of one activities-list request. Keeps response bodies bounded when a
+/// long-idle install catches up.
+const SYNC_PAGE_DAYS: u64 = 30;
+
+/// How far back the first wellness sync reaches (the widest chart range).
+const INITIAL_WELLNESS_DAYS: u64 = 365;
+
+/// Default days of stored wellness re-fetched each sync. Sleep and HRV often
+/// land hours or days after the fact from a watch import.
+const DEFAULT_WELLNESS_OVERLAP_DAYS: u64 = 7;
+
+/// Power-curve window fitted into the day's `cp_history` snapshot once its
+/// curve is refreshed. Recent efforts, like the pace snapshot.
+const CP_SNAPSHOT_WINDOW: &str = "42d";
+
+/// The lifecycle state TypeScript renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    Idle,
+    Syncing,
+    Paused,
+    AuthExpired,
+}
+
+impl SyncState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncState::Idle => "idle",
+            SyncState::Syncing => "syncing",
+            SyncState::Paused => "paused",
+            SyncState::AuthExpired => "authExpired",
+        }
+    }
+}
+
+/// Which running slot a job holds. Incremental syncs the recent window on
+/// `Lane::Interactive`; backfill walks history on `Lane::Backfill`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncMode {
+    #[default]
+    Incremental,
+    Backfill,
+}
+
+impl SyncMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncMode::Incremental => "incremental",
+            SyncMode::Backfill => "backfill",
+        }
+    }
+
+    fn lane(&self) -> Lane {
+        match self {
+            SyncMode::Incremental => Lane::Interactive,
+            SyncMode::Backfill => Lane::Backfill,
+        }
+    }
+}
+
+/// Authentication scheme for the held credential.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthKind {
+    OAuth,
+    ApiKey,
+}
+
+impl AuthKind {
+    fn parse(method: &str) -> Option<AuthKind> {
+        match method.to_ascii_lowercase().as_str() {
+            "oauth" | "bearer" => Some(AuthKind::OAuth),
+            "api_key" | "apikey" | "basic" => Some(AuthKind::ApiKey),
+            _ => None,
+        }
+    }
+}
+
+/// Credentials held in Rust RAM only (TypeScript owns SecureStore). Cleared on
+/// logout via `clear_credentials`.
+#[derive(Clone)]
+struct Credentials {
+    method: AuthKind,
+    secret: String,
+    athlete_id: String,
+}
+
+/// The OAuth token pair currently in use. TypeScript persists it to SecureStore
+/// after a refresh so the next launch starts from the rotated tokens.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiOAuthTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// The status fields TypeScript reads / subscribes to.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiSyncStatus {
+    pub state: String,
+    /// "incremental" | "backfill": the job the counters describe.
+    pub mode: String,
+    pub in_flight: u32,
+    pub completed: u32,
+    pub total: u32,
+    pub last_error: Option<String>,
+}
+
+/// Status and control flags of one job slot.
+struct JobSlot {
+    state: SyncState,
+    in_flight: u32,
+    completed: u32,
+    total: u32,
+    last_error: Option<String>,
+    running: bool,
+    cancel: bool,
+}
+
+impl Default for JobSlot {
+    fn default() -> Self {
+        JobSlot {
+            state: SyncState::Idle,
+            in_flight: 0,
+            completed: 0,
+            total: 0,
+            last_error: None,
+            running: false,
+            cancel: false,
+        }
+    }
+}
+
+/// Incremental and backfill each own a slot, so a sync of the recent window
+/// never waits behind years of history. `shown` is the slot the status
+/// snapshot describes.
+#[derive(Default)]
+struct SyncInner {
+    incremental: JobSlot,
+    backfill: JobSlot,
+    shown: SyncMode,
+}
+
+impl SyncInner {
+    fn slot(&mut self, mode: SyncMode) -> &mut JobSlot {
+        match mode {
+            SyncMode::Incremental => &mut self.incremental,
+            SyncMode::Backfill => &mut self.backfill,
+        }
+    }
+}
+
+/// The long-lived service: status + credentials + base URL. One instance lives in
+/// the `SYNC_SERVICE` static; tests construct their own.
+pub struct SyncService {
+    inner: Mutex<SyncInner>,
+    creds: Mutex<Option<Credentials>>,
+    /// Present once an OAuth session has a refresh token and token endpoint.
+    /// Shared by every transport so concurrent 401s refresh only once.
+    refresher: Mutex<Option<Arc<TokenRefresher>>>,
+    /// Demo mode: recorded responses plus the athlete id they belong to.
+    /// Takes precedence over credentials while set.
+    demo: Mutex<Option<(Arc<Fixtures>, String)>>,
+    /// Capture live responses into a fixture directory while set.
+    recorder: Mutex<Option<Arc<Recorder>>>,
+    base_url: Mutex<String>,
+}
+
+impl SyncService {
+    fn new() -> Self {
+        SyncService {
+            inner: Mutex::new(SyncInner::default()),
+            creds: Mutex::new(None),
+            refresher: Mutex::new(None),
+            demo: Mutex::new(None),
+            recorder: Mutex::new(None),
+            base_url: Mutex::new(INTERVALS_BASE_URL.to_string()),
+        }
+    }
+
+    fn set_credentials(&self, method: AuthKind, secret: String, athlete_id: String) {
+        let mut g = self.creds.lock().unwrap_or_else(|e| e.into_inner());
+        *g = Some(Credentials {
+            method,
+            secret,
+            athlete_id,
+        });
+        *self.refresher.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn clear_credentials(&self) {
+        let mut g = self.creds.lock().unwrap_or_else(|e| e.into_inner());
+        *g = None;
+        *self.refresher.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Enable token refresh for the held OAuth credential. The held secret is
+    /// the initial access token.
+    fn set_oauth_refresh(
+        &self,
+        refresh_token: String,
+        token_endpoint: String,
+    ) -> Result<(), String> {
+        let creds_guard = self.creds.lock().unwrap_or_else(|e| e.into_inner());
+        let access_token = match creds_guard.as_ref() {
+            Some(c) if c.method == AuthKind::OAuth => c.secret.clone(),
+            Some(_) => return Err("token refresh requires OAuth credentials".to_string()),
+            None => return Err("no credentials set".to_string()),
+        };
+        let refresher = TokenRefresher::new(token_endpoint, access_token, refresh_token)?;
+        *self.refresher.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(refresher));
+        Ok(())
+    }
+
+    fn set_demo(&self, demo: Option<(Arc<Fixtures>, String)>) {
+        *self.demo.lock().unwrap_or_else(|e| e.into_inner()) = demo;
+    }
+
+    /// Turn demo mode on (`Some`) or off, moving the engines in `slot` with
+    /// `swap` so replayed data lands in a throwaway database instead of the
+    /// user's. Refused while a job runs, so no job straddles the swap; the
+    /// status lock is held throughout so none can start mid-way.
+    fn switch_demo(
+        &self,
+        demo: Option<(Arc<Fixtures>, String)>,
+        swap: &DemoEngineSwap,
+        slot: &RwLock<Option<PersistentRouteEngine>>,
+    ) -> Result<(), String> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.incremental.running || inner.backfill.running {
+            return Err("a sync is running".to_string());
+        }
+        match &demo {
+            Some(_) => swap.enter(slot)?,
+            None => swap.leave(slot),
+        }
+        self.set_demo(demo);
+        drop(inner);
+        Ok(())
+    }
+
+    fn set_recorder(&self, recorder: Option<Arc<Recorder>>) {
+        *self.recorder.lock().unwrap_or_else(|e| e.into_inner()) = recorder;
+    }
+
+    /// The token pair in use, reflecting any refresh since `set_oauth_refresh`.
+    fn oauth_tokens(&self) -> Option<OAuthTokens> {
+        self.refresher
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|r| r.current())
+    }
+
+    /// Build a transport from the held credentials and base URL, or a replay
+    /// transport in demo mode.
+    fn build_transport(&self) -> Result<(Transport, String), String> {
+        let base = self
+            .base_url
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some((fixtures, athlete_id)) =
+            self.demo.lock().unwrap_or_else(|e| e.into_inner()).clone()
+        {
+            let transport = Transport::new(base, AuthMethod::ApiKey(""))?.with_replay(fixtures);
+            return Ok((transport, athlete_id));
+        }
+        let creds_guard = self.creds.lock().unwrap_or_else(|e| e.into_inner());
+        let creds = creds_guard
+            .as_ref()
+            .ok_or_else(|| "no credentials set".to_string())?;
+        let auth = match creds.method {
+            AuthKind::OAuth => AuthMethod::Bearer(&creds.secret),
+            AuthKind::ApiKey => AuthMethod::ApiKey(&creds.secret),
+        };
+        let mut transport = Transport::new(base, auth)?;
+        if let Some(r) = self
+            .refresher
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            transport = transport.with_refresher(r);
+        }
+        if let Some(r) = self
+            .recorder
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            transport = transport.with_recorder(r);
+        }
+        Ok((transport, creds.athlete_id.clone()))
+    }
+
+    /// Atomically claim `mode`'s running slot and move it to `Syncing`. Returns
+    /// false if that job is already in flight (so commands are idempotent
+    /// under rapid taps). Incremental and backfill hold separate slots, so a
+    /// running backfill never blocks a sync of the recent window.
+    fn try_begin(&self, mode: SyncMode) -> bool {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = inner.slot(mode);
+        if slot.running {
+            return false;
+        }
+        *slot = JobSlot {
+            state: SyncState::Syncing,
+            in_flight: 1,
+            running: true,
+            ..JobSlot::default()
+        };
+        inner.shown = mode;
+        true
+    }
+
+    /// Grow the work total as pages reveal more activities.
+    fn add_total(&self, mode: SyncMode, n: u32) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = inner.slot(mode);
+        slot.total = slot.total.saturating_add(n);
+    }
+
+    /// Mark `n` units of work done, never past the known total.
+    fn advance(&self, mode: SyncMode, n: u32) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = inner.slot(mode);
+        slot.completed = slot.completed.saturating_add(n).min(slot.total);
+    }
+
+    /// Terminal transition for a finished job. The status moves over to the
+    /// other job if that one is still running, unless this one ends in
+    /// `AuthExpired`, which TypeScript must see to re-authenticate.
+    fn finish(&self, mode: SyncMode, state: SyncState, last_error: Option<String>, success: bool) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = inner.slot(mode);
+        slot.state = state;
+        slot.running = false;
+        slot.in_flight = 0;
+        if success {
+            slot.completed = slot.total;
+        }
+        slot.last_error = last_error;
+        let other = match mode {
+            SyncMode::Incremental => SyncMode::Backfill,
+            SyncMode::Backfill => SyncMode::Incremental,
+        };
+        inner.shown = if state != SyncState::AuthExpired && inner.slot(other).running {
+            other
+        } else {
+            mode
+        };
+    }
+
+    /// Soft cancel: flag both jobs so they stop dispatching new work. An
+    /// in-flight request is allowed to finish.
+    fn request_cancel(&self) {
+        let mut guard = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let inner = &mut *guard;
+        for slot in [&mut inner.incremental, &mut inner.backfill] {
+            slot.cancel = true;
+            // Pause dispatch while a request is in flight; the job's terminal
+            // transition then settles back to Idle.
+            if slot.running {
+                slot.state = SyncState::Paused;
+            }
+        }
+    }
+
+    fn is_cancelled(&self, mode: SyncMode) -> bool {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .slot(mode)
+            .cancel
+    }
+
+    fn snapshot(&self) -> FfiSyncStatus {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mode = inner.shown;
+        let slot = inner.slot(mode);
+        FfiSyncStatus {
+            state: slot.state.as_str().to_string(),
+            mode: mode.as_str().to_string(),
+            in_flight: slot.in_flight,
+            completed: slot.completed,
+            total: slot.total,
+            last_error: slot.last_error.clone(),
+        }
+    }
+}
+
+/// The process-wide sync service.
+pub static SYNC_SERVICE: Lazy<SyncService> = Lazy::new(SyncService::new);
+
+/// Engine access for the sync job. Production writes go through the global
+/// `PERSISTENT_ENGINE`; tests hand in a private in-memory engine so they never
+/// contend on the process-wide singleton.
+pub(crate) trait SyncStore: Sync {
+    fn with_engine<R>(&self, f: impl FnOnce(&mut PersistentRouteEngine) -> R) -> Option<R>;
+}
+
+/// The process-wide engine behind `with_persistent_engine`.
+pub(crate) struct GlobalEngine;
+
+impl SyncStore for GlobalEngine {
+    fn with_engine<R>(&self, f: impl FnOnce(&mut PersistentRouteEngine) -> R) -> Option<R> {
+        crate::persistence::with_persistent_engine(f)
+    }
+}
+
+/// Why a sync stopped early. Mapped onto the terminal status by `settle`.
+enum SyncAbort {
+    Cancelled,
+    Net(NetError),
+    Engine(String),
+}
+
+impl From<NetError> for SyncAbort {
+    fn from(e: NetError) -> Self {
+        SyncAbort::Net(e)
+    }
+}
+
+/// Run `f` against the engine, folding "not initialized" and SQLite failures
+/// into a `SyncAbort`.
+fn engine_call<S, R>(
+    store: &S,
+    f: impl FnOnce(&mut PersistentRouteEngine) -> rusqlite::Result<R>,
+) -> Result<R, SyncAbort>
+where
+    S: SyncStore,
+{
+    match store.with_engine(f) {
+        Some(Ok(v)) => Ok(v),
+        Some(Err(e)) => Err(SyncAbort::Engine(e.to_string())),
+        None => Err(SyncAbort::Engine("engine not initialized".to_string())),
+    }
+}
+
+/// The sync job: an incremental activity sync from the stored watermark,
+/// followed by the wellness and fitness-curve stages.
+///
+/// The window `[watermark - overlap, today]` is walked in `SYNC_PAGE_DAYS`
+/// pages, the overlap re-listing recent days for late uploads. Each
+/// page upserts metrics for every listed activity, then downloads GPS + time
+/// streams for activities the engine has not stored yet. The watermark moves
+/// forward only after a page completes, so a cancel, a crash or an auth expiry
+/// resumes from the last finished page. Status `total` grows as pages reveal
+/// activities; `completed` counts activities fully processed. The wellness
+/// and curve stages count as one unit each.
+///
+/// Free function over `&SyncService` so tests can drive it with a mock-server
+/// transport against a local service instance and engine.
+pub(crate) async fn perform_sync<S: SyncStore>(
+    svc: &SyncService,
+    transport: Transport,
+    athlete_id: String,
+    store: &S,
+) {
+    settle(
+        svc,
+        SyncMode::Incremental,
+        run_stages(svc, &transport, &athlete_id, store).await,
+    );
+}
+
+/// Terminal transition shared by every job.
+fn settle(svc: &SyncService, mode: SyncMode, result: Result<(), SyncAbort>) {
+    match result {
+        Ok(()) => svc.finish(mode, SyncState::Idle, None, true),
+        Err(SyncAbort::Cancelled) => svc.finish(mode, SyncState::Idle, None, false),
+        Err(SyncAbort::Net(NetError::Unauthorized)) => svc.finish(
+            mode,
+            SyncState::AuthExpired,
+            Some("unauthorized".to_string()),
+            false,
+        ),
+        Err(SyncAbort::Net(e)) => svc.finish(mode, SyncState::Idle, Some(e.to_string()), false),
+        Err(SyncAbort::Engine(msg)) => svc.finish(mode, SyncState::Idle, Some(msg), false),
+    }
+}
+
+async fn run_stages<S: SyncStore>(
+    svc: &SyncService,
+    t: &Transport,
+    athlete_id: &str,
+    store: &S,
+) -> Result<(), SyncAbort> {
+    let added = sync_activities(svc, t, athlete_id, store).await?;
+    reconcile_recent(svc, t, athlete_id, store).await?;
+    derive_from_streams(store)?;
+    sync_wellness(svc, t, athlete_id, store).await?;
+    sync_curves(svc, t, athlete_id, store, added > 0).await
+}
+
+/// Returns the number of activities seen for the first time.
+async fn sync_activities<S: SyncStore>(
+    svc: &SyncService,
+    t: &Transport,
+    athlete_id: &str,
+    store: &S,
+) -> Result<u32, SyncAbort> {
+    let today = Local::now().date_naive();
+    let (watermark, overlap) = engine_call(store, |e| {
+        let watermark = e.get_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK)?;
+        let overlap = e
+            .get_setting(settings_keys::SYNC_ACTIVITIES_OVERLAP_DAYS)?
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_ACTIVITIES_OVERLAP_DAYS);
+        Ok((watermark, overlap))
+    })?;
+    let mut page_start = watermark
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .map(|d| d.min(today) - Days::new(overlap))
+        .unwrap_or_else(|| today - Days::new(INITIAL_SYNC_DAYS));
+
+    let mut added = 0;
+    // Day of the oldest activity whose streams failed to download this run.
+    // The watermark never moves past it, so the next sync re-lists that day
+    // and retries the download.
+    let mut held: Option<NaiveDate> = None;
+    loop {
+        if svc.is_cancelled(SyncMode::Incremental) {
+            return Err(SyncAbort::Cancelled);
+        }
+        let page_end = (page_start + Days::new(SYNC_PAGE_DAYS - 1)).min(today);
+        let oldest = page_start.format("%Y-%m-%d").to_string();
+        let newest = page_end.format("%Y-%m-%d").to_string();
+        let records =
+            endpoints::fetch_activities(t, athlete_id, &oldest, &newest, true, Lane::Interactive)
+                .await?;
+        reconcile_window(
+            t,
+            store,
+            page_start,
+            page_end,
+            records.iter().map(|r| r.id.as_str()),
+        )?;
+        let page = sync_page(svc, t, store, &records, SyncMode::Incremental).await?;
+        added += page.added;
+        if let Some(day) = page.oldest_failed {
+            held = Some(held.map_or(day, |h| h.min(day)));
+        }
+        let watermark = held
+            .map_or(page_end, |h| h.min(page_end))
+            .format("%Y-%m-%d")
+            .to_string();
+        engine_call(store, |e| {
+            e.set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &watermark)
+        })?;
+        log::info!(
+            "tracematch: [Sync] Activities {}..{}: {} listed",
+            oldest,
+            newest,
+            records.len()
+        );
+        if page_end >= today {
+            return Ok(added);
+        }
+        page_start = page_end + Days::new(1);
+    }
+}
+
+/// Purge local activities dated inside `[start, end]` that the server no
+/// longer lists: deleted, or merged into another activity. Runs only on a
+/// page fetched live from the server (never a cached copy), so a failed
+/// request never looks like deletion. Recorded demo pages never purge.
+fn reconcile_window<'a, S: SyncStore>(
+    t: &Transport,
+    store: &S,
+    start: NaiveDate,
+    end: NaiveDate,
+    listed: impl IntoIterator<Item = &'a str>,
+) -> Result<u32, SyncAbort> {
+    if t.is_replay() {
+        return Ok(0);
+    }
+    let (Some(from), Some(to)) = (local_midnight(start), local_midnight(end + Days::new(1))) else {
+        return Ok(0);
+    };
+    let listed: HashSet<&str> = listed.into_iter().collect();
+    engine_call(store, |e| {
+        let gone: Vec<String> = e
+            .get_activity_ids_between(from, to)
+            .into_iter()
+            .filter(|id| !listed.contains(id.as_str()))
+            .collect();
+        e.purge_deleted_activities(&gone)
+    })
+}
+
+/// Purge activities deleted on the server from the trailing
+/// `SYNC_RECONCILE_DAYS` window. The paging loop only reconciles the days it
+/// re-lists, so a deletion older than the watermark overlap would otherwise
+/// stay forever. One id-only listing; skipped in demo mode.
+async fn reconcile_recent<S: SyncStore>(
+    svc: &SyncService,
+    t: &Transport,
+    athlete_id: &str,
+    store: &S,
+) -> Result<(), SyncAbort> {
+    if svc.is_cancelled(SyncMode::Incremental) {
+        return Err(SyncAbort::Cancelled);
+    }
+    if t.is_replay() {
+        return Ok(());
+    }
+    let days = engine_call(store, |e| {
+        Ok(e.get_setting(settings_keys::SYNC_RECONCILE_DAYS)?
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_RECONCILE_DAYS))
+    })?;
+    if days == 0 {
+        return Ok(());
+    }
+    let today = Local::now().date_naive();
+    let start = today - Days::new(days);
+    let oldest = start.format("%Y-%m-%d").to_string();
+    let newest = today.format("%Y-%m-%d").to_string();
+    let ids =
+        endpoints::fetch_activity_ids(t, athlete_id, &oldest, &newest, Lane::Interactive).await?;
+    let purged = reconcile_window(t, store, start, today, ids.iter().map(String::as_str))?;
+    log::info!(
+        "tracematch: [Sync] Reconciled {}..{}: {} listed, {} purged",
+        oldest,
+        newest,
+        ids.len(),
+        purged
+    );
+    Ok(())
+}
+
+/// What one processed page reports back to the paging loop.
+#[derive(Debug, Default)]
+struct PageOutcome {
+    /// Listed activities that were new locally.
+    added: u32,
+    /// Day of the oldest activity whose stream download failed, if any. The
+    /// caller keeps its resume point at or before this day so the download is
+    /// retried on the next run.
+    oldest_failed: Option<NaiveDate>,
+}
+
+/// Process one listed page: metrics for everything, then one stream download
+/// per activity that is missing its GPS track or its stored sensor streams.
+/// Incremental mode counts progress per activity; backfill counts months in
+/// its caller instead.
+async fn sync_page<S: SyncStore>(
+    svc: &SyncService,
+    t: &Transport,
+    store: &S,
+    records: &[ActivityRecord],
+    mode: SyncMode,
+) -> Result<PageOutcome, SyncAbort> {
+    if records.is_empty() {
+        return Ok(PageOutcome::default());
+    }
+    let per_activity = mode == SyncMode::Incremental;
+    if per_activity {
+        svc.add_total(mode, records.len() as u32);
+    }
+
+    let metrics: Vec<FfiActivityMetrics> = records.iter().map(metrics_from_record).collect();
+    let (added, pending): (u32, Vec<(&ActivityRecord, bool)>) = engine_call(store, |e| {
+        let added = records
+            .iter()
+            .filter(|r| e.get_activity_metrics(&r.id).is_none())
+            .count() as u32;
+        e.set_activity_metrics_extended(metrics)?;
+        let mut pending = Vec::new();
+        for r in records {
+            // A fetched activity is never downloaded again, even when its
+            // streams came back empty or without a usable track.
+            if e.has_fetched_streams(&r.id)? {
+                continue;
+            }
+            let needs_gps = has_gps_stream(r) && !e.has_activity(&r.id);
+            if needs_gps || has_any_stream(r) {
+                pending.push((r, needs_gps));
+            }
+        }
+        Ok((added, pending))
+    })?;
+    if per_activity {
+        svc.advance(mode, (records.len() - pending.len()) as u32);
+    }
+
+    let mut tracks: Vec<(String, Vec<GpsPoint>, String)> = Vec::new();
+    let mut time_ids: Vec<String> = Vec::new();
+    let mut all_times: Vec<u32> = Vec::new();
+    let mut offsets: Vec<u32> = Vec::new();
+    let mut sensor_streams: Vec<(String, ParsedStreams)> = Vec::new();
+    let mut oldest_failed: Option<NaiveDate> = None;
+    for (record, needs_gps) in pending {
+        if svc.is_cancelled(mode) {
+            break;
+        }
+        match endpoints::fetch_streams(t, &record.id, None, mode.lane()).await {
+            Ok(streams) => {
+                if needs_gps {
+                    if let Some((coords, times)) = gps_from_streams(&streams) {
+                        if let Some(times) = times {
+                            time_ids.push(record.id.clone());
+                            offsets.push(all_times.len() as u32);
+                            all_times.extend(times);
+                        }
+                        let sport = record
+                            .activity_type
+                            .clone()
+                            .unwrap_or_else(|| "Ride".to_string());
+                        tracks.push((record.id.clone(), coords, sport));
+                    }
+                }
+                sensor_streams.push((record.id.clone(), streams));
+            }
+            Err(NetError::Unauthorized) => return Err(NetError::Unauthorized.into()),
+            // One bad activity must not stall the whole sync; its metrics are
+            // already stored and the map lacks its trace until a later run
+            // retries it.
+            Err(e) => {
+                log::warn!(
+                    "tracematch: [Sync] Stream download failed for {}: {}",
+                    record.id,
+                    e
+                );
+                if let Some(day) = record.start_date_local.as_deref().and_then(record_day) {
+                    oldest_failed = Some(oldest_failed.map_or(day, |d| d.min(day)));
+                }
+            }
+        }
+        if per_activity {
+            svc.advance(mode, 1);
+        }
+    }
+
+    // Store what was downloaded even on cancel, so the next run skips it.
+    engine_call(store, |e| {
+        e.add_activities_batch(tracks)?;
+        if !time_ids.is_empty() {
+            e.set_time_streams_flat(&time_ids, &all_times, &offsets);
+        }
+        for (id, streams) in &sensor_streams {
+            e.store_activity_streams(id, streams)?;
+        }
+        Ok(())
+    })?;
+
+    if svc.is_cancelled(mode) {
+        return Err(SyncAbort::Cancelled);
+    }
+    Ok(PageOutcome {
+        added,
+        oldest_failed,
+    })
+}
+
+/// Passes over the streams stored by `sync_page`, run once per sync rather
+/// than per page: effort metrics for section traversals whose streams
+/// arrived, best efforts of activities not scanned yet with the PR
+/// indicators built from them, and time-in-zone.
+fn derive_from_streams<S: SyncStore>(store: &S) -> Result<(), SyncAbort> {
+    engine_call(store, |e| {
+        e.update_section_effort_metrics(None)?;
+        e.update_best_efforts()?;
+        e.refresh_effort_indicators()?;
+        e.update_zone_times(None)?;
+        Ok(())
+    })
+}
+
+/// Fetch wellness from the newest stored day minus the overlap through today
+/// and upsert it in one transaction, so sparklines and the HRV trend read
+/// current rows as soon as the job finishes.
+async fn sync_wellness<S: SyncStore>(
+    svc: &SyncService,
+    t: &Transport,
+    athlete_id: &str,
+    store: &S,
+) -> Result<(), SyncAbort> {
+    if svc.is_cancelled(SyncMode::Incremental) {
+        return Err(SyncAbort::Cancelled);
+    }
+    svc.add_total(SyncMode::Incremental, 1);
+    let today = Local::now().date_naive();
+    let (latest, overlap) = engine_call(store, |e| {
+        let overlap = e
+            .get_setting(settings_keys::SYNC_WELLNESS_OVERLAP_DAYS)?
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_WELLNESS_OVERLAP_DAYS);
+        Ok((e.latest_wellness_date()?, overlap))
+    })?;
+    let oldest = latest
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .map(|d| d.min(today) - Days::new(overlap))
+        .unwrap_or_else(|| today - Days::new(INITIAL_WELLNESS_DAYS));
+    let oldest = oldest.format("%Y-%m-%d").to_string();
+    let newest = today.format("%Y-%m-%d").to_string();
+
+    let records =
+        endpoints::fetch_wellness(t, athlete_id, &oldest, &newest, Lane::Interactive).await?;
+    let rows: Vec<WellnessRow> = records
+        .iter()
+        .filter(|r| !r.id.is_empty())
+        .map(wellness_row_from_record)
+        .collect();
+    engine_call(store, |e| e.upsert_wellness(&rows))?;
+    log::info!(
+        "tracematch: [Sync] Wellness {}..{}: {} rows",
+        oldest,
+        newest,
+        rows.len()
+    );
+    svc.advance(SyncMode::Incremental, 1);
+    Ok(())
+}
+
+/// Refresh the stored power / pace curves for every sport family the athlete
+/// has activities in. Curves only change when activities do, so a window is
+/// re-fetched when this sync added activities or nothing is stored for it
+/// yet; otherwise the fitness screens read the stored copy. A failed curve is
+/// logged and skipped - it keeps its previous copy and does not fail the
+/// sync. A refreshed `CP_SNAPSHOT_WINDOW` power curve is fitted into the
+/// day's critical power snapshot.
+async fn sync_curves<S: SyncStore>(
+    svc: &SyncService,
+    t: &Transport,
+    athlete_id: &str,
+    store: &S,
+    activities_changed: bool,
+) -> Result<(), SyncAbort> {
+    if svc.is_cancelled(SyncMode::Incremental) {
+        return Err(SyncAbort::Cancelled);
+    }
+    let pending: Vec<(&'static str, &'static str, &'static str)> = engine_call(store, |e| {
+        let mut targets: Vec<(&'static str, &'static str)> = e
+            .get_available_sport_types()
+            .iter()
+            .filter_map(|sport| curve_target(sport))
+            .collect();
+        targets.sort();
+        targets.dedup();
+        let mut pending = Vec::new();
+        for (kind, sport) in targets {
+            for &window in CURVE_WINDOWS {
+                if activities_changed || !e.has_fitness_curve(kind, sport, window)? {
+                    pending.push((kind, sport, window));
+                }
+            }
+        }
+        Ok(pending)
+    })?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    svc.add_total(SyncMode::Incremental, 1);
+
+    let mut stored = 0;
+    let mut cp_sports = Vec::new();
+    for (kind, sport, window) in pending {
+        if svc.is_cancelled(SyncMode::Incremental) {
+            return Err(SyncAbort::Cancelled);
+        }
+        let fetched = if kind == CURVE_KIND_POWER {
+            endpoints::fetch_power_curve(t, athlete_id, sport, window, Lane::Interactive)
+                .await
+                .map(|c| engine_call(store, |e| e.save_power_curve(sport, window, &c)))
+        } else {
+            endpoints::fetch_pace_curve(t, athlete_id, sport, window, Lane::Interactive)
+                .await
+                .map(|c| engine_call(store, |e| e.save_pace_curve(sport, window, &c)))
+        };
+        match fetched {
+            Ok(saved) => {
+                saved?;
+                stored += 1;
+                if kind == CURVE_KIND_POWER && window == CP_SNAPSHOT_WINDOW {
+                    cp_sports.push(sport);
+                }
+            }
+            Err(NetError::Unauthorized) => return Err(NetError::Unauthorized.into()),
+            Err(e) => log::warn!(
+                "tracematch: [Sync] Curve download failed for {} {} {}: {}",
+                kind,
+                sport,
+                window,
+                e
+            ),
+        }
+    }
+    log::info!("tracematch: [Sync] Fitness curves: {} stored", stored);
+    engine_call(store, |e| {
+        for sport in cp_sports {
+            e.fit_critical_power(sport, CP_SNAPSHOT_WINDOW)?;
+        }
+        Ok(())
+    })?;
+    svc.advance(SyncMode::Incremental, 1);
+    Ok(())
+}
+
+/// Curve kind and request sport for an activity sport type: power for the
+/// cycling family, pace for running and swimming.
+fn curve_target(sport: &str) -> Option<(&'static str, &'static str)> {
+    match sport {
+        "Ride" | "VirtualRide" | "MountainBikeRide" | "GravelRide" | "Handcycle" | "Velomobile" => {
+            Some((CURVE_KIND_POWER, "Ride"))
+        }
+        "Run" | "VirtualRun" | "TrailRun" => Some((CURVE_KIND_PACE, "Run")),
+        "Swim" | "OpenWaterSwim" => Some((CURVE_KIND_PACE, "Swim")),
+        _ => None,
+    }
+}
+
+/// Convert a wellness record into the stored row. Mirrors the mapping in
+/// `syncWellnessToEngine` (`src/features/wellness/hooks/useWellness.ts`).
+fn wellness_row_from_record(r: &WellnessRecord) -> WellnessRow {
+    let scale = |v: Option<f64>| v.map(|x| x.round() as i32);
+    WellnessRow {
+        date: r.id.clone(),
+        ctl: r.ctl.or(r.ctl_load),
+        atl: r.atl.or(r.atl_load),
+        ramp_rate: r.ramp_rate,
+        hrv: r.hrv,
+        resting_hr: r.resting_hr,
+        weight: r.weight,
+        sleep_secs: r.sleep_secs.map(|s| s.round() as i64),
+        sleep_score: r.sleep_score,
+        soreness: scale(r.soreness),
+        fatigue: scale(r.fatigue),
+        stress: scale(r.stress),
+        mood: scale(r.mood),
+        motivation: scale(r.motivation),
+    }
+}
+
+/// Activities whose list entry names its streams are skipped when `latlng` is
+/// absent (indoor rides, manual entries). An unknown stream list is tried.
+fn has_gps_stream(r: &ActivityRecord) -> bool {
+    r.stream_types
+        .as_ref()
+        .is_none_or(|types| types.iter().any(|t| t == "latlng"))
+}
+
+/// Manual entries list no streams at all; an unknown list is tried.
+fn has_any_stream(r: &ActivityRecord) -> bool {
+    r.stream_types
+        .as_ref()
+        .is_none_or(|types| !types.is_empty())
+}
+
+/// Validated GPS points plus the matching time stream. The time stream is kept
+/// only when it lines up 1:1 with the points (no dropped coordinates), since
+/// section timing indexes it by point. Returns None for fewer than 2 points.
+fn gps_from_streams(streams: &ParsedStreams) -> Option<(Vec<GpsPoint>, Option<Vec<u32>>)> {
+    let coords: Vec<GpsPoint> = streams
+        .latlng
+        .iter()
+        .filter(|p| {
+            p[0].is_finite()
+                && p[1].is_finite()
+                && (-90.0..=90.0).contains(&p[0])
+                && (-180.0..=180.0).contains(&p[1])
+        })
+        .map(|p| GpsPoint::new(p[0], p[1]))
+        .collect();
+    if coords.len() < 2 {
+        return None;
+    }
+    let times = (streams.time.len() == coords.len())
+        .then(|| streams.time.iter().map(|&s| s.max(0) as u32).collect());
+    Some((coords, times))
+}
+
+/// Convert an activities-list record into the engine's metrics row. Mirrors
+/// `toActivityMetrics` in `src/features/activity/lib/activityMetrics.ts`.
+fn metrics_from_record(r: &ActivityRecord) -> FfiActivityMetrics {
+    FfiActivityMetrics {
+        activity_id: r.id.clone(),
+        name: r.name.clone().unwrap_or_default(),
+        date: r
+            .start_date_local
+            .as_deref()
+            .and_then(local_timestamp)
+            .unwrap_or(0),
+        distance: r.distance.unwrap_or(0.0),
+        moving_time: r.moving_time.unwrap_or(0).max(0) as u32,
+        elapsed_time: r.elapsed_time.unwrap_or(0).max(0) as u32,
+        elevation_gain: r.total_elevation_gain.unwrap_or(0.0),
+        avg_hr: r.average_heartrate.map(|v| v.round() as u16),
+        avg_power: r.average_watts.map(|v| v.round() as u16),
+        sport_type: r
+            .activity_type
+            .clone()
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| "Ride".to_string()),
+        training_load: r.icu_training_load,
+        ftp: r.icu_ftp.map(|v| v.round() as u16),
+        power_zone_times: r
+            .icu_zone_times
+            .as_ref()
+            .map(|zones| zones.iter().map(|z| z.secs.max(0.0) as u32).collect()),
+        hr_zone_times: r
+            .icu_hr_zone_times
+            .as_ref()
+            .map(|zones| zones.iter().map(|&s| s.max(0.0) as u32).collect()),
+    }
+}
+
+/// Start of `day` in the device timezone, on the same clock as
+/// `local_timestamp`.
+fn local_midnight(day: NaiveDate) -> Option<i64> {
+    day.and_hms_opt(0, 0, 0)?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+/// The calendar day of a `start_date_local` string.
+fn record_day(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// `start_date_local` ("2026-06-20T07:00:00") read in the device timezone, as
+/// `new Date(...)` does in the TS layer.
+fn local_timestamp(s: &str) -> Option<i64> {
+    let naive: NaiveDateTime = s.parse().ok()?;
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+/// Claim the running slot for `mode` and post `job` to the shared runtime.
+/// Returns false if that job already holds its slot or credentials are
+/// missing.
+fn start_job<F, Fut>(mode: SyncMode, job: F) -> bool
+where
+    F: FnOnce(Transport, String) -> Fut,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    if !SYNC_SERVICE.try_begin(mode) {
+        return false;
+    }
+    match SYNC_SERVICE.build_transport() {
+        Ok((transport, athlete_id)) => {
+            let fut = job(transport, athlete_id);
+            crate::runtime::spawn(async move {
+                // Release the running slot even if the job panics (tokio
+                // catches the panic, but a skipped finish() would leave
+                // state=Syncing and try_begin() refusing every future sync for
+                // the session).
+                struct FinishGuard(SyncMode);
+                impl Drop for FinishGuard {
+                    fn drop(&mut self) {
+                        if std::thread::panicking() {
+                            SYNC_SERVICE.finish(
+                                self.0,
+                                SyncState::Idle,
+                                Some("sync task panicked".to_string()),
+                                false,
+                            );
+                        }
+                    }
+                }
+                let _guard = FinishGuard(mode);
+                fut.await;
+            });
+            true
+        }
+        Err(e) => {
+            SYNC_SERVICE.finish(mode, SyncState::Idle, Some(e), false);
+            false
+        }
+    }
+}
+
+/// The FFI service object. The single thing TypeScript calls for I/O.
+#[derive(uniffi::Object)]
+pub struct SyncManager {
+    pub(crate) _private: (),
+}
+
+#[uniffi::export]
+impl SyncManager {
+    #[uniffi::constructor]
+    fn new() -> Arc<Self> {
+        Arc::new(Self { _private: () })
+    }
+
+    /// Set the credential once (method = "oauth" | "api_key"). Never passed per request.
+    fn set_credentials(
+        &self,
+        method: String,
+        secret: String,
+        athlete_id: String,
+    ) -> Result<(), VeloqError> {
+        let kind = AuthKind::parse(&method).ok_or(VeloqError::ParseError {
+            msg: format!("unknown auth method: {}", method),
+        })?;
+        SYNC_SERVICE.set_credentials(kind, secret, athlete_id);
+        Ok(())
+    }
+
+    /// Enable transparent token refresh for the OAuth credential set via
+    /// `set_credentials`. On a 401 the service exchanges `refresh_token` at
+    /// `token_endpoint` and replays the request; `authExpired` is only
+    /// reported when that exchange is rejected.
+    fn set_oauth_refresh(
+        &self,
+        refresh_token: String,
+        token_endpoint: String,
+    ) -> Result<(), VeloqError> {
+        SYNC_SERVICE
+            .set_oauth_refresh(refresh_token, token_endpoint)
+            .map_err(|msg| VeloqError::ParseError { msg })
+    }
+
+    /// The current OAuth token pair, or None without a refresh-enabled session.
+    fn get_oauth_tokens(&self) -> Option<FfiOAuthTokens> {
+        SYNC_SERVICE.oauth_tokens().map(|t| FfiOAuthTokens {
+            access_token: t.access_token,
+            refresh_token: t.refresh_token,
+        })
+    }
+
+    /// Serve every sync from recorded responses (a fixture directory or ZIP of
+    /// one) for `athlete_id`, without network or credentials. Used for the
+    /// offline demo athlete. The user's engine is set aside until
+    /// `disable_demo_mode`; every engine call meanwhile reads and writes a
+    /// throwaway demo database. Fails while a sync is running.
+    fn enable_demo_mode(
+        &self,
+        fixtures_path: String,
+        athlete_id: String,
+    ) -> Result<(), VeloqError> {
+        let fixtures =
+            Fixtures::open(&fixtures_path).map_err(|msg| VeloqError::NotFound { msg })?;
+        log::info!(
+            "tracematch: [Sync] Demo mode with {} recorded responses",
+            fixtures.len()
+        );
+        SYNC_SERVICE
+            .switch_demo(
+                Some((Arc::new(fixtures), athlete_id)),
+                &DEMO_ENGINE_SWAP,
+                &PERSISTENT_ENGINE,
+            )
+            .map_err(|msg| VeloqError::Database { msg })
+    }
+
+    /// Return to live syncing with the held credentials and the user's own
+    /// engine. The demo database is deleted.
+    fn disable_demo_mode(&self) -> Result<(), VeloqError> {
+        SYNC_SERVICE
+            .switch_demo(None, &DEMO_ENGINE_SWAP, &PERSISTENT_ENGINE)
+            .map_err(|msg| VeloqError::Database { msg })
+    }
+
+    /// Record live response bodies (never headers) into `dir` for later
+    /// replay, or stop recording with None.
+    fn set_recording_dir(&self, dir: Option<String>) -> Result<(), VeloqError> {
+        let recorder = match dir {
+            Some(d) => Some(Arc::new(
+                Recorder::new(&d).map_err(|msg| VeloqError::Database { msg })?,
+            )),
+            None => None,
+        };
+        SYNC_SERVICE.set_recorder(recorder);
+        Ok(())
+    }
+
+    /// Forget the credential (logout) and the cached responses fetched with it.
+    fn clear_credentials(&self) {
+        SYNC_SERVICE.clear_credentials();
+        if let Some(cache) = crate::net::transport::cache::shared() {
+            cache.clear();
+        }
+    }
+
+    /// Start a sync. Returns instantly: true if a new sync started, false if one
+    /// was already running or credentials are missing. A running backfill does
+    /// not block it. Work runs on the shared
+    /// runtime; observe progress via `get_sync_status`.
+    fn sync_now(&self) -> Result<bool, VeloqError> {
+        Ok(start_job(
+            SyncMode::Incremental,
+            |transport, athlete_id| async move {
+                perform_sync(&SYNC_SERVICE, transport, athlete_id, &GlobalEngine).await
+            },
+        ))
+    }
+
+    /// Start (or resume) the historical backfill: walks activities backwards a
+    /// month at a time on the backfill lane, from the persisted cursor down to
+    /// the athlete's oldest activity. Same return contract as `sync_now`;
+    /// status reports months (`completed` / `total`) with `mode = "backfill"`.
+    fn start_backfill(&self) -> Result<bool, VeloqError> {
+        Ok(start_job(
+            SyncMode::Backfill,
+            |transport, athlete_id| async move {
+                perform_backfill(&SYNC_SERVICE, transport, athlete_id, &GlobalEngine).await
+            },
+        ))
+    }
+
+    /// Days of already-stored wellness each sync re-fetches so late edits are
+    /// caught. Persisted; defaults to 7.
+    fn set_wellness_overlap_days(&self, days: u32) -> Result<(), VeloqError> {
+        with_engine(|e| {
+            e.set_setting(settings_keys::SYNC_WELLNESS_OVERLAP_DAYS, &days.to_string())
+                .map_err(|err| VeloqError::Database {
+                    msg: format!("{}", err),
+                })
+        })?
+    }
+
+    /// Soft-cancel the running sync.
+    fn cancel(&self) {
+        SYNC_SERVICE.request_cancel();
+    }
+
+    /// Current status snapshot.
+    fn get_sync_status(&self) -> FfiSyncStatus {
+        SYNC_SERVICE.snapshot()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governor::{Governor, NoopPolicy};
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    pub(super) fn transport_to(base: String) -> Transport {
+        let gov = Arc::new(Governor::new(1000, Box::new(NoopPolicy)));
+        Transport::with_governor(base, AuthMethod::ApiKey("k"), gov).unwrap()
+    }
+
+    impl SyncStore for Mutex<PersistentRouteEngine> {
+        fn with_engine<R>(&self, f: impl FnOnce(&mut PersistentRouteEngine) -> R) -> Option<R> {
+            Some(f(&mut self.lock().unwrap_or_else(|e| e.into_inner())))
+        }
+    }
+
+    impl SyncStore for RwLock<Option<PersistentRouteEngine>> {
+        fn with_engine<R>(&self, f: impl FnOnce(&mut PersistentRouteEngine) -> R) -> Option<R> {
+            self.write()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_mut()
+                .map(f)
+        }
+    }
+
+    pub(super) fn test_store() -> Mutex<PersistentRouteEngine> {
+        Mutex::new(PersistentRouteEngine::in_memory().unwrap())
+    }
+
+    pub(super) fn mock_empty_wellness(server: &MockServer) {
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/wellness");
+            then.status(200).json_body(json!([]));
+        });
+    }
+
+    pub(super) fn today() -> String {
+        Local::now().date_naive().format("%Y-%m-%d").to_string()
+    }
+
+    fn days_ago(days: u64) -> String {
+        (Local::now().date_naive() - Days::new(days))
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+
+    /// Id-only reconcile listing with nothing deleted, for tests whose page
+    /// mocks match one window only.
+    fn mock_empty_reconcile(server: &MockServer) {
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("fields", "id");
+            then.status(200).json_body(json!([]));
+        });
+    }
+
+    #[test]
+    fn fresh_service_is_idle() {
+        let svc = SyncService::new();
+        let s = svc.snapshot();
+        assert_eq!(s.state, "idle");
+        assert_eq!(s.in_flight, 0);
+        assert!(s.last_error.is_none());
+    }
+
+    #[test]
+    fn try_begin_is_exclusive() {
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        assert_eq!(svc.snapshot().state, "syncing");
+        // Second begin while running is rejected.
+        assert!(!svc.try_begin(SyncMode::Incremental));
+    }
+
+    #[test]
+    fn build_transport_requires_credentials() {
+        let svc = SyncService::new();
+        assert!(svc.build_transport().is_err());
+        svc.set_credentials(AuthKind::ApiKey, "secret".into(), "i1".into());
+        let (_t, athlete) = svc.build_transport().unwrap();
+        assert_eq!(athlete, "i1");
+    }
+
+    #[test]
+    fn successful_sync_returns_to_idle_completed() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([
+                {"id": "a1", "name": "Loop", "type": "Ride", "start_date_local": "2026-06-20T07:00:00",
+                 "moving_time": 3600, "distance": 30000.0, "stream_types": ["time", "latlng"]},
+                {"id": "a2", "name": "Trainer", "type": "VirtualRide", "start_date_local": "2026-06-21T07:00:00",
+                 "moving_time": 1800, "stream_types": ["time", "watts"]}
+            ]));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/activity/a1/streams.json");
+            then.status(200).json_body(json!([
+                {"type": "latlng", "data": [46.50, 46.51, 46.52], "data2": [6.60, 6.61, 6.62]},
+                {"type": "time", "data": [0, 10, 20]}
+            ]));
+        });
+        let store = test_store();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        let s = svc.snapshot();
+        assert_eq!(s.state, "idle");
+        assert_eq!(s.in_flight, 0);
+        assert!(s.last_error.is_none());
+
+        let engine = store.lock().unwrap();
+        assert!(engine.has_activity("a1"));
+        assert!(!engine.has_activity("a2"), "no latlng stream listed");
+        assert!(engine.get_activity_metrics("a2").is_some());
+        assert!(engine.has_time_stream("a1"));
+        assert_eq!(
+            engine
+                .get_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK)
+                .unwrap(),
+            Some(today())
+        );
+    }
+
+    #[test]
+    fn sync_stores_sensor_streams_once() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([
+                {"id": "a2", "type": "VirtualRide", "start_date_local": "2026-06-21T07:00:00",
+                 "stream_types": ["time", "watts", "heartrate"]},
+                {"id": "m1", "type": "Workout", "stream_types": []}
+            ]));
+        });
+        let streams = server.mock(|when, then| {
+            when.method(GET).path("/activity/a2/streams.json");
+            then.status(200).json_body(json!([
+                {"type": "time", "data": [0, 1, 2]},
+                {"type": "watts", "data": [200, null, 220]},
+                {"type": "heartrate", "data": [130, 131, 133]}
+            ]));
+        });
+        let store = test_store();
+        for _ in 0..2 {
+            store
+                .lock()
+                .unwrap()
+                .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+                .unwrap();
+            let svc = SyncService::new();
+            assert!(svc.try_begin(SyncMode::Incremental));
+            crate::runtime::block_on(perform_sync(
+                &svc,
+                transport_to(server.base_url()),
+                "i1".into(),
+                &store,
+            ));
+        }
+        // The second run finds the streams already stored.
+        streams.assert_hits(1);
+
+        let engine = store.lock().unwrap();
+        assert!(!engine.has_activity("a2"));
+        let watts = engine.get_activity_stream("a2", "watts").unwrap().unwrap();
+        assert_eq!(watts[0], 200.0);
+        assert!(watts[1].is_nan());
+        assert_eq!(
+            engine.get_activity_stream("a2", "heartrate").unwrap(),
+            Some(vec![130.0, 131.0, 133.0])
+        );
+        assert!(!engine.has_activity_streams("m1").unwrap());
+    }
+
+    #[test]
+    fn empty_stream_download_is_not_repeated() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([
+                {"id": "e1", "type": "Ride", "start_date_local": "2026-06-21T07:00:00",
+                 "stream_types": ["time", "watts"]}
+            ]));
+        });
+        let streams = server.mock(|when, then| {
+            when.method(GET).path("/activity/e1/streams.json");
+            then.status(200).json_body(json!([]));
+        });
+        let store = test_store();
+        for _ in 0..2 {
+            store
+                .lock()
+                .unwrap()
+                .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+                .unwrap();
+            let svc = SyncService::new();
+            assert!(svc.try_begin(SyncMode::Incremental));
+            crate::runtime::block_on(perform_sync(
+                &svc,
+                transport_to(server.base_url()),
+                "i1".into(),
+                &store,
+            ));
+        }
+        streams.assert_hits(1);
+        let engine = store.lock().unwrap();
+        assert!(engine.has_fetched_streams("e1").unwrap());
+        assert!(!engine.has_activity_streams("e1").unwrap());
+    }
+
+    #[test]
+    fn progress_counts_listed_activities() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([
+                {"id": "a1", "stream_types": ["watts"]},
+                {"id": "a2", "stream_types": ["watts"]}
+            ]));
+        });
+        let store = test_store();
+        store
+            .lock()
+            .unwrap()
+            .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+            .unwrap();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        let s = svc.snapshot();
+        // Two activities plus the wellness and curve stages.
+        assert_eq!(s.total, 4);
+        assert_eq!(s.completed, 4);
+    }
+
+    #[test]
+    fn curves_are_stored_and_refetched_only_after_new_activities() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([
+                {"id": "a1", "type": "GravelRide", "stream_types": []}
+            ]));
+        });
+        let power = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/power-curves.json")
+                .query_param("type", "Ride");
+            then.status(200).json_body(json!({"list": [
+                {"secs": [5, 60, 300], "values": [800, 420, 310], "activity_id": ["a1", "a1", "a1"]}
+            ]}));
+        });
+        let pace = server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/pace-curves.json");
+            then.status(200).json_body(json!({"list": []}));
+        });
+        let store = test_store();
+        for _ in 0..2 {
+            store
+                .lock()
+                .unwrap()
+                .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+                .unwrap();
+            let svc = SyncService::new();
+            assert!(svc.try_begin(SyncMode::Incremental));
+            crate::runtime::block_on(perform_sync(
+                &svc,
+                transport_to(server.base_url()),
+                "i1".into(),
+                &store,
+            ));
+            assert!(svc.snapshot().last_error.is_none());
+        }
+        // One fetch per window on the first run; the second run lists no new
+        // activity and every window is already stored.
+        power.assert_hits(CURVE_WINDOWS.len());
+        pace.assert_hits(0);
+
+        let engine = store.lock().unwrap();
+        for window in CURVE_WINDOWS {
+            assert_eq!(
+                engine.best_power_at("Ride", window, 60.0).unwrap(),
+                Some(420.0)
+            );
+        }
+    }
+
+    #[test]
+    fn first_sync_pages_the_initial_window() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        let list = server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([]));
+        });
+        let store = test_store();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        // 90 days back to today inclusive, in 30-day pages, then the id-only
+        // reconcile listing.
+        list.assert_hits(5);
+        assert_eq!(svc.snapshot().state, "idle");
+    }
+
+    #[test]
+    fn sync_resumes_from_watermark() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        mock_empty_reconcile(&server);
+        let since = days_ago(3);
+        let oldest = days_ago(3 + DEFAULT_ACTIVITIES_OVERLAP_DAYS);
+        let list = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("oldest", oldest.as_str())
+                .query_param("newest", today().as_str());
+            then.status(200).json_body(json!([]));
+        });
+        let store = test_store();
+        store
+            .lock()
+            .unwrap()
+            .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &since)
+            .unwrap();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        list.assert_hits(1);
+        assert!(svc.snapshot().last_error.is_none());
+    }
+
+    #[test]
+    fn late_upload_behind_the_watermark_is_picked_up() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        mock_empty_reconcile(&server);
+        let list = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("oldest", days_ago(DEFAULT_ACTIVITIES_OVERLAP_DAYS).as_str())
+                .query_param("newest", today().as_str());
+            then.status(200).json_body(json!([
+                {"id": "late", "type": "Ride", "start_date_local": format!("{}T07:00:00", days_ago(2)),
+                 "stream_types": []}
+            ]));
+        });
+        let store = test_store();
+        store
+            .lock()
+            .unwrap()
+            .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+            .unwrap();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        list.assert_hits(1);
+        assert!(svc.snapshot().last_error.is_none());
+        let engine = store.lock().unwrap();
+        assert!(engine.get_activity_metrics("late").is_some());
+        assert_eq!(
+            engine
+                .get_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK)
+                .unwrap(),
+            Some(today())
+        );
+    }
+
+    #[test]
+    fn failed_page_keeps_watermark() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(401);
+        });
+        let store = test_store();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        let engine = store.lock().unwrap();
+        assert_eq!(
+            engine
+                .get_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn failed_stream_download_holds_watermark() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([
+                {"id": "a1", "type": "Ride", "start_date_local": "2026-01-10T07:00:00",
+                 "stream_types": ["time", "watts"]}
+            ]));
+        });
+        let mut failing = server.mock(|when, then| {
+            when.method(GET).path("/activity/a1/streams.json");
+            then.status(404);
+        });
+        let store = test_store();
+        store
+            .lock()
+            .unwrap()
+            .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, "2026-01-01")
+            .unwrap();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        assert_eq!(
+            store
+                .lock()
+                .unwrap()
+                .get_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK)
+                .unwrap()
+                .as_deref(),
+            Some("2026-01-10")
+        );
+
+        // The next run re-lists the failed day, retries and moves on.
+        failing.delete();
+        let streams = server.mock(|when, then| {
+            when.method(GET).path("/activity/a1/streams.json");
+            then.status(200).json_body(json!([
+                {"type": "time", "data": [0, 1, 2]},
+                {"type": "watts", "data": [200, 210, 220]}
+            ]));
+        });
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        streams.assert_hits(1);
+        let engine = store.lock().unwrap();
+        assert!(engine.has_activity_streams("a1").unwrap());
+        assert_eq!(
+            engine
+                .get_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK)
+                .unwrap(),
+            Some(today())
+        );
+    }
+
+    #[test]
+    fn wellness_stage_upserts_rows() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([]));
+        });
+        let first = (Local::now().date_naive() - Days::new(INITIAL_WELLNESS_DAYS))
+            .format("%Y-%m-%d")
+            .to_string();
+        let wellness = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/wellness")
+                .query_param("oldest", first.as_str());
+            then.status(200).json_body(json!([
+                {"id": "2026-06-01", "ctl": 50.0, "atl": 60.0, "hrv": 55.0, "mood": 3},
+                {"id": "2026-06-02", "ctlLoad": 51.0, "atlLoad": 58.0, "hrv": 57.0}
+            ]));
+        });
+        let store = test_store();
+        store
+            .lock()
+            .unwrap()
+            .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+            .unwrap();
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        wellness.assert_hits(1);
+        assert!(svc.snapshot().last_error.is_none());
+        let engine = store.lock().unwrap();
+        let rows = engine.get_wellness_window(30).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].mood, Some(3));
+        assert_eq!(rows[1].ctl, Some(51.0), "ctlLoad fills a missing ctl");
+        assert!(engine.get_wellness_sparklines(30).unwrap().is_some());
+    }
+
+    #[test]
+    fn wellness_refetches_configured_overlap() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200).json_body(json!([]));
+        });
+        let wellness = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/wellness")
+                .query_param("oldest", "2026-05-29");
+            then.status(200).json_body(json!([]));
+        });
+        let store = test_store();
+        {
+            let mut engine = store.lock().unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+                .unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_WELLNESS_OVERLAP_DAYS, "3")
+                .unwrap();
+            engine
+                .upsert_wellness(&[wellness_row_from_record(&WellnessRecord {
+                    id: "2026-06-01".into(),
+                    ..Default::default()
+                })])
+                .unwrap();
+        }
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        wellness.assert_hits(1);
+        assert_eq!(svc.snapshot().state, "idle");
+    }
+
+    #[test]
+    fn sync_purges_activities_missing_from_server() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200)
+                .json_body(json!([{"id": "kept", "stream_types": ["watts"]}]));
+        });
+        let store = test_store();
+        {
+            let mut engine = store.lock().unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+                .unwrap();
+            let noon = Local::now().date_naive().and_hms_opt(12, 0, 0).unwrap();
+            let date = noon
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap()
+                .timestamp();
+            let record = |id: &str| ActivityRecord {
+                id: id.into(),
+                ..Default::default()
+            };
+            engine
+                .set_activity_metrics_extended(vec![
+                    FfiActivityMetrics {
+                        date,
+                        ..metrics_from_record(&record("kept"))
+                    },
+                    FfiActivityMetrics {
+                        date,
+                        ..metrics_from_record(&record("deleted"))
+                    },
+                ])
+                .unwrap();
+        }
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        assert!(svc.snapshot().last_error.is_none());
+        let engine = store.lock().unwrap();
+        assert!(engine.get_activity_metrics("kept").is_some());
+        assert!(engine.get_activity_metrics("deleted").is_none());
+    }
+
+    #[test]
+    fn trailing_reconcile_purges_deletions_behind_the_watermark() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("oldest", days_ago(DEFAULT_ACTIVITIES_OVERLAP_DAYS).as_str());
+            then.status(200).json_body(json!([]));
+        });
+        let ids = server.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .query_param("oldest", days_ago(DEFAULT_RECONCILE_DAYS).as_str())
+                .query_param("fields", "id");
+            then.status(200).json_body(json!([{"id": "kept"}]));
+        });
+        let store = test_store();
+        {
+            let mut engine = store.lock().unwrap();
+            engine
+                .set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, &today())
+                .unwrap();
+            // Well behind the watermark overlap, inside the reconcile window
+            let noon = (Local::now().date_naive() - Days::new(40))
+                .and_hms_opt(12, 0, 0)
+                .unwrap();
+            let date = noon
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap()
+                .timestamp();
+            let record = |id: &str| ActivityRecord {
+                id: id.into(),
+                ..Default::default()
+            };
+            engine
+                .set_activity_metrics_extended(vec![
+                    FfiActivityMetrics {
+                        date,
+                        ..metrics_from_record(&record("kept"))
+                    },
+                    FfiActivityMetrics {
+                        date,
+                        ..metrics_from_record(&record("deleted"))
+                    },
+                ])
+                .unwrap();
+        }
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &store,
+        ));
+        assert!(svc.snapshot().last_error.is_none());
+        ids.assert_hits(1);
+        let engine = store.lock().unwrap();
+        assert!(engine.get_activity_metrics("kept").is_some());
+        assert!(engine.get_activity_metrics("deleted").is_none());
+    }
+
+    #[test]
+    fn metrics_mirror_ts_conversion() {
+        let record: ActivityRecord = serde_json::from_value(json!({
+            "id": "a1", "name": "Ride", "type": "Ride", "start_date_local": "not a date",
+            "moving_time": 3600, "average_heartrate": 141.6, "average_watts": 201.2,
+            "icu_zone_times": [{"id": "Z1", "secs": 600}, {"id": "Z2", "secs": 1200}],
+            "icu_hr_zone_times": [300, 600, 1200, 900, 100]
+        }))
+        .unwrap();
+        let m = metrics_from_record(&record);
+        assert_eq!(m.date, 0);
+        assert_eq!(m.distance, 0.0);
+        assert_eq!(m.avg_hr, Some(142));
+        assert_eq!(m.avg_power, Some(201));
+        assert_eq!(m.power_zone_times, Some(vec![600, 1200]));
+        assert_eq!(m.hr_zone_times, Some(vec![300, 600, 1200, 900, 100]));
+
+        let bare = metrics_from_record(&ActivityRecord {
+            id: "a2".into(),
+            ..Default::default()
+        });
+        assert_eq!(bare.sport_type, "Ride");
+        assert!(bare.power_zone_times.is_none());
+    }
+
+    #[test]
+    fn misaligned_time_stream_is_dropped() {
+        let streams = ParsedStreams {
+            latlng: vec![[46.5, 6.6], [46.51, 6.61], [200.0, 6.62]],
+            time: vec![0, 10, 20],
+            ..Default::default()
+        };
+        let (coords, times) = gps_from_streams(&streams).unwrap();
+        assert_eq!(coords.len(), 2);
+        assert!(times.is_none());
+    }
+
+    #[test]
+    fn unauthorized_sync_moves_to_auth_expired() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(401);
+        });
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &test_store(),
+        ));
+        let s = svc.snapshot();
+        assert_eq!(s.state, "authExpired");
+        assert_eq!(s.completed, 0);
+        assert_eq!(s.last_error.as_deref(), Some("unauthorized"));
+    }
+
+    fn oauth_service(token: &MockServer, api: &MockServer) -> SyncService {
+        let svc = SyncService::new();
+        svc.set_credentials(AuthKind::OAuth, "old".into(), "i1".into());
+        svc.set_oauth_refresh("r0".into(), token.url("/oauth/refresh"))
+            .unwrap();
+        *svc.base_url.lock().unwrap() = api.base_url();
+        svc
+    }
+
+    #[test]
+    fn set_oauth_refresh_requires_oauth_credentials() {
+        let svc = SyncService::new();
+        assert!(
+            svc.set_oauth_refresh("r".into(), "http://x".into())
+                .is_err()
+        );
+        svc.set_credentials(AuthKind::ApiKey, "k".into(), "i1".into());
+        assert!(
+            svc.set_oauth_refresh("r".into(), "http://x".into())
+                .is_err()
+        );
+        svc.set_credentials(AuthKind::OAuth, "a".into(), "i1".into());
+        assert!(svc.set_oauth_refresh("r".into(), "http://x".into()).is_ok());
+        assert_eq!(svc.oauth_tokens().unwrap().access_token, "a");
+        // New credentials drop the refresher bound to the old session.
+        svc.set_credentials(AuthKind::OAuth, "b".into(), "i1".into());
+        assert!(svc.oauth_tokens().is_none());
+    }
+
+    #[test]
+    fn expired_token_is_refreshed_and_sync_completes() {
+        let api = MockServer::start();
+        let token = MockServer::start();
+        mock_empty_wellness(&api);
+        // Registered first so requests carrying the new token match it.
+        api.mock(|when, then| {
+            when.method(GET)
+                .path("/athlete/i1/activities")
+                .header("authorization", "Bearer new");
+            then.status(200).json_body(json!([]));
+        });
+        api.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(401);
+        });
+        let refresh = token.mock(|when, then| {
+            when.method(POST).path("/oauth/refresh");
+            then.status(200)
+                .json_body(json!({"access_token": "new", "refresh_token": "r1"}));
+        });
+        let svc = oauth_service(&token, &api);
+        let (transport, athlete) = svc.build_transport().unwrap();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(&svc, transport, athlete, &test_store()));
+
+        let s = svc.snapshot();
+        assert_eq!(s.state, "idle");
+        assert!(s.last_error.is_none());
+        refresh.assert_hits(1);
+        let tokens = svc.oauth_tokens().unwrap();
+        assert_eq!(tokens.access_token, "new");
+        assert_eq!(tokens.refresh_token, "r1");
+    }
+
+    #[test]
+    fn rejected_refresh_moves_to_auth_expired() {
+        let api = MockServer::start();
+        let token = MockServer::start();
+        api.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(401);
+        });
+        token.mock(|when, then| {
+            when.method(POST).path("/oauth/refresh");
+            then.status(400)
+                .json_body(json!({"error": "invalid_grant"}));
+        });
+        let svc = oauth_service(&token, &api);
+        let (transport, athlete) = svc.build_transport().unwrap();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(&svc, transport, athlete, &test_store()));
+
+        let s = svc.snapshot();
+        assert_eq!(s.state, "authExpired");
+        assert_eq!(s.last_error.as_deref(), Some("unauthorized"));
+    }
+
+    fn demo_fixtures() -> Arc<Fixtures> {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/replay_demo");
+        Arc::new(Fixtures::open(dir).unwrap())
+    }
+
+    #[test]
+    fn demo_mode_syncs_from_recorded_fixtures() {
+        let svc = SyncService::new();
+        svc.set_demo(Some((demo_fixtures(), "i1".into())));
+        // No credentials and an unreachable base URL: everything is replayed.
+        *svc.base_url.lock().unwrap() = "http://127.0.0.1:9".into();
+        let (transport, athlete) = svc.build_transport().unwrap();
+        let store = test_store();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(&svc, transport, athlete, &store));
+
+        let s = svc.snapshot();
+        assert_eq!(s.state, "idle");
+        assert!(s.last_error.is_none(), "{:?}", s.last_error);
+        let engine = store.lock().unwrap();
+        assert!(engine.has_activity("demo1"));
+        assert!(!engine.has_activity("demo2"));
+        assert_eq!(
+            engine.get_activity_stream("demo2", "heartrate").unwrap(),
+            Some(vec![120.0, 131.0, 138.0, 140.0])
+        );
+        assert_eq!(
+            engine.latest_wellness_date().unwrap().as_deref(),
+            Some("2026-06-21")
+        );
+        assert_eq!(
+            engine.best_power_at("Ride", "42d", 60.0).unwrap(),
+            Some(380.0)
+        );
+    }
+
+    #[test]
+    fn demo_session_leaves_real_engine_untouched() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db_path = dir.path().join("veloq.db");
+        let slot = RwLock::new(Some(
+            PersistentRouteEngine::new(&db_path.to_string_lossy()).unwrap(),
+        ));
+        let real: ActivityRecord = serde_json::from_value(json!({
+            "id": "real1", "type": "Ride", "start_date_local": "2026-06-20T07:00:00"
+        }))
+        .unwrap();
+        slot.with_engine(|e| {
+            e.set_activity_metrics_extended(vec![metrics_from_record(&real)])
+                .unwrap();
+            e.set_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK, "2026-06-01")
+                .unwrap();
+        });
+
+        let svc = SyncService::new();
+        let swap = DemoEngineSwap::new();
+        svc.switch_demo(Some((demo_fixtures(), "i1".into())), &swap, &slot)
+            .unwrap();
+        assert!(swap.is_active());
+        *svc.base_url.lock().unwrap() = "http://127.0.0.1:9".into();
+        let (transport, athlete) = svc.build_transport().unwrap();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        // No swap while a job holds a slot.
+        assert!(svc.switch_demo(None, &swap, &slot).is_err());
+        crate::runtime::block_on(perform_sync(&svc, transport, athlete, &slot));
+        assert!(svc.snapshot().last_error.is_none());
+        slot.with_engine(|e| {
+            assert!(e.has_activity("demo1"));
+            assert!(e.get_activity_metrics("real1").is_none());
+        });
+
+        svc.switch_demo(None, &swap, &slot).unwrap();
+        assert!(!swap.is_active());
+        assert!(!dir.path().join("veloq_demo.db").exists());
+        slot.with_engine(|e| {
+            assert!(e.get_activity_metrics("real1").is_some());
+            assert!(!e.has_activity("demo1"));
+            assert!(e.get_activity_metrics("demo2").is_none());
+            assert_eq!(
+                e.get_setting(settings_keys::SYNC_ACTIVITIES_WATERMARK)
+                    .unwrap()
+                    .as_deref(),
+                Some("2026-06-01")
+            );
+        });
+    }
+
+    #[test]
+    fn recording_captures_live_bodies_for_replay() {
+        let server = MockServer::start();
+        mock_empty_wellness(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(200)
+                .json_body(json!([{"id": "a1", "stream_types": []}]));
+        });
+        let dir = tempfile::TempDir::new().unwrap();
+        let svc = SyncService::new();
+        svc.set_credentials(AuthKind::ApiKey, "secret".into(), "i1".into());
+        svc.set_recorder(Some(Arc::new(
+            Recorder::new(dir.path().to_str().unwrap()).unwrap(),
+        )));
+        *svc.base_url.lock().unwrap() = server.base_url();
+        let (transport, athlete) = svc.build_transport().unwrap();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(&svc, transport, athlete, &test_store()));
+
+        let replayed = Fixtures::open(dir.path().to_str().unwrap()).unwrap();
+        assert!(
+            replayed
+                .lookup("/athlete/i1/activities", &[])
+                .is_some_and(|b| String::from_utf8_lossy(b).contains("a1"))
+        );
+        assert!(replayed.lookup("/athlete/i1/wellness", &[]).is_some());
+    }
+
+    #[test]
+    fn server_error_records_error_but_returns_idle() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(500);
+        });
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &test_store(),
+        ));
+        let s = svc.snapshot();
+        assert_eq!(s.state, "idle");
+        assert_eq!(s.completed, 0);
+        assert!(s.last_error.is_some());
+    }
+
+    #[test]
+    fn cancel_before_run_skips_work() {
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        svc.request_cancel();
+        assert!(svc.is_cancelled(SyncMode::Incremental));
+        assert_eq!(svc.snapshot().state, "paused");
+        // A mock that would panic the assertion if hit is unnecessary: a cancelled
+        // job finishes without dispatching. Point at an unroutable base; the job
+        // must not touch it.
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to("http://127.0.0.1:1".into()),
+            "i1".into(),
+            &test_store(),
+        ));
+        assert_eq!(svc.snapshot().state, "idle");
+    }
+
+    #[test]
+    fn set_and_clear_credentials_round_trip() {
+        let svc = SyncService::new();
+        svc.set_credentials(AuthKind::OAuth, "tok".into(), "i9".into());
+        assert!(svc.build_transport().is_ok());
+        svc.clear_credentials();
+        assert!(svc.build_transport().is_err());
+    }
+
+    #[test]
+    fn auth_kind_parsing() {
+        assert_eq!(AuthKind::parse("oauth"), Some(AuthKind::OAuth));
+        assert_eq!(AuthKind::parse("API_KEY"), Some(AuthKind::ApiKey));
+        assert_eq!(AuthKind::parse("nonsense"), None);
+    }
+
+    #[test]
+    fn try_begin_is_exclusive_under_contention() {
+        // Race many threads on one service. The running slot is the lock that
+        // stops two concurrent syncs, so exactly one caller may claim it.
+        use std::sync::atomic::{AtomicU32, Ordering};
+        let svc = Arc::new(SyncService::new());
+        let winners = Arc::new(AtomicU32::new(0));
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let svc = svc.clone();
+                let winners = winners.clone();
+                std::thread::spawn(move || {
+                    if svc.try_begin(SyncMode::Incremental) {
+                        winners.fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(winners.load(Ordering::Relaxed), 1);
+        assert_eq!(svc.snapshot().state, "syncing");
+    }
+
+    #[test]
+    fn backfill_does_not_block_incremental() {
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Backfill));
+        svc.add_total(SyncMode::Backfill, 12);
+        assert!(svc.try_begin(SyncMode::Incremental));
+        assert!(!svc.try_begin(SyncMode::Backfill));
+        assert_eq!(svc.snapshot().mode, "incremental");
+
+        // When the incremental job ends, status returns to the backfill.
+        svc.finish(SyncMode::Incremental, SyncState::Idle, None, true);
+        let s = svc.snapshot();
+        assert_eq!((s.mode.as_str(), s.state.as_str()), ("backfill", "syncing"));
+        assert_eq!(s.total, 12);
+        svc.finish(SyncMode::Backfill, SyncState::Idle, None, true);
+        assert_eq!(svc.snapshot().state, "idle");
+    }
+
+    #[test]
+    fn begin_after_cancel_clears_cancel_flag() {
+        // A soft-cancel must not persist into the next sync, or every future run
+        // would bail immediately at the is_cancelled() gate.
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        svc.request_cancel();
+        svc.finish(SyncMode::Incremental, SyncState::Idle, None, false);
+        assert!(
+            svc.is_cancelled(SyncMode::Incremental),
+            "the flag survives until the next begin consumes it"
+        );
+        assert!(svc.try_begin(SyncMode::Incremental));
+        assert!(
+            !svc.is_cancelled(SyncMode::Incremental),
+            "a fresh begin clears the prior cancellation"
+        );
+    }
+
+    #[test]
+    fn auth_expired_recovers_on_next_begin() {
+        // After a 401 the service rests in authExpired. Once TypeScript re-auths
+        // and issues sync_now again, try_begin moves it back into syncing.
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/athlete/i1/activities");
+            then.status(401);
+        });
+        let svc = SyncService::new();
+        assert!(svc.try_begin(SyncMode::Incremental));
+        crate::runtime::block_on(perform_sync(
+            &svc,
+            transport_to(server.base_url()),
+            "i1".into(),
+            &test_store(),
+        ));
+        assert_eq!(svc.snapshot().state, "authExpired");
+        assert!(svc.try_begin(SyncMode::Incremental));
+        assert_eq!(svc.snapshot().state, "syncing");
+    }
+}
@@ -3,6 +3,7 @@
 use crate::{ActivityMatchInfo, ActivityMetrics, Bounds, GpsPoint, RouteSignature};
 use rstar::{AABB, RTree};
 use rusqlite::{Result as SqlResult, params, types::Type};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use super::codec;
@@ -298,10 +299,113 @@ impl PersistentRouteEngine {
         Ok(())
     }
 
+    /// Purge activities that were deleted or merged away on intervals.icu.
+    ///
+    /// `remove_activity` drops the `activities` row and its cascades, but the
+    /// rows keyed by activity id without a foreign key (section traversals,
+    /// metrics, time streams, indicators, FIT status) would otherwise outlive
+    /// it. Affected sections are refreshed from SQLite, route groups regroup
+    /// and indicators are recomputed so no ghost effort stays on a
+    /// leaderboard. Returns the number of activities that were known locally.
+    pub fn purge_deleted_activities(&mut self, ids: &[String]) -> SqlResult<u32> {
+        let known: Vec<&String> = ids
+            .iter()
+            .filter(|id| {
+                self.activity_metadata.contains_key(*id) || self.activity_metrics.contains_key(*id)
+            })
+            .collect();
+        if known.is_empty() {
+            return Ok(0);
+        }
+
+        let mut had_tracks = false;
+        for id in &known {
+            if self.activity_metadata.contains_key(*id) {
+                self.remove_activity(id)?;
+                had_tracks = true;
+            }
+        }
+
+        let mut affected_sections: HashSet<String> = HashSet::new();
+        let tx = self.db.unchecked_transaction()?;
+        for id in &known {
+            {
+                let mut stmt = tx.prepare(
+                    "SELECT DISTINCT section_id FROM section_activities WHERE activity_id = ?",
+                )?;
+                let rows = stmt.query_map(params![id], |r| r.get::<_, String>(0))?;
+                for row in rows {
+                    affected_sections.insert(row?);
+                }
+            }
+            if let Some(m) = self.activity_metrics.get(*id) {
+                let date_str = chrono::DateTime::from_timestamp(m.date, 0)
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                tx.execute(
+                    "UPDATE activity_heatmap SET activity_count = activity_count - 1 WHERE date = ?",
+                    params![date_str],
+                )?;
+            }
+            for table in [
+                "section_activities",
+                "activity_matches",
+                "activity_metrics",
+                "time_streams",
+                "activity_streams",
+                "activity_best_efforts",
+                "best_effort_scans",
+                "activity_zone_times",
+                "activity_indicators",
+                "route_overrides",
+                "processed_activities",
+                "ftp_history",
+                "exercise_sets",
+                "fit_file_status",
+            ] {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE activity_id = ?", table),
+                    params![id],
+                )?;
+            }
+        }
+        tx.execute("DELETE FROM activity_heatmap WHERE activity_count <= 0", [])?;
+        tx.commit()?;
+
+        for id in &known {
+            self.activity_metrics.remove(*id);
+            for matches in self.activity_matches.values_mut() {
+                matches.retain(|m| &m.activity_id != *id);
+            }
+            self.time_streams.pop(*id);
+            self.processed_activity_ids.remove(*id);
+        }
+        for section_id in &affected_sections {
+            self.invalidate_section_cache(section_id);
+            self.refresh_section_in_memory(section_id);
+        }
+        self.invalidate_perf_cache();
+        if had_tracks {
+            // Regroup now rather than on the next read, so route screens and
+            // the indicators below never see the purged activities.
+            let _ = self.get_groups();
+        }
+        self.recompute_activity_indicators()?;
+
+        log::info!(
+            "tracematch: [PersistentEngine] Purged {} deleted activities ({} sections refreshed)",
+            known.len(),
+            affected_sections.len()
+        );
+        Ok(known.len() as u32)
+    }
+
     /// Clear all data.
     pub fn clear(&mut self) -> SqlResult<()> {
         self.db.execute_batch(
             "DELETE FROM section_activities;
+             DELETE FROM section_checkpoints;
+             DELETE FROM climb_profiles;
              DELETE FROM sections;
              DELETE FROM route_groups;
              DELETE FROM gps_tracks;
@@ -309,7 +413,13 @@ impl PersistentRouteEngine {
              DELETE FROM activities;
              DELETE FROM activity_metrics;
              DELETE FROM activity_matches;
+             DELETE FROM route_overrides;
              DELETE FROM time_streams;
+             DELETE FROM activity_streams;
+             DELETE FROM activity_best_efforts;
+             DELETE FROM best_effort_scans;
+             DELETE FROM activity_zone_times;
+             DELETE FROM fitness_curves;
              DELETE FROM overlap_cache;
              DELETE FROM processed_activities;
              DELETE FROM athlete_profile;
@@ -338,9 +448,12 @@ impl PersistentRouteEngine {
     pub fn clear_routes_and_sections(&mut self) -> SqlResult<()> {
         self.db.execute_batch(
             "DELETE FROM section_activities;
+             DELETE FROM section_checkpoints;
+             DELETE FROM climb_profiles;
              DELETE FROM sections;
              DELETE FROM route_groups;
              DELETE FROM activity_matches;
+             DELETE FROM route_overrides;
              DELETE FROM overlap_cache;",
         )?;
 
@@ -0,0 +1,512 @@
+//! Climb categorisation and gradient profiles for sections and routes.
+//!
+//! The section polyline or route consensus is resampled to elevations every
+//! `GRADIENT_STEP_M`, which gives the gradient profile, the max gradient and
+//! the hardest climb. The climb is scored as length (m) × average gradient
+//! (%) and bucketed Cat 4 to HC. Consensus lines often carry no elevation;
+//! it is then filled from the representative activity (see `elevation`).
+//!
+//! Results live in `climb_profiles`, keyed by a hash of the polyline so
+//! trims, recalibrations and consensus changes are recomputed. A row with
+//! NULL metrics records that no elevation data was available. Profiles are
+//! computed when sections are detected or edited and when route groups are
+//! saved; group saves drop the rows of groups whose membership changed.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult, params};
+
+use super::PersistentRouteEngine;
+use super::codec;
+use super::elevation::elevation_of;
+use crate::{FfiClimbProfile, FfiGradientSegment, GpsPoint, geo_utils};
+
+/// Length of one gradient profile segment.
+const GRADIENT_STEP_M: f64 = 100.0;
+/// Elevation changes smaller than this are noise when summing ascent.
+const ASCENT_HYSTERESIS_M: f64 = 2.0;
+/// A climb must be at least this long and this steep (%) to be categorised.
+const MIN_CLIMB_LENGTH_M: f64 = 500.0;
+const MIN_CLIMB_GRADIENT: f64 = 3.0;
+/// Minimum length × gradient score per category, hardest first.
+const CATEGORY_SCORES: [(f64, &str); 5] = [
+    (80_000.0, "hc"),
+    (64_000.0, "cat1"),
+    (32_000.0, "cat2"),
+    (16_000.0, "cat3"),
+    (8_000.0, "cat4"),
+];
+/// Scores closer than this (0.01 m of gain) are a tie.
+const SCORE_EPSILON: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy)]
+enum ClimbTarget {
+    Section,
+    Route,
+}
+
+impl ClimbTarget {
+    fn as_str(self) -> &'static str {
+        match self {
+            ClimbTarget::Section => "section",
+            ClimbTarget::Route => "route",
+        }
+    }
+}
+
+/// Resampled elevations every `GRADIENT_STEP_M` (the last sample sits at the
+/// end), total distance and total ascent. None with fewer than two points
+/// carrying elevation.
+fn profile_samples(points: &[GpsPoint]) -> Option<(Vec<f64>, f64, f64)> {
+    let mut known: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+    let mut distance = 0.0;
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            distance += geo_utils::haversine_distance(&points[i - 1], p);
+        }
+        if let Some(ele) = elevation_of(p) {
+            known.push((distance, ele));
+        }
+    }
+    if known.len() < 2 || distance <= 0.0 {
+        return None;
+    }
+
+    let mut ascent = 0.0;
+    let mut anchor = known[0].1;
+    for &(_, ele) in &known[1..] {
+        if ele - anchor >= ASCENT_HYSTERESIS_M {
+            ascent += ele - anchor;
+            anchor = ele;
+        } else if anchor - ele >= ASCENT_HYSTERESIS_M {
+            anchor = ele;
+        }
+    }
+
+    let elevation_at = |d: f64| {
+        let i = known.partition_point(|&(kd, _)| kd < d);
+        if i == 0 {
+            return known[0].1;
+        }
+        if i >= known.len() {
+            return known[known.len() - 1].1;
+        }
+        let ((d0, e0), (d1, e1)) = (known[i - 1], known[i]);
+        if d1 > d0 {
+            e0 + (e1 - e0) * (d - d0) / (d1 - d0)
+        } else {
+            e1
+        }
+    };
+    let steps = (distance / GRADIENT_STEP_M).ceil() as usize;
+    let samples = (0..=steps)
+        .map(|k| elevation_at((k as f64 * GRADIENT_STEP_M).min(distance)))
+        .collect();
+    Some((samples, distance, ascent))
+}
+
+fn category_for(score: f64) -> Option<&'static str> {
+    CATEGORY_SCORES
+        .iter()
+        .find(|&&(min, _)| score >= min)
+        .map(|&(_, category)| category)
+}
+
+/// Build the profile from resampled elevations.
+fn summarize(samples: &[f64], distance: f64, ascent: f64) -> FfiClimbProfile {
+    let at = |k: usize| (k as f64 * GRADIENT_STEP_M).min(distance);
+    let segments: Vec<FfiGradientSegment> = samples
+        .windows(2)
+        .enumerate()
+        .map(|(k, pair)| {
+            let (start, end) = (at(k), at(k + 1));
+            FfiGradientSegment {
+                start_distance: start,
+                end_distance: end,
+                start_elevation: pair[0],
+                end_elevation: pair[1],
+                gradient: if end > start {
+                    (pair[1] - pair[0]) / (end - start) * 100.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+
+    // A short trailing segment is too noisy to set the max on its own
+    let max_gradient = segments
+        .iter()
+        .filter(|s| {
+            segments.len() == 1 || s.end_distance - s.start_distance >= GRADIENT_STEP_M / 2.0
+        })
+        .map(|s| s.gradient)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    // Hardest climb: the stretch with the highest length × gradient score
+    // that is long and steep enough to count. Equal scores go to the shorter
+    // stretch so flat approaches aren't counted as part of the climb.
+    let mut climb: Option<(f64, f64, f64)> = None;
+    for (i, &start_ele) in samples.iter().enumerate() {
+        for (j, &end_ele) in samples.iter().enumerate().skip(i + 1) {
+            let length = at(j) - at(i);
+            if length < MIN_CLIMB_LENGTH_M {
+                continue;
+            }
+            let gradient = (end_ele - start_ele) / length * 100.0;
+            let score = length * gradient;
+            let better = climb.is_none_or(|(best_length, _, best)| {
+                score > best + SCORE_EPSILON
+                    || (score > best - SCORE_EPSILON && length < best_length)
+            });
+            if gradient >= MIN_CLIMB_GRADIENT && better {
+                climb = Some((length, gradient, score));
+            }
+        }
+    }
+
+    FfiClimbProfile {
+        distance_meters: distance,
+        total_ascent: ascent,
+        avg_gradient: (samples[samples.len() - 1] - samples[0]) / distance * 100.0,
+        max_gradient: if max_gradient.is_finite() {
+            max_gradient
+        } else {
+            0.0
+        },
+        climb_category: climb
+            .and_then(|(_, _, score)| category_for(score))
+            .map(str::to_string),
+        climb_distance_meters: climb.map(|(length, _, _)| length),
+        climb_gradient: climb.map(|(_, gradient, _)| gradient),
+        segments,
+    }
+}
+
+/// Fingerprint of every point of a polyline, elevation included; a stored
+/// profile is stale when it changes. `DefaultHasher` is not stable across
+/// Rust releases, which at worst recomputes a profile.
+fn source_key(polyline: &[GpsPoint]) -> String {
+    let mut hasher = DefaultHasher::new();
+    for p in polyline {
+        p.latitude.to_bits().hash(&mut hasher);
+        p.longitude.to_bits().hash(&mut hasher);
+        p.elevation.map(f64::to_bits).hash(&mut hasher);
+    }
+    format!("{}:{:016x}", polyline.len(), hasher.finish())
+}
+
+/// Route groups and the membership blob they were saved with, read before
+/// `route_groups` is wiped and rebuilt.
+pub(crate) type GroupMembership = (String, Option<Vec<u8>>);
+
+pub(crate) fn snapshot_group_memberships(conn: &Connection) -> SqlResult<Vec<GroupMembership>> {
+    let mut stmt = conn.prepare("SELECT id, activity_ids_blob FROM route_groups")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Drop route climb profiles of groups that are gone or whose membership
+/// changed, so `update_route_climb_profiles` recomputes them.
+pub(crate) fn drop_changed_route_profiles(
+    conn: &Connection,
+    before: &[GroupMembership],
+) -> SqlResult<()> {
+    conn.execute(
+        "DELETE FROM climb_profiles WHERE target_type = 'route'
+         AND target_id NOT IN (SELECT id FROM route_groups)",
+        [],
+    )?;
+    let mut stmt = conn.prepare(
+        "DELETE FROM climb_profiles WHERE target_type = 'route' AND target_id = ?1
+         AND NOT EXISTS (SELECT 1 FROM route_groups WHERE id = ?1 AND activity_ids_blob IS ?2)",
+    )?;
+    for (id, blob) in before {
+        stmt.execute(params![id, blob])?;
+    }
+    Ok(())
+}
+
+/// Stored climb metrics of a section or route, as shown in lists.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StoredClimb {
+    pub(crate) total_ascent: Option<f64>,
+    pub(crate) avg_gradient: Option<f64>,
+    pub(crate) max_gradient: Option<f64>,
+    pub(crate) climb_category: Option<String>,
+}
+
+impl PersistentRouteEngine {
+    /// Gradient profile and climb category of a section. None without
+    /// elevation data.
+    pub fn get_section_climb_profile(&mut self, section_id: &str) -> Option<FfiClimbProfile> {
+        self.climb_profile(ClimbTarget::Section, section_id)?.0
+    }
+
+    /// Gradient profile and climb category of a route's consensus line.
+    /// None without elevation data.
+    pub fn get_route_climb_profile(&mut self, route_id: &str) -> Option<FfiClimbProfile> {
+        self.climb_profile(ClimbTarget::Route, route_id)?.0
+    }
+
+    /// Compute missing or stale section climb profiles, checking each
+    /// section against its current polyline. Run after section detection.
+    /// Returns how many profiles were computed.
+    pub fn update_section_climb_profiles(&mut self) -> SqlResult<u32> {
+        self.db.execute(
+            "DELETE FROM climb_profiles WHERE target_type = 'section'
+             AND target_id NOT IN (SELECT id FROM sections)",
+            [],
+        )?;
+        let section_ids: Vec<String> = {
+            let mut stmt = self
+                .db
+                .prepare("SELECT id FROM sections WHERE disabled = 0 AND superseded_by IS NULL")?;
+            stmt.query_map([], |row| row.get(0))?
+                .collect::<SqlResult<_>>()?
+        };
+        Ok(self.compute_climb_profiles(ClimbTarget::Section, &section_ids))
+    }
+
+    /// Compute climb profiles for route groups that have none. Group saves
+    /// drop the profiles of changed groups, so only those and new groups
+    /// need their consensus built. Returns how many profiles were computed.
+    pub fn update_route_climb_profiles(&mut self) -> SqlResult<u32> {
+        let route_ids: Vec<String> = {
+            let mut stmt = self.db.prepare(
+                "SELECT id FROM route_groups WHERE id NOT IN
+                 (SELECT target_id FROM climb_profiles WHERE target_type = 'route')",
+            )?;
+            stmt.query_map([], |row| row.get(0))?
+                .collect::<SqlResult<_>>()?
+        };
+        Ok(self.compute_climb_profiles(ClimbTarget::Route, &route_ids))
+    }
+
+    fn compute_climb_profiles(&mut self, target: ClimbTarget, ids: &[String]) -> u32 {
+        let mut computed = 0u32;
+        for id in ids {
+            if let Some((_, true)) = self.climb_profile(target, id) {
+                computed += 1;
+            }
+        }
+        if computed > 0 {
+            log::info!(
+                "tracematch: [PersistentEngine] Computed {} {} climb profiles",
+                computed,
+                target.as_str()
+            );
+        }
+        computed
+    }
+
+    /// Recompute a section's climb profile after its polyline was edited.
+    pub(crate) fn refresh_section_climb_profile(&mut self, section_id: &str) {
+        self.climb_profile(ClimbTarget::Section, section_id);
+    }
+
+    /// Stored climb metrics of every section, keyed by section id.
+    pub(crate) fn get_section_climbs(&self) -> SqlResult<HashMap<String, StoredClimb>> {
+        self.stored_climbs(ClimbTarget::Section)
+    }
+
+    /// Stored climb metrics of every route group, keyed by group id.
+    pub(crate) fn get_route_climbs(&self) -> SqlResult<HashMap<String, StoredClimb>> {
+        self.stored_climbs(ClimbTarget::Route)
+    }
+
+    fn stored_climbs(&self, target: ClimbTarget) -> SqlResult<HashMap<String, StoredClimb>> {
+        let mut stmt = self.db.prepare(
+            "SELECT target_id, total_ascent, avg_gradient, max_gradient, climb_category
+             FROM climb_profiles WHERE target_type = ?",
+        )?;
+        let rows = stmt.query_map(params![target.as_str()], |row| {
+            Ok((
+                row.get(0)?,
+                StoredClimb {
+                    total_ascent: row.get(1)?,
+                    avg_gradient: row.get(2)?,
+                    max_gradient: row.get(3)?,
+                    climb_category: row.get(4)?,
+                },
+            ))
+        })?;
+        rows.collect()
+    }
+
+    /// Stored profile if still fresh, otherwise compute and store it. Returns
+    /// the profile and whether it was computed; None if the target is gone.
+    fn climb_profile(
+        &mut self,
+        target: ClimbTarget,
+        id: &str,
+    ) -> Option<(Option<FfiClimbProfile>, bool)> {
+        let (polyline, reference_id) = match target {
+            ClimbTarget::Section => {
+                let section = self.get_section(id)?;
+                (section.polyline, section.representative_activity_id)
+            }
+            ClimbTarget::Route => {
+                let consensus = self.get_consensus_route(id)?;
+                let representative = self
+                    .groups
+                    .iter()
+                    .find(|g| g.group_id == id)
+                    .map(|g| g.representative_id.clone());
+                (consensus.to_vec(), representative)
+            }
+        };
+        let key = source_key(&polyline);
+
+        let stored: Option<(Option<f64>, f64, Option<Vec<u8>>)> = self
+            .db
+            .query_row(
+                "SELECT total_ascent, profile_distance, samples_blob FROM climb_profiles
+                 WHERE target_type = ? AND target_id = ? AND source_key = ?",
+                params![target.as_str(), id, key],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .ok()
+            .flatten();
+        if let Some((ascent, distance, blob)) = stored {
+            let samples: Option<Vec<f64>> = blob.and_then(|b| codec::deserialize(&b).ok());
+            let profile = match (ascent, samples) {
+                (Some(ascent), Some(samples)) if samples.len() >= 2 => {
+                    Some(summarize(&samples, distance, ascent))
+                }
+                _ => None,
+            };
+            return Some((profile, false));
+        }
+
+        let points = self.with_elevation(polyline, reference_id.as_deref());
+
+        let samples = profile_samples(&points);
+        let profile = samples
+            .as_ref()
+            .map(|(samples, distance, ascent)| summarize(samples, *distance, *ascent));
+        let blob = samples
+            .as_ref()
+            .and_then(|(samples, _, _)| codec::serialize(samples).ok());
+        if let Err(e) = self.db.execute(
+            "INSERT OR REPLACE INTO climb_profiles
+             (target_type, target_id, source_key, profile_distance, total_ascent,
+              avg_gradient, max_gradient, climb_category, samples_blob)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                target.as_str(),
+                id,
+                key,
+                samples.as_ref().map_or(0.0, |(_, distance, _)| *distance),
+                profile.as_ref().map(|p| p.total_ascent),
+                profile.as_ref().map(|p| p.avg_gradient),
+                profile.as_ref().map(|p| p.max_gradient),
+                profile.as_ref().and_then(|p| p.climb_category.clone()),
+                blob,
+            ],
+        ) {
+            log::warn!(
+                "tracematch: [PersistentEngine] Failed to store climb profile for {}: {}",
+                id,
+                e
+            );
+        }
+        Some((profile, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::elevation::fill_elevation;
+
+    /// Straight line north, one point every ~10 m, elevation from `ele(metres)`.
+    fn climb(length_m: f64, ele: impl Fn(f64) -> f64) -> Vec<GpsPoint> {
+        let deg_per_m = 1.0 / 111_195.0;
+        (0..=(length_m / 10.0) as usize)
+            .map(|i| {
+                let d = i as f64 * 10.0;
+                GpsPoint {
+                    latitude: 46.0 + d * deg_per_m,
+                    longitude: 7.0,
+                    elevation: Some(ele(d)),
+                }
+            })
+            .collect()
+    }
+
+    fn profile_of(points: &[GpsPoint]) -> FfiClimbProfile {
+        let (samples, distance, ascent) = profile_samples(points).unwrap();
+        summarize(&samples, distance, ascent)
+    }
+
+    #[test]
+    fn climbs_are_categorised_by_length_and_gradient() {
+        // 5 km at 6%: score 30 000 -> Cat 3
+        let p = profile_of(&climb(5000.0, |d| 400.0 + d * 0.06));
+        assert_eq!(p.climb_category.as_deref(), Some("cat3"));
+        assert!((p.total_ascent - 300.0).abs() < 3.0);
+        assert!((p.avg_gradient - 6.0).abs() < 0.1);
+        assert!((p.climb_gradient.unwrap() - 6.0).abs() < 0.1);
+        assert!(p.segments.len() >= 50);
+        assert!(p.segments.iter().all(|s| (s.gradient - 6.0).abs() < 0.1));
+
+        // 12 km at 7.5%: score 108 000 -> HC
+        let p = profile_of(&climb(12_000.0, |d| 300.0 + d * 0.075));
+        assert_eq!(p.climb_category.as_deref(), Some("hc"));
+
+        // Flat sprint
+        let p = profile_of(&climb(800.0, |_| 500.0));
+        assert_eq!(p.climb_category, None);
+        assert_eq!(p.total_ascent, 0.0);
+    }
+
+    #[test]
+    fn hardest_climb_is_found_inside_a_rolling_profile() {
+        // 2 km flat, 3 km at 8% (score 24 000), 2 km descent at 4%
+        let p = profile_of(&climb(7000.0, |d| {
+            if d < 2000.0 {
+                100.0
+            } else if d < 5000.0 {
+                100.0 + (d - 2000.0) * 0.08
+            } else {
+                340.0 - (d - 5000.0) * 0.04
+            }
+        }));
+        assert_eq!(p.climb_category.as_deref(), Some("cat3"));
+        assert!((p.climb_distance_meters.unwrap() - 3000.0).abs() < 1.0);
+        assert!((p.max_gradient - 8.0).abs() < 0.1);
+        assert!((p.avg_gradient - 160.0 / 7000.0 * 100.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn missing_elevation_is_filled_from_the_reference_track() {
+        let reference = climb(1000.0, |d| 200.0 + d * 0.05);
+        let bare: Vec<GpsPoint> = reference
+            .iter()
+            .step_by(10)
+            .map(|p| GpsPoint::new(p.latitude, p.longitude))
+            .collect();
+        assert!(profile_samples(&bare).is_none());
+
+        let filled = fill_elevation(&bare, &reference);
+        let p = profile_of(&filled);
+        assert!((p.total_ascent - 50.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn source_key_tracks_interior_points() {
+        let original = climb(1000.0, |d| 200.0 + d * 0.05);
+        let mut reshaped = original.clone();
+        reshaped[50].longitude += 0.0001;
+        let mut regraded = original.clone();
+        regraded[50].elevation = Some(260.0);
+
+        let key = source_key(&original);
+        assert_eq!(key, source_key(&original.clone()));
+        assert_ne!(key, source_key(&reshaped));
+        assert_ne!(key, source_key(&regraded));
+    }
+}
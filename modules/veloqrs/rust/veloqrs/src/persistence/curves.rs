@@ -0,0 +1,377 @@
+//! Fitness curves: power and pace best-effort curves fetched during sync.
+//!
+//! One row per (kind, sport, window). The window is the intervals.icu
+//! `curves` parameter, so a stored row maps one-to-one onto the request that
+//! produced it. Series are delta-varint encoded like activity streams; pace
+//! curves keep the server's distance/time pairs and derive pace on read.
+
+use rusqlite::{OptionalExtension, Result as SqlResult, params};
+
+use super::PersistentRouteEngine;
+use crate::coords;
+use crate::net::types::{PaceCurve, PowerCurve};
+
+/// Curve kinds stored in `fitness_curves.kind`.
+pub const CURVE_KIND_POWER: &str = "power";
+pub const CURVE_KIND_PACE: &str = "pace";
+
+/// Windows fetched by sync: current season, the last 42 days, all time.
+pub const CURVE_WINDOWS: &[&str] = &["s0", "42d", "all"];
+
+/// Durations (seconds) sampled by `compare_curve_windows`.
+const COMPARE_DURATIONS: &[i64] = &[5, 15, 30, 60, 120, 300, 600, 1200, 1800, 3600, 7200];
+
+/// Power-curve durations are whole seconds from the server, stored at 1 s.
+const SECS_SCALE: f64 = 1.0;
+/// Power values and pace-curve distances / times, stored at 0.1 W, 0.1 m
+/// and 0.1 s.
+const VALUE_SCALE: f64 = 10.0;
+
+/// Linear interpolation of `ys` at `x` over ascending `xs`. `None` outside
+/// the sampled range.
+fn interpolate(xs: &[f64], ys: &[f64], x: f64) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n == 0 || x < xs[0] || x > xs[n - 1] {
+        return None;
+    }
+    let i = xs[..n].partition_point(|&v| v < x);
+    if xs[i] == x || i == 0 {
+        return Some(ys[i]);
+    }
+    let (x0, x1) = (xs[i - 1], xs[i]);
+    let (y0, y1) = (ys[i - 1], ys[i]);
+    if x1 <= x0 {
+        return Some(y1);
+    }
+    Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+}
+
+/// Best average speed (m/s) sustainable for `secs`, from a distance/time
+/// curve: interpolate the distance covered in `secs` and divide.
+fn speed_at(curve: &PaceCurve, secs: f64) -> Option<f64> {
+    if secs <= 0.0 {
+        return None;
+    }
+    interpolate(&curve.times, &curve.distances, secs).map(|d| d / secs)
+}
+
+pub(super) fn power_at(curve: &PowerCurve, secs: f64) -> Option<f64> {
+    let xs: Vec<f64> = curve.secs.iter().map(|&s| s as f64).collect();
+    interpolate(&xs, &curve.watts, secs)
+}
+
+fn ids_to_json(ids: &Option<Vec<String>>) -> Option<String> {
+    ids.as_ref().and_then(|v| serde_json::to_string(v).ok())
+}
+
+fn ids_from_json(raw: Option<String>) -> Option<Vec<String>> {
+    raw.and_then(|s| serde_json::from_str(&s).ok())
+}
+
+impl PersistentRouteEngine {
+    /// Store (replace) the power curve for a sport and window.
+    pub fn save_power_curve(
+        &self,
+        sport_type: &str,
+        window: &str,
+        curve: &PowerCurve,
+    ) -> SqlResult<()> {
+        let secs: Vec<f64> = curve.secs.iter().map(|&s| s as f64).collect();
+        self.db.execute(
+            "INSERT OR REPLACE INTO fitness_curves
+                (kind, sport_type, curve_window, x_data, y_data, activity_ids, fetched_at)
+             VALUES (?, ?, ?, ?, ?, ?, strftime('%s', 'now'))",
+            params![
+                CURVE_KIND_POWER,
+                sport_type,
+                window,
+                coords::encode_series(&secs, SECS_SCALE),
+                coords::encode_series(&curve.watts, VALUE_SCALE),
+                ids_to_json(&curve.activity_ids),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Store (replace) the pace curve for a sport and window.
+    pub fn save_pace_curve(
+        &self,
+        sport_type: &str,
+        window: &str,
+        curve: &PaceCurve,
+    ) -> SqlResult<()> {
+        self.db.execute(
+            "INSERT OR REPLACE INTO fitness_curves
+                (kind, sport_type, curve_window, x_data, y_data, activity_ids,
+                 critical_speed, d_prime, r2, start_date, end_date, days, fetched_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%s', 'now'))",
+            params![
+                CURVE_KIND_PACE,
+                sport_type,
+                window,
+                coords::encode_series(&curve.distances, VALUE_SCALE),
+                coords::encode_series(&curve.times, VALUE_SCALE),
+                ids_to_json(&curve.activity_ids),
+                curve.critical_speed,
+                curve.d_prime,
+                curve.r2,
+                curve.start_date,
+                curve.end_date,
+                curve.days,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Stored power curve and its fetch time (Unix seconds).
+    pub fn get_power_curve(
+        &self,
+        sport_type: &str,
+        window: &str,
+    ) -> SqlResult<Option<(PowerCurve, i64)>> {
+        self.db
+            .query_row(
+                "SELECT x_data, y_data, activity_ids, fetched_at FROM fitness_curves
+                 WHERE kind = ? AND sport_type = ? AND curve_window = ?",
+                params![CURVE_KIND_POWER, sport_type, window],
+                |row| {
+                    let secs = coords::decode_series(&row.get::<_, Vec<u8>>(0)?, SECS_SCALE);
+                    let curve = PowerCurve {
+                        secs: secs.iter().map(|&s| s as i64).collect(),
+                        watts: coords::decode_series(&row.get::<_, Vec<u8>>(1)?, VALUE_SCALE),
+                        activity_ids: ids_from_json(row.get(2)?),
+                    };
+                    Ok((curve, row.get(3)?))
+                },
+            )
+            .optional()
+    }
+
+    /// Stored pace curve and its fetch time (Unix seconds). `pace` is
+    /// recomputed as distance / time, as in `parse_pace_curve`.
+    pub fn get_pace_curve(
+        &self,
+        sport_type: &str,
+        window: &str,
+    ) -> SqlResult<Option<(PaceCurve, i64)>> {
+        self.db
+            .query_row(
+                "SELECT x_data, y_data, activity_ids, critical_speed, d_prime, r2,
+                        start_date, end_date, days, fetched_at
+                 FROM fitness_curves
+                 WHERE kind = ? AND sport_type = ? AND curve_window = ?",
+                params![CURVE_KIND_PACE, sport_type, window],
+                |row| {
+                    let distances = coords::decode_series(&row.get::<_, Vec<u8>>(0)?, VALUE_SCALE);
+                    let times = coords::decode_series(&row.get::<_, Vec<u8>>(1)?, VALUE_SCALE);
+                    let pace = distances
+                        .iter()
+                        .zip(times.iter())
+                        .map(|(d, t)| if *t > 0.0 { d / t } else { 0.0 })
+                        .collect();
+                    let curve = PaceCurve {
+                        distances,
+                        times,
+                        pace,
+                        activity_ids: ids_from_json(row.get(2)?),
+                        critical_speed: row.get(3)?,
+                        d_prime: row.get(4)?,
+                        r2: row.get(5)?,
+                        start_date: row.get(6)?,
+                        end_date: row.get(7)?,
+                        days: row.get(8)?,
+                    };
+                    Ok((curve, row.get(9)?))
+                },
+            )
+            .optional()
+    }
+
+    /// Whether a curve of `kind` is stored for the sport and window.
+    pub fn has_fitness_curve(&self, kind: &str, sport_type: &str, window: &str) -> SqlResult<bool> {
+        self.db.query_row(
+            "SELECT EXISTS(SELECT 1 FROM fitness_curves
+                           WHERE kind = ? AND sport_type = ? AND curve_window = ?)",
+            params![kind, sport_type, window],
+            |row| row.get(0),
+        )
+    }
+
+    /// Best average power (W) held for `secs`, interpolated between curve
+    /// points. `None` if no curve is stored or `secs` is outside it.
+    pub fn best_power_at(
+        &self,
+        sport_type: &str,
+        window: &str,
+        secs: f64,
+    ) -> SqlResult<Option<f64>> {
+        Ok(self
+            .get_power_curve(sport_type, window)?
+            .and_then(|(curve, _)| power_at(&curve, secs)))
+    }
+
+    /// Best average speed (m/s) held for `secs`. Callers convert to pace.
+    pub fn best_pace_at(
+        &self,
+        sport_type: &str,
+        window: &str,
+        secs: f64,
+    ) -> SqlResult<Option<f64>> {
+        Ok(self
+            .get_pace_curve(sport_type, window)?
+            .and_then(|(curve, _)| speed_at(&curve, secs)))
+    }
+
+    /// Sample two windows of the same curve at standard durations (5 s to
+    /// 2 h). Values are watts for power and m/s for pace; a duration missing
+    /// from either window is `None` on that side.
+    pub fn compare_curve_windows(
+        &self,
+        kind: &str,
+        sport_type: &str,
+        window_a: &str,
+        window_b: &str,
+    ) -> SqlResult<crate::FfiCurveComparison> {
+        Ok(crate::FfiCurveComparison {
+            kind: kind.to_string(),
+            sport_type: sport_type.to_string(),
+            window_a: window_a.to_string(),
+            window_b: window_b.to_string(),
+            durations: COMPARE_DURATIONS.to_vec(),
+            values_a: self.sample_curve(kind, sport_type, window_a)?,
+            values_b: self.sample_curve(kind, sport_type, window_b)?,
+        })
+    }
+
+    /// One curve sampled at `COMPARE_DURATIONS`; all `None` when not stored.
+    fn sample_curve(
+        &self,
+        kind: &str,
+        sport_type: &str,
+        window: &str,
+    ) -> SqlResult<Vec<Option<f64>>> {
+        let secs = COMPARE_DURATIONS.iter().map(|&d| d as f64);
+        Ok(match kind {
+            CURVE_KIND_POWER => match self.get_power_curve(sport_type, window)? {
+                Some((c, _)) => secs.map(|s| power_at(&c, s)).collect(),
+                None => vec![None; COMPARE_DURATIONS.len()],
+            },
+            CURVE_KIND_PACE => match self.get_pace_curve(sport_type, window)? {
+                Some((c, _)) => secs.map(|s| speed_at(&c, s)).collect(),
+                None => vec![None; COMPARE_DURATIONS.len()],
+            },
+            _ => vec![None; COMPARE_DURATIONS.len()],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn power() -> PowerCurve {
+        PowerCurve {
+            secs: vec![1, 5, 60, 300, 1200],
+            watts: vec![900.0, 800.0, 450.0, 320.0, 260.5],
+            activity_ids: Some(vec![
+                "a1".into(),
+                "a1".into(),
+                "a2".into(),
+                "a2".into(),
+                "a3".into(),
+            ]),
+        }
+    }
+
+    fn pace() -> PaceCurve {
+        PaceCurve {
+            distances: vec![400.0, 1000.0, 5000.0],
+            times: vec![80.0, 220.0, 1250.0],
+            pace: vec![5.0, 1000.0 / 220.0, 4.0],
+            activity_ids: None,
+            critical_speed: Some(3.9),
+            d_prime: Some(180.0),
+            r2: Some(0.98),
+            start_date: Some("2026-05-01T00:00:00".into()),
+            end_date: Some("2026-06-12T00:00:00".into()),
+            days: Some(42),
+        }
+    }
+
+    #[test]
+    fn curves_round_trip() {
+        let engine = PersistentRouteEngine::in_memory().unwrap();
+        assert!(engine.get_power_curve("Ride", "42d").unwrap().is_none());
+        engine.save_power_curve("Ride", "42d", &power()).unwrap();
+        engine.save_pace_curve("Run", "42d", &pace()).unwrap();
+
+        let (got, fetched_at) = engine.get_power_curve("Ride", "42d").unwrap().unwrap();
+        assert_eq!(got, power());
+        assert!(fetched_at > 0);
+        let (got, _) = engine.get_pace_curve("Run", "42d").unwrap().unwrap();
+        assert_eq!(got, pace());
+
+        assert!(
+            engine
+                .has_fitness_curve(CURVE_KIND_PACE, "Run", "42d")
+                .unwrap()
+        );
+        assert!(
+            !engine
+                .has_fitness_curve(CURVE_KIND_PACE, "Run", "all")
+                .unwrap()
+        );
+        assert!(
+            !engine
+                .has_fitness_curve(CURVE_KIND_POWER, "Run", "42d")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn best_at_duration_interpolates() {
+        let engine = PersistentRouteEngine::in_memory().unwrap();
+        engine.save_power_curve("Ride", "42d", &power()).unwrap();
+        engine.save_pace_curve("Run", "42d", &pace()).unwrap();
+
+        assert_eq!(
+            engine.best_power_at("Ride", "42d", 60.0).unwrap(),
+            Some(450.0)
+        );
+        // Halfway between 60 s @ 450 W and 300 s @ 320 W.
+        assert_eq!(
+            engine.best_power_at("Ride", "42d", 180.0).unwrap(),
+            Some(385.0)
+        );
+        assert_eq!(engine.best_power_at("Ride", "42d", 3600.0).unwrap(), None);
+        assert_eq!(engine.best_power_at("Ride", "all", 60.0).unwrap(), None);
+
+        // 150 s sits halfway between 400 m and 1000 m.
+        let speed = engine.best_pace_at("Run", "42d", 150.0).unwrap().unwrap();
+        assert!((speed - 700.0 / 150.0).abs() < 1e-9);
+        assert_eq!(engine.best_pace_at("Run", "42d", 10.0).unwrap(), None);
+    }
+
+    #[test]
+    fn compare_windows_samples_standard_durations() {
+        let engine = PersistentRouteEngine::in_memory().unwrap();
+        engine.save_power_curve("Ride", "42d", &power()).unwrap();
+        let mut season = power();
+        season.watts = season.watts.iter().map(|w| w + 10.0).collect();
+        engine.save_power_curve("Ride", "s0", &season).unwrap();
+
+        let cmp = engine
+            .compare_curve_windows(CURVE_KIND_POWER, "Ride", "42d", "s0")
+            .unwrap();
+        assert_eq!(cmp.durations.len(), COMPARE_DURATIONS.len());
+        let i60 = cmp.durations.iter().position(|&d| d == 60).unwrap();
+        assert_eq!(cmp.values_a[i60], Some(450.0));
+        assert_eq!(cmp.values_b[i60], Some(460.0));
+        assert_eq!(cmp.values_a.last().copied().flatten(), None);
+
+        let missing = engine
+            .compare_curve_windows(CURVE_KIND_PACE, "Run", "42d", "all")
+            .unwrap();
+        assert!(missing.values_a.iter().all(Option::is_none));
+    }
+}
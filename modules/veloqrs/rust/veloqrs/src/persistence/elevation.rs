@@ -0,0 +1,105 @@
+//! Elevation for polylines that carry little or none.
+//!
+//! Consensus routes and section polylines often have no elevation. Missing
+//! points are filled from the nearest point of a reference activity's track
+//! (or its altitude stream), so everything computed from these lines sees
+//! the same terrain.
+
+use rstar::RTree;
+use rstar::primitives::GeomWithData;
+
+use super::PersistentRouteEngine;
+use super::route_deviation::{LocalPlane, plane_distance};
+use crate::GpsPoint;
+
+/// Reference track points further than this don't lend their elevation.
+const ELEVATION_FILL_RADIUS_M: f64 = 50.0;
+
+pub(crate) fn elevation_of(p: &GpsPoint) -> Option<f64> {
+    p.elevation.filter(|e| e.is_finite())
+}
+
+/// Fill points without elevation from the nearest reference point.
+pub(crate) fn fill_elevation(polyline: &[GpsPoint], reference: &[GpsPoint]) -> Vec<GpsPoint> {
+    let Some(origin) = polyline.first() else {
+        return Vec::new();
+    };
+    let plane = LocalPlane::new(origin);
+    let rtree = RTree::bulk_load(
+        reference
+            .iter()
+            .filter_map(|p| elevation_of(p).map(|ele| GeomWithData::new(plane.project(p), ele)))
+            .collect(),
+    );
+    polyline
+        .iter()
+        .map(|p| {
+            if elevation_of(p).is_some() {
+                return *p;
+            }
+            let xy = plane.project(p);
+            let elevation = rtree
+                .nearest_neighbor(&xy)
+                .filter(|n| plane_distance(*n.geom(), xy) <= ELEVATION_FILL_RADIUS_M)
+                .map(|n| n.data);
+            GpsPoint { elevation, ..*p }
+        })
+        .collect()
+}
+
+impl PersistentRouteEngine {
+    /// `polyline` with missing elevations filled from the track of
+    /// `reference_id` when fewer than half its points carry one. Points keep
+    /// their order, so indices into the original still apply.
+    pub(crate) fn with_elevation(
+        &self,
+        polyline: Vec<GpsPoint>,
+        reference_id: Option<&str>,
+    ) -> Vec<GpsPoint> {
+        let known = polyline
+            .iter()
+            .filter(|p| elevation_of(p).is_some())
+            .count();
+        match reference_id.filter(|id| !id.is_empty()) {
+            Some(reference_id) if known * 2 < polyline.len() => {
+                match self.elevation_reference(reference_id) {
+                    Some(reference) => fill_elevation(&polyline, &reference),
+                    None => polyline,
+                }
+            }
+            _ => polyline,
+        }
+    }
+
+    /// A route group's consensus polyline with elevation filled from its
+    /// representative activity. None if the route has no consensus.
+    pub(crate) fn route_with_elevation(&mut self, route_id: &str) -> Option<Vec<GpsPoint>> {
+        let consensus = self.get_consensus_route(route_id)?;
+        let representative = self
+            .groups
+            .iter()
+            .find(|g| g.group_id == route_id)
+            .map(|g| g.representative_id.clone());
+        Some(self.with_elevation(consensus.to_vec(), representative.as_deref()))
+    }
+
+    /// An activity's GPS track with elevation, taken from the altitude
+    /// stream when the track itself has none.
+    fn elevation_reference(&self, activity_id: &str) -> Option<Vec<GpsPoint>> {
+        let mut track = self.get_gps_track(activity_id)?;
+        if track.iter().any(|p| elevation_of(p).is_some()) {
+            return Some(track);
+        }
+        let altitude = self
+            .get_activity_stream(activity_id, "altitude")
+            .ok()
+            .flatten()?;
+        if altitude.len() != track.len() {
+            return None;
+        }
+        for (p, ele) in track.iter_mut().zip(altitude) {
+            p.elevation = ele.is_finite().then_some(ele);
+        }
+        Some(track)
+    }
+}
@@ -3,6 +3,9 @@
 //! Processes one activity at a time - peak memory is ~1 track regardless of
 //! total activity count. Avoids the OOM crash from holding all GPX strings
 //! in the JS heap.
+//!
+//! Also exports single routes and sections as courses (GPX or FIT) for
+//! loading onto a head unit.
 
 use std::io::Write;
 
@@ -17,6 +20,15 @@ pub struct BulkExportResult {
     pub total_bytes: u64,
 }
 
+/// Result of a single route or section course export.
+#[derive(Debug, Clone, serde::Serialize, uniffi::Record)]
+pub struct CourseExportResult {
+    pub name: String,
+    pub point_count: u32,
+    pub distance_meters: f64,
+    pub total_bytes: u64,
+}
+
 impl PersistentRouteEngine {
     /// Export all activities with GPS data as GPX files inside a ZIP archive.
     ///
@@ -357,6 +369,426 @@ impl PersistentRouteEngine {
     }
 }
 
+impl PersistentRouteEngine {
+    /// Export a route's consensus polyline as a course file.
+    ///
+    /// `format` is "gpx" (track), "gpx_route" (`<rte>` waypoints) or "fit".
+    /// The course is named after the route's custom name, falling back to
+    /// its ID. Missing elevation is filled from the representative activity.
+    pub fn export_route_course(
+        &mut self,
+        route_id: &str,
+        format: &str,
+        dest_path: &str,
+    ) -> Result<CourseExportResult, String> {
+        let points = self
+            .route_with_elevation(route_id)
+            .ok_or_else(|| format!("Route {} has no consensus polyline", route_id))?;
+        let name = self
+            .get_all_route_names()
+            .remove(route_id)
+            .unwrap_or_else(|| route_id.to_string());
+        let sport = self
+            .get_group_by_id(route_id)
+            .map(|g| g.sport_type)
+            .unwrap_or_default();
+        write_course(&name, &sport, &points, format, dest_path)
+    }
+
+    /// Export a section polyline as a course file. Formats and elevation as
+    /// for [`export_route_course`](Self::export_route_course).
+    pub fn export_section_course(
+        &mut self,
+        section_id: &str,
+        format: &str,
+        dest_path: &str,
+    ) -> Result<CourseExportResult, String> {
+        let section = self
+            .get_section(section_id)
+            .ok_or_else(|| format!("Section {} not found", section_id))?;
+        let name = section.name.unwrap_or_else(|| section_id.to_string());
+        let points = self.with_elevation(
+            section.polyline,
+            section.representative_activity_id.as_deref(),
+        );
+        write_course(&name, &section.sport_type, &points, format, dest_path)
+    }
+}
+
+/// Export an arbitrary polyline (e.g. a route suggestion) as a course file.
+/// Formats as for [`PersistentRouteEngine::export_route_course`].
+pub fn export_course_points(
+    name: &str,
+    sport: &str,
+    points: &[GpsPoint],
+    format: &str,
+    dest_path: &str,
+) -> Result<CourseExportResult, String> {
+    write_course(name, sport, points, format, dest_path)
+}
+
+/// Encode a course in the requested format and write it to `dest_path`.
+fn write_course(
+    name: &str,
+    sport: &str,
+    points: &[GpsPoint],
+    format: &str,
+    dest_path: &str,
+) -> Result<CourseExportResult, String> {
+    let points: Vec<GpsPoint> = points
+        .iter()
+        .filter(|p| p.latitude.is_finite() && p.longitude.is_finite())
+        .copied()
+        .collect();
+    if points.len() < 2 {
+        return Err("Course needs at least two points".to_string());
+    }
+    let distances = cumulative_distances(&points);
+    let distance_meters = distances.last().copied().unwrap_or(0.0);
+
+    let bytes = match format {
+        "gpx" => generate_gpx_course(name, sport, &points, distance_meters, false).into_bytes(),
+        "gpx_route" => {
+            generate_gpx_course(name, sport, &points, distance_meters, true).into_bytes()
+        }
+        "fit" => generate_fit_course(
+            name,
+            sport,
+            &points,
+            &distances,
+            chrono::Utc::now().timestamp(),
+        ),
+        other => return Err(format!("Unsupported course format: {}", other)),
+    };
+    std::fs::write(dest_path, &bytes).map_err(|e| format!("Failed to write course: {}", e))?;
+
+    log::info!(
+        "[CourseExport] Exported '{}' as {} ({} points, {:.0} m)",
+        name,
+        format,
+        points.len(),
+        distance_meters
+    );
+
+    Ok(CourseExportResult {
+        name: name.to_string(),
+        point_count: points.len() as u32,
+        distance_meters,
+        total_bytes: bytes.len() as u64,
+    })
+}
+
+/// Distance from the first point, in meters, at every point.
+fn cumulative_distances(points: &[GpsPoint]) -> Vec<f64> {
+    let mut total = 0.0;
+    let mut distances = Vec::with_capacity(points.len());
+    distances.push(0.0);
+    for pair in points.windows(2) {
+        total += tracematch::geo_utils::haversine_distance(&pair[0], &pair[1]);
+        distances.push(total);
+    }
+    distances
+}
+
+/// Generate GPX 1.1 XML for a course, as a `<trk>` or a `<rte>`.
+/// The total distance goes into the metadata description.
+fn generate_gpx_course(
+    name: &str,
+    sport: &str,
+    points: &[GpsPoint],
+    distance_meters: f64,
+    as_route: bool,
+) -> String {
+    let escaped_name = escape_xml(name);
+    let (container, point_tag, indent) = if as_route {
+        ("rte", "rtept", "    ")
+    } else {
+        ("trk", "trkpt", "      ")
+    };
+
+    let mut gpx = String::with_capacity(points.len() * 100 + 500);
+    gpx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    gpx.push_str("<gpx version=\"1.1\" creator=\"Veloq\"\n");
+    gpx.push_str("  xmlns=\"http://www.topografix.com/GPX/1/1\"\n");
+    gpx.push_str("  xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"\n");
+    gpx.push_str("  xsi:schemaLocation=\"http://www.topografix.com/GPX/1/1 http://www.topografix.com/GPX/1/1/gpx.xsd\">\n");
+    gpx.push_str("  <metadata>\n");
+    gpx.push_str(&format!("    <name>{}</name>\n", escaped_name));
+    gpx.push_str(&format!("    <desc>{:.0} m</desc>\n", distance_meters));
+    gpx.push_str("  </metadata>\n");
+    gpx.push_str(&format!("  <{}>\n", container));
+    gpx.push_str(&format!("    <name>{}</name>\n", escaped_name));
+    if !sport.is_empty() {
+        gpx.push_str(&format!("    <type>{}</type>\n", escape_xml(sport)));
+    }
+    if !as_route {
+        gpx.push_str("    <trkseg>\n");
+    }
+
+    for p in points {
+        gpx.push_str(&format!(
+            "{}<{} lat=\"{:.6}\" lon=\"{:.6}\">",
+            indent, point_tag, p.latitude, p.longitude
+        ));
+        if let Some(ele) = p.elevation.filter(|e| e.is_finite()) {
+            gpx.push_str(&format!("<ele>{:.1}</ele>", ele));
+        }
+        gpx.push_str(&format!("</{}>\n", point_tag));
+    }
+
+    if !as_route {
+        gpx.push_str("    </trkseg>\n");
+    }
+    gpx.push_str(&format!("  </{}>\n", container));
+    gpx.push_str("</gpx>");
+
+    gpx
+}
+
+// ============================================================================
+// FIT Course Writer
+// ============================================================================
+
+/// Seconds between the Unix epoch and the FIT epoch (1989-12-31T00:00:00Z).
+const FIT_EPOCH_OFFSET: i64 = 631_065_600;
+
+// FIT base types
+const FIT_ENUM: u8 = 0x00;
+const FIT_UINT8: u8 = 0x02;
+const FIT_STRING: u8 = 0x07;
+const FIT_UINT16: u8 = 0x84;
+const FIT_SINT32: u8 = 0x85;
+const FIT_UINT32: u8 = 0x86;
+
+// FIT global message numbers
+const MESG_FILE_ID: u16 = 0;
+const MESG_LAP: u16 = 19;
+const MESG_RECORD: u16 = 20;
+const MESG_EVENT: u16 = 21;
+const MESG_COURSE: u16 = 31;
+
+/// Minimal FIT encoder: little-endian definition and data messages, with the
+/// 14-byte header and trailing CRC added by `finish`.
+struct FitWriter {
+    body: Vec<u8>,
+}
+
+impl FitWriter {
+    fn new() -> Self {
+        Self { body: Vec::new() }
+    }
+
+    /// Write a definition message. `fields` are (field number, size, base type).
+    fn define(&mut self, local: u8, global: u16, fields: &[(u8, u8, u8)]) {
+        self.body.extend_from_slice(&[0x40 | local, 0, 0]);
+        self.body.extend_from_slice(&global.to_le_bytes());
+        self.body.push(fields.len() as u8);
+        for &(num, size, base) in fields {
+            self.body.extend_from_slice(&[num, size, base]);
+        }
+    }
+
+    /// Start a data message; field values are appended to the returned buffer
+    /// in definition order.
+    fn data(&mut self, local: u8) -> &mut Vec<u8> {
+        self.body.push(local);
+        &mut self.body
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.body.len() + 16);
+        out.push(14);
+        out.push(0x20); // protocol 2.0
+        out.extend_from_slice(&2132u16.to_le_bytes()); // profile 21.32
+        out.extend_from_slice(&(self.body.len() as u32).to_le_bytes());
+        out.extend_from_slice(b".FIT");
+        let header_crc = fit_crc(&out);
+        out.extend_from_slice(&header_crc.to_le_bytes());
+        out.extend_from_slice(&self.body);
+        let file_crc = fit_crc(&out);
+        out.extend_from_slice(&file_crc.to_le_bytes());
+        out
+    }
+}
+
+/// CRC-16 as specified by the FIT protocol.
+fn fit_crc(bytes: &[u8]) -> u16 {
+    const TABLE: [u16; 16] = [
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800,
+        0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    ];
+    let mut crc: u16 = 0;
+    for &byte in bytes {
+        for nibble in [byte & 0x0F, byte >> 4] {
+            let tmp = TABLE[(crc & 0x0F) as usize];
+            crc = ((crc >> 4) & 0x0FFF) ^ tmp ^ TABLE[nibble as usize];
+        }
+    }
+    crc
+}
+
+/// FIT sport enum for an activity sport type.
+fn fit_sport(sport: &str) -> u8 {
+    let sport = sport.to_ascii_lowercase();
+    if sport.contains("ride") || sport.contains("cycl") || sport.contains("bike") {
+        2
+    } else if sport.contains("run") {
+        1
+    } else if sport.contains("swim") {
+        5
+    } else if sport.contains("walk") {
+        11
+    } else if sport.contains("hike") {
+        17
+    } else {
+        0
+    }
+}
+
+/// Pace of the virtual partner in m/s. Course records need timestamps, so
+/// points are spaced as if travelled at this speed.
+fn virtual_speed(fit_sport: u8) -> f64 {
+    match fit_sport {
+        2 => 7.0,
+        1 => 3.0,
+        5 => 1.0,
+        _ => 1.4,
+    }
+}
+
+fn semicircles(degrees: f64) -> i32 {
+    (degrees * (2_147_483_648.0 / 180.0)).round() as i32
+}
+
+/// Generate a FIT course file: file_id, course, lap, timer start, one record
+/// per point, timer stop.
+fn generate_fit_course(
+    name: &str,
+    sport: &str,
+    points: &[GpsPoint],
+    distances: &[f64],
+    created: i64,
+) -> Vec<u8> {
+    let sport = fit_sport(sport);
+    let speed = virtual_speed(sport);
+    let start = (created - FIT_EPOCH_OFFSET) as u32;
+    let total_distance = distances.last().copied().unwrap_or(0.0);
+    let total_secs = total_distance / speed;
+    let end = start + total_secs.round() as u32;
+    let first = &points[0];
+    let last = &points[points.len() - 1];
+
+    // Course names are NUL-terminated; keep them short for head unit displays
+    let mut name_len = name.len().min(63);
+    while !name.is_char_boundary(name_len) {
+        name_len -= 1;
+    }
+    let mut name_bytes = name.as_bytes()[..name_len].to_vec();
+    name_bytes.push(0);
+
+    let mut fit = FitWriter::new();
+
+    // file_id: type = course, manufacturer = development
+    fit.define(
+        0,
+        MESG_FILE_ID,
+        &[
+            (0, 1, FIT_ENUM),
+            (1, 2, FIT_UINT16),
+            (2, 2, FIT_UINT16),
+            (4, 4, FIT_UINT32),
+        ],
+    );
+    let buf = fit.data(0);
+    buf.push(6);
+    buf.extend_from_slice(&255u16.to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes());
+    buf.extend_from_slice(&start.to_le_bytes());
+
+    fit.define(
+        1,
+        MESG_COURSE,
+        &[(5, name_bytes.len() as u8, FIT_STRING), (4, 1, FIT_ENUM)],
+    );
+    let buf = fit.data(1);
+    buf.extend_from_slice(&name_bytes);
+    buf.push(sport);
+
+    fit.define(
+        2,
+        MESG_LAP,
+        &[
+            (253, 4, FIT_UINT32),
+            (2, 4, FIT_UINT32),
+            (3, 4, FIT_SINT32),
+            (4, 4, FIT_SINT32),
+            (5, 4, FIT_SINT32),
+            (6, 4, FIT_SINT32),
+            (7, 4, FIT_UINT32),
+            (8, 4, FIT_UINT32),
+            (9, 4, FIT_UINT32),
+        ],
+    );
+    let buf = fit.data(2);
+    buf.extend_from_slice(&start.to_le_bytes());
+    buf.extend_from_slice(&start.to_le_bytes());
+    buf.extend_from_slice(&semicircles(first.latitude).to_le_bytes());
+    buf.extend_from_slice(&semicircles(first.longitude).to_le_bytes());
+    buf.extend_from_slice(&semicircles(last.latitude).to_le_bytes());
+    buf.extend_from_slice(&semicircles(last.longitude).to_le_bytes());
+    let elapsed_ms = (total_secs * 1000.0).round() as u32;
+    buf.extend_from_slice(&elapsed_ms.to_le_bytes());
+    buf.extend_from_slice(&elapsed_ms.to_le_bytes());
+    buf.extend_from_slice(&((total_distance * 100.0).round() as u32).to_le_bytes());
+
+    // event: timer (0), start (0) / stop_all (4)
+    fit.define(
+        3,
+        MESG_EVENT,
+        &[
+            (253, 4, FIT_UINT32),
+            (0, 1, FIT_ENUM),
+            (1, 1, FIT_ENUM),
+            (4, 1, FIT_UINT8),
+        ],
+    );
+    let buf = fit.data(3);
+    buf.extend_from_slice(&start.to_le_bytes());
+    buf.extend_from_slice(&[0, 0, 0]);
+
+    fit.define(
+        4,
+        MESG_RECORD,
+        &[
+            (253, 4, FIT_UINT32),
+            (0, 4, FIT_SINT32),
+            (1, 4, FIT_SINT32),
+            (2, 2, FIT_UINT16),
+            (5, 4, FIT_UINT32),
+        ],
+    );
+    for (p, &distance) in points.iter().zip(distances) {
+        // altitude: scale 5, offset 500; 0xFFFF marks it invalid
+        let altitude = p
+            .elevation
+            .filter(|e| e.is_finite())
+            .map(|e| ((e + 500.0) * 5.0).round().clamp(0.0, 65_534.0) as u16)
+            .unwrap_or(u16::MAX);
+        let buf = fit.data(4);
+        buf.extend_from_slice(&(start + (distance / speed).round() as u32).to_le_bytes());
+        buf.extend_from_slice(&semicircles(p.latitude).to_le_bytes());
+        buf.extend_from_slice(&semicircles(p.longitude).to_le_bytes());
+        buf.extend_from_slice(&altitude.to_le_bytes());
+        buf.extend_from_slice(&((distance * 100.0).round() as u32).to_le_bytes());
+    }
+
+    let buf = fit.data(3);
+    buf.extend_from_slice(&end.to_le_bytes());
+    buf.extend_from_slice(&[0, 4, 0]);
+
+    fit.finish()
+}
+
 /// Generate GPX 1.1 XML for a single activity.
 fn generate_gpx(name: &str, sport: &str, time: Option<&str>, points: &[GpsPoint]) -> String {
     let escaped_name = escape_xml(name);
@@ -402,3 +834,108 @@ fn escape_xml(s: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&apos;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn course() -> Vec<GpsPoint> {
+        (0..20)
+            .map(|i| GpsPoint {
+                latitude: 47.0 + i as f64 * 0.001,
+                longitude: 7.0,
+                elevation: Some(400.0 + i as f64),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gpx_course_has_elevation_and_distance() {
+        let points = course();
+        let distance = *cumulative_distances(&points).last().unwrap();
+        assert!((distance - 19.0 * 111.2).abs() < 5.0);
+
+        let trk = generate_gpx_course("Lake <Loop>", "Ride", &points, distance, false);
+        assert!(trk.contains("<name>Lake &lt;Loop&gt;</name>"));
+        assert!(trk.contains("<trkseg>"));
+        assert!(trk.contains("<ele>419.0</ele>"));
+        assert_eq!(trk.matches("<trkpt ").count(), 20);
+
+        let rte = generate_gpx_course("Lake", "Ride", &points, distance, true);
+        assert!(!rte.contains("<trk>"));
+        assert_eq!(rte.matches("<rtept ").count(), 20);
+    }
+
+    #[test]
+    fn section_course_takes_elevation_from_the_altitude_stream() {
+        use crate::net::types::ParsedStreams;
+        use crate::sections::CreateSectionParams;
+
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        let bare: Vec<GpsPoint> = course()
+            .iter()
+            .map(|p| GpsPoint::new(p.latitude, p.longitude))
+            .collect();
+        engine
+            .add_activity("a1".to_string(), bare.clone(), "Ride".to_string())
+            .unwrap();
+        let streams = ParsedStreams {
+            time: (0..20).collect(),
+            altitude: (0..20).map(|i| 400.0 + i as f64).collect(),
+            ..Default::default()
+        };
+        engine.store_activity_streams("a1", &streams).unwrap();
+        let section_id = engine
+            .create_section(CreateSectionParams {
+                sport_type: "Ride".to_string(),
+                distance_meters: *cumulative_distances(&bare).last().unwrap(),
+                polyline: bare,
+                name: Some("Lake".to_string()),
+                source_activity_id: Some("a1".to_string()),
+                start_index: Some(0),
+                end_index: Some(19),
+            })
+            .unwrap();
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let dest = dir.path().join("lake.gpx");
+        engine
+            .export_section_course(&section_id, "gpx", dest.to_str().unwrap())
+            .unwrap();
+        let gpx = std::fs::read_to_string(&dest).unwrap();
+        assert_eq!(gpx.matches("<ele>").count(), 20);
+        assert!(gpx.contains("<ele>400.0</ele>"));
+        assert!(gpx.contains("<ele>419.0</ele>"));
+    }
+
+    #[test]
+    fn fit_course_round_trips_through_parser() {
+        use fitparser::profile::MesgNum;
+
+        let points = course();
+        let distances = cumulative_distances(&points);
+        let bytes = generate_fit_course("Lake Loop", "Ride", &points, &distances, 1_750_000_000);
+
+        // A FIT file's CRC over itself, trailing CRC included, is zero
+        assert_eq!(fit_crc(&bytes), 0);
+
+        let records = fitparser::from_bytes(&bytes).expect("course should parse");
+        assert_eq!(
+            records
+                .iter()
+                .filter(|r| r.kind() == MesgNum::Record)
+                .count(),
+            20
+        );
+        let course = records
+            .iter()
+            .find(|r| r.kind() == MesgNum::Course)
+            .expect("course message");
+        let name = course
+            .fields()
+            .iter()
+            .find(|f| f.name() == "name")
+            .map(|f| f.value().to_string());
+        assert_eq!(name.as_deref(), Some("Lake Loop"));
+    }
+}
@@ -0,0 +1,680 @@
+//! Best efforts over standard distances and durations, from stored streams.
+//!
+//! Independent of section detection: each activity's `time` series is
+//! scanned with sliding windows for the fastest time over each target
+//! distance (`distance` series) and the best mean power / heart rate over
+//! each target duration (`watts` / `heartrate`, resampled to 1 Hz). Results
+//! are materialized per activity in `activity_best_efforts` as streams are
+//! stored; `best_effort_scans` records the target set each activity was
+//! scanned with, so a changed set rescans only what is stale. The table
+//! feeds all-time / season / 90-day records and the `effort_pr` /
+//! `effort_season_pr` activity indicators.
+
+use chrono::{Datelike, Days, Local, NaiveDate, Utc};
+use rusqlite::{Result as SqlResult, Transaction, params};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::net::types::ParsedStreams;
+use crate::persistence::settings_keys;
+use crate::{FfiBestEffort, FfiBestEffortRecord, FfiBestEffortTargets};
+
+use super::super::PersistentRouteEngine;
+use super::super::streams::EMPTY_STREAMS_MARKER;
+use super::local_midnight;
+
+/// 400 m, 1 km, 1 mile, 5 km, 10 km, half marathon, marathon.
+const DEFAULT_DISTANCES: &[f64] = &[
+    400.0, 1000.0, 1609.344, 5000.0, 10_000.0, 21_097.5, 42_195.0,
+];
+/// 5 s, 1 min, 5 min, 20 min, 60 min.
+const DEFAULT_DURATIONS: &[u32] = &[5, 60, 300, 1200, 3600];
+/// Longest gap (seconds) a sample is held across when resampling to 1 Hz;
+/// the rest of a longer gap counts as zero.
+const MAX_HOLD_S: i64 = 5;
+/// Speed (m/s) above which a single distance step is a GPS glitch.
+const MAX_STEP_SPEED: f64 = 40.0;
+/// Efforts a target needs (per sport, per season) before its best is badged.
+const MIN_EFFORTS_FOR_PR: usize = 2;
+/// Trailing window for the "recent" record.
+const RECENT_DAYS: u64 = 90;
+
+/// Distances (metres) and durations (seconds) to scan for.
+#[derive(Debug, Clone, PartialEq)]
+struct EffortTargets {
+    distances: Vec<f64>,
+    durations: Vec<u32>,
+}
+
+impl EffortTargets {
+    /// Sorted, de-duplicated, positive targets.
+    fn new(mut distances: Vec<f64>, mut durations: Vec<u32>) -> Self {
+        distances.retain(|d| d.is_finite() && *d > 0.0);
+        distances.sort_by(f64::total_cmp);
+        distances.dedup();
+        durations.retain(|&d| d > 0);
+        durations.sort_unstable();
+        durations.dedup();
+        Self {
+            distances,
+            durations,
+        }
+    }
+
+    /// Targets from the stored settings; an unset key means the defaults.
+    fn from_settings(distances: Option<&str>, durations: Option<&str>) -> Self {
+        Self::new(
+            distances.map_or_else(|| DEFAULT_DISTANCES.to_vec(), parse_list),
+            durations.map_or_else(|| DEFAULT_DURATIONS.to_vec(), parse_list),
+        )
+    }
+
+    /// Fingerprint stored per scanned activity.
+    fn key(&self) -> String {
+        format!(
+            "d={};t={}",
+            join_list(&self.distances),
+            join_list(&self.durations)
+        )
+    }
+
+    /// Every (effort_type, target) pair, distances first.
+    fn pairs(&self) -> Vec<(&'static str, f64)> {
+        let durations = self.durations.iter().map(|&d| d as f64);
+        self.distances
+            .iter()
+            .map(|&d| ("distance", d))
+            .chain(durations.clone().map(|d| ("power", d)))
+            .chain(durations.map(|d| ("heartrate", d)))
+            .collect()
+    }
+}
+
+fn parse_list<T: FromStr>(value: &str) -> Vec<T> {
+    value
+        .split(',')
+        .filter_map(|v| v.trim().parse().ok())
+        .collect()
+}
+
+fn join_list<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// One activity's best effort over one target.
+#[derive(Debug, Clone, PartialEq)]
+struct Effort {
+    effort_type: &'static str,
+    target: f64,
+    value: f64,
+    start_offset: f64,
+}
+
+/// Fastest `(elapsed, start_offset)` over `target` metres. The window start
+/// is interpolated between samples so every window covers exactly `target`;
+/// no window spans a glitch step.
+fn fastest_distance(times: &[f64], distance: &[f64], target: f64) -> Option<(f64, f64)> {
+    let mut t = Vec::with_capacity(times.len());
+    let mut d: Vec<f64> = Vec::with_capacity(times.len());
+    for (&time, &dist) in times.iter().zip(distance) {
+        if time.is_finite() && dist.is_finite() {
+            t.push(time);
+            // Distance never runs backwards
+            d.push(d.last().map_or(dist, |&last: &f64| last.max(dist)));
+        }
+    }
+    let origin = *t.first()?;
+
+    let mut best: Option<(f64, f64)> = None;
+    let mut i = 0;
+    for j in 0..d.len() {
+        if j > 0 && d[j] - d[j - 1] > MAX_STEP_SPEED * (t[j] - t[j - 1]).max(1.0) {
+            i = j;
+            continue;
+        }
+        while i + 1 < j && d[j] - d[i + 1] >= target {
+            i += 1;
+        }
+        let covered = d[j] - d[i];
+        if covered < target {
+            continue;
+        }
+        let start = if i < j && d[i + 1] > d[i] {
+            t[i] + (covered - target) / (d[i + 1] - d[i]) * (t[i + 1] - t[i])
+        } else {
+            t[i]
+        };
+        let elapsed = t[j] - start;
+        if elapsed <= 0.0 {
+            continue;
+        }
+        if best.is_none_or(|(b, _)| elapsed < b) {
+            best = Some((elapsed, start - origin));
+        }
+    }
+    best
+}
+
+/// Resample to one value per second. Non-finite samples count as zero.
+fn per_second(times: &[f64], values: &[f64]) -> Vec<f64> {
+    let n = times.len().min(values.len());
+    let mut out = Vec::new();
+    for (pair, &v) in times[..n].windows(2).zip(&values[..n]) {
+        let v = if v.is_finite() { v } else { 0.0 };
+        let gap = (pair[1] - pair[0]).round() as i64;
+        for s in 0..gap.max(0) {
+            out.push(if s < MAX_HOLD_S { v } else { 0.0 });
+        }
+    }
+    out
+}
+
+/// Best `(mean, start_offset)` over `secs` consecutive 1 Hz samples.
+fn best_mean(samples: &[f64], secs: u32) -> Option<(f64, f64)> {
+    let d = secs as usize;
+    if d == 0 || samples.len() < d {
+        return None;
+    }
+    let mut sum: f64 = samples[..d].iter().sum();
+    let (mut best, mut best_start) = (sum, 0);
+    for (start, (&entering, &leaving)) in samples[d..].iter().zip(samples).enumerate() {
+        sum += entering - leaving;
+        if sum > best {
+            best = sum;
+            best_start = start + 1;
+        }
+    }
+    Some((best / d as f64, best_start as f64))
+}
+
+/// All best efforts in one activity's streams. Series whose length does not
+/// match `time` are skipped.
+fn activity_efforts(streams: &ParsedStreams, targets: &EffortTargets) -> Vec<Effort> {
+    let times: Vec<f64> = streams.time.iter().map(|&t| t as f64).collect();
+    let mut efforts = Vec::new();
+    if times.is_empty() {
+        return efforts;
+    }
+    if streams.distance.len() == times.len() {
+        for &target in &targets.distances {
+            if let Some((elapsed, start_offset)) =
+                fastest_distance(&times, &streams.distance, target)
+            {
+                efforts.push(Effort {
+                    effort_type: "distance",
+                    target,
+                    value: elapsed,
+                    start_offset,
+                });
+            }
+        }
+    }
+    for (effort_type, series) in [("power", &streams.watts), ("heartrate", &streams.heartrate)] {
+        if series.len() != times.len() {
+            continue;
+        }
+        let samples = per_second(&times, series);
+        for &secs in &targets.durations {
+            if let Some((mean, start_offset)) = best_mean(&samples, secs).filter(|(m, _)| *m > 0.0)
+            {
+                efforts.push(Effort {
+                    effort_type,
+                    target: secs as f64,
+                    value: mean,
+                    start_offset,
+                });
+            }
+        }
+    }
+    efforts
+}
+
+/// Whether `(value, date)` beats `other`: faster for distance, higher
+/// otherwise, the earlier effort on a tie.
+fn beats(effort_type: &str, (value, date): (f64, i64), (other, other_date): (f64, i64)) -> bool {
+    if value == other {
+        date < other_date
+    } else if effort_type == "distance" {
+        value < other
+    } else {
+        value > other
+    }
+}
+
+/// (activity_id, value, date) of one effort, for indicator ranking.
+type IndicatorEffort = (String, f64, i64);
+
+fn best_of<'a>(effort_type: &str, efforts: &[&'a IndicatorEffort]) -> Option<&'a IndicatorEffort> {
+    efforts.iter().copied().fold(None, |best, e| match best {
+        Some(b) if !beats(effort_type, (e.1, e.2), (b.1, b.2)) => Some(b),
+        _ => Some(e),
+    })
+}
+
+fn duration_label(secs: f64) -> String {
+    let s = secs as u32;
+    if s < 60 || s % 60 != 0 {
+        format!("{} s", s)
+    } else if s <= 3600 || s % 3600 != 0 {
+        format!("{} min", s / 60)
+    } else {
+        format!("{} h", s / 3600)
+    }
+}
+
+/// Display label, e.g. "5 km", "1 mile", "20 min power", "60 min HR".
+fn effort_label(effort_type: &str, target: f64) -> String {
+    match effort_type {
+        "distance" if (target - 1609.344).abs() < 0.5 => "1 mile".to_string(),
+        "distance" if (target - 21_097.5).abs() < 1.0 => "Half marathon".to_string(),
+        "distance" if (target - 42_195.0).abs() < 1.0 => "Marathon".to_string(),
+        "distance" if target >= 1000.0 => format!("{} km", target / 1000.0),
+        "distance" => format!("{} m", target),
+        "power" => format!("{} power", duration_label(target)),
+        _ => format!("{} HR", duration_label(target)),
+    }
+}
+
+fn best_effort_from_row(row: &rusqlite::Row) -> SqlResult<FfiBestEffort> {
+    let effort_type: String = row.get(2)?;
+    let target: f64 = row.get(3)?;
+    Ok(FfiBestEffort {
+        activity_id: row.get(0)?,
+        sport_type: row.get(1)?,
+        label: effort_label(&effort_type, target),
+        effort_type,
+        target,
+        value: row.get(4)?,
+        start_offset: row.get(5)?,
+        date: row.get(6)?,
+    })
+}
+
+impl PersistentRouteEngine {
+    fn best_effort_targets(&self) -> SqlResult<EffortTargets> {
+        Ok(EffortTargets::from_settings(
+            self.get_setting(settings_keys::BEST_EFFORT_DISTANCES)?
+                .as_deref(),
+            self.get_setting(settings_keys::BEST_EFFORT_DURATIONS)?
+                .as_deref(),
+        ))
+    }
+
+    /// Distances and durations currently scanned for.
+    pub fn get_best_effort_targets(&self) -> SqlResult<FfiBestEffortTargets> {
+        let targets = self.best_effort_targets()?;
+        Ok(FfiBestEffortTargets {
+            distances_meters: targets.distances,
+            durations_secs: targets.durations,
+        })
+    }
+
+    /// Replace the scanned targets, rescan every activity against them and
+    /// rebuild the effort indicators. Returns the number of activities
+    /// rescanned.
+    pub fn set_best_effort_targets(&self, distances: &[f64], durations: &[u32]) -> SqlResult<u32> {
+        let targets = EffortTargets::new(distances.to_vec(), durations.to_vec());
+        self.set_setting(
+            settings_keys::BEST_EFFORT_DISTANCES,
+            &join_list(&targets.distances),
+        )?;
+        self.set_setting(
+            settings_keys::BEST_EFFORT_DURATIONS,
+            &join_list(&targets.durations),
+        )?;
+        let scanned = self.update_best_efforts()?;
+        self.refresh_effort_indicators()?;
+        Ok(scanned)
+    }
+
+    /// Scan streams just stored for `activity_id`. Returns false (leaving the
+    /// activity for `update_best_efforts`) when its metrics are not known yet.
+    pub(crate) fn store_best_efforts(
+        &self,
+        activity_id: &str,
+        streams: &ParsedStreams,
+    ) -> SqlResult<bool> {
+        let Some(metrics) = self.activity_metrics.get(activity_id) else {
+            return Ok(false);
+        };
+        let targets = self.best_effort_targets()?;
+        self.write_best_efforts(
+            activity_id,
+            &metrics.sport_type,
+            metrics.date,
+            streams,
+            &targets,
+        )?;
+        Ok(true)
+    }
+
+    fn write_best_efforts(
+        &self,
+        activity_id: &str,
+        sport_type: &str,
+        date: i64,
+        streams: &ParsedStreams,
+        targets: &EffortTargets,
+    ) -> SqlResult<()> {
+        let efforts = activity_efforts(streams, targets);
+        let tx = self.db.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM activity_best_efforts WHERE activity_id = ?",
+            params![activity_id],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO activity_best_efforts
+                 (activity_id, effort_type, target, value, start_offset, sport_type, date)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            for e in &efforts {
+                stmt.execute(params![
+                    activity_id,
+                    e.effort_type,
+                    e.target,
+                    e.value,
+                    e.start_offset,
+                    sport_type,
+                    date
+                ])?;
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO best_effort_scans (activity_id, targets_key) VALUES (?, ?)",
+            params![activity_id, targets.key()],
+        )?;
+        tx.commit()
+    }
+
+    /// Scan every activity with stored streams that has not been scanned
+    /// with the current targets (new streams, pre-existing history or a
+    /// changed target set). Returns the number of activities scanned.
+    pub fn update_best_efforts(&self) -> SqlResult<u32> {
+        let targets = self.best_effort_targets()?;
+        let key = targets.key();
+        let ids: Vec<String> = {
+            let mut stmt = self.db.prepare(
+                "SELECT DISTINCT s.activity_id FROM activity_streams s
+                 LEFT JOIN best_effort_scans b ON b.activity_id = s.activity_id
+                 WHERE s.stream_type != ?
+                   AND (b.targets_key IS NULL OR b.targets_key != ?)",
+            )?;
+            stmt.query_map(params![EMPTY_STREAMS_MARKER, key], |row| row.get(0))?
+                .collect::<SqlResult<_>>()?
+        };
+
+        let mut scanned = 0;
+        for id in &ids {
+            let Some(metrics) = self.activity_metrics.get(id) else {
+                continue;
+            };
+            let Some(streams) = self.get_activity_streams(id)? else {
+                continue;
+            };
+            self.write_best_efforts(id, &metrics.sport_type, metrics.date, &streams, &targets)?;
+            scanned += 1;
+        }
+        if scanned > 0 {
+            log::info!(
+                "tracematch: [BestEfforts] Scanned {} activities for best efforts",
+                scanned
+            );
+        }
+        Ok(scanned)
+    }
+
+    /// Best efforts found in one activity, distances first.
+    pub fn get_activity_best_efforts(&self, activity_id: &str) -> SqlResult<Vec<FfiBestEffort>> {
+        let mut stmt = self.db.prepare(
+            "SELECT activity_id, sport_type, effort_type, target, value, start_offset, date
+             FROM activity_best_efforts WHERE activity_id = ?
+             ORDER BY effort_type != 'distance', effort_type, target",
+        )?;
+        stmt.query_map(params![activity_id], best_effort_from_row)?
+            .collect()
+    }
+
+    /// All-time, season and 90-day bests for every target with at least one
+    /// effort in `sport_type`. Reads what sync and target changes scanned.
+    pub fn get_best_effort_records(&self, sport_type: &str) -> SqlResult<Vec<FfiBestEffortRecord>> {
+        let targets = self.best_effort_targets()?;
+        let today = Local::now().date_naive();
+        let season_start = NaiveDate::from_ymd_opt(today.year(), 1, 1).and_then(local_midnight);
+        let recent_start = today
+            .checked_sub_days(Days::new(RECENT_DAYS))
+            .and_then(local_midnight);
+
+        let mut records = Vec::new();
+        for (effort_type, target) in targets.pairs() {
+            let best_since = |since: Option<i64>| match since {
+                Some(since) => self.best_effort_since(sport_type, effort_type, target, since),
+                None => Ok(None),
+            };
+            let Some(all_time) = best_since(Some(i64::MIN))? else {
+                continue;
+            };
+            records.push(FfiBestEffortRecord {
+                effort_type: effort_type.to_string(),
+                target,
+                label: effort_label(effort_type, target),
+                all_time: Some(all_time),
+                season: best_since(season_start)?,
+                last_90_days: best_since(recent_start)?,
+            });
+        }
+        Ok(records)
+    }
+
+    fn best_effort_since(
+        &self,
+        sport_type: &str,
+        effort_type: &str,
+        target: f64,
+        since: i64,
+    ) -> SqlResult<Option<FfiBestEffort>> {
+        self.db
+            .query_row(
+                "SELECT activity_id, sport_type, effort_type, target, value, start_offset, date
+                 FROM activity_best_efforts
+                 WHERE sport_type = ? AND effort_type = ? AND target = ? AND date >= ?
+                 ORDER BY CASE WHEN effort_type = 'distance' THEN value ELSE -value END, date
+                 LIMIT 1",
+                params![sport_type, effort_type, target, since],
+                best_effort_from_row,
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                other => Err(other),
+            })
+    }
+
+    /// Rebuild only the `effort_*` indicators. Sync runs this once after
+    /// storing new streams.
+    pub fn refresh_effort_indicators(&self) -> SqlResult<usize> {
+        let tx = self.db.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM activity_indicators
+             WHERE indicator_type IN ('effort_pr', 'effort_season_pr')",
+            [],
+        )?;
+        let count = self.compute_effort_indicators(&tx, Utc::now().timestamp())?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    /// Insert `effort_pr` for each (sport, target)'s all-time best and
+    /// `effort_season_pr` for each calendar year's best that is not also the
+    /// all-time best. Returns the number of indicators inserted.
+    pub(crate) fn compute_effort_indicators(&self, tx: &Transaction, now: i64) -> SqlResult<usize> {
+        let mut groups: HashMap<(String, String, u64), Vec<IndicatorEffort>> = HashMap::new();
+        {
+            let mut stmt = tx.prepare(
+                "SELECT activity_id, sport_type, effort_type, target, value, date
+                 FROM activity_best_efforts",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, f64>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            })?;
+            for row in rows {
+                let (activity_id, sport, effort_type, target, value, date) = row?;
+                groups
+                    .entry((sport, effort_type, target.to_bits()))
+                    .or_default()
+                    .push((activity_id, value, date));
+            }
+        }
+
+        let mut insert_stmt = tx.prepare(
+            "INSERT OR REPLACE INTO activity_indicators
+             (activity_id, indicator_type, target_id, target_name, direction, lap_time, trend, computed_at)
+             VALUES (?, ?, ?, ?, 'same', ?, 1, ?)",
+        )?;
+
+        let mut total = 0;
+        for ((sport, effort_type, bits), efforts) in &groups {
+            if efforts.len() < MIN_EFFORTS_FOR_PR {
+                continue;
+            }
+            let target = f64::from_bits(*bits);
+            let target_id = format!("{}:{}:{}", sport, effort_type, target);
+            let label = effort_label(effort_type, target);
+            let all: Vec<_> = efforts.iter().collect();
+            let Some(best) = best_of(effort_type, &all) else {
+                continue;
+            };
+            insert_stmt.execute(params![best.0, "effort_pr", target_id, label, best.1, now])?;
+            total += 1;
+
+            let mut seasons: HashMap<i32, Vec<&IndicatorEffort>> = HashMap::new();
+            for e in efforts {
+                if let Some(dt) = chrono::DateTime::from_timestamp(e.2, 0) {
+                    seasons
+                        .entry(dt.with_timezone(&Local).year())
+                        .or_default()
+                        .push(e);
+                }
+            }
+            for season in seasons.values() {
+                if season.len() < MIN_EFFORTS_FOR_PR {
+                    continue;
+                }
+                if let Some(season_best) = best_of(effort_type, season).filter(|s| s.0 != best.0) {
+                    insert_stmt.execute(params![
+                        season_best.0,
+                        "effort_season_pr",
+                        target_id,
+                        label,
+                        season_best.1,
+                        now
+                    ])?;
+                    total += 1;
+                }
+            }
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fastest_distance_finds_interpolated_window() {
+        // 4 m/s for 1000 s, with 5 m/s between 100 s and 300 s, 1 Hz
+        let times: Vec<f64> = (0..=1000).map(|s| s as f64).collect();
+        let mut distance = vec![0.0];
+        for s in 1..=1000 {
+            let speed = if (101..=300).contains(&s) { 5.0 } else { 4.0 };
+            distance.push(distance[s - 1] + speed);
+        }
+        let (elapsed, start) = fastest_distance(&times, &distance, 1000.0).unwrap();
+        assert!((elapsed - 200.0).abs() < 1e-9);
+        assert!((start - 100.0).abs() < 1e-9);
+        // Off-grid target: half a sample at 4 m/s on top of the fast block
+        let (elapsed, _) = fastest_distance(&times, &distance, 1002.0).unwrap();
+        assert!((elapsed - 200.5).abs() < 1e-9);
+        assert_eq!(fastest_distance(&times, &distance, 5000.0), None);
+
+        // A 2 km GPS jump in one second is not a record
+        let mut glitch = distance.clone();
+        for d in glitch.iter_mut().skip(500) {
+            *d += 2000.0;
+        }
+        let (elapsed, _) = fastest_distance(&times, &glitch, 1000.0).unwrap();
+        assert!((elapsed - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn best_mean_over_resampled_stream() {
+        // 20 min at 200 W with 60 s at 400 W; a 10 s dropout near the end
+        let times: Vec<f64> = (0..1190).map(|s| s as f64).chain([1200.0]).collect();
+        let watts: Vec<f64> = (0..1191)
+            .map(|s| {
+                if (300..360).contains(&s) {
+                    400.0
+                } else {
+                    200.0
+                }
+            })
+            .collect();
+        let samples = per_second(&times, &watts);
+        assert_eq!(samples.len(), 1200);
+        assert_eq!(samples[1195], 0.0);
+        assert_eq!(best_mean(&samples, 60), Some((400.0, 300.0)));
+        assert_eq!(best_mean(&samples, 1201), None);
+
+        let streams = ParsedStreams {
+            time: (0..1190).chain([1200]).collect(),
+            watts,
+            ..Default::default()
+        };
+        let targets = EffortTargets::new(vec![1000.0], vec![5, 60, 1200]);
+        let efforts = activity_efforts(&streams, &targets);
+        assert_eq!(efforts.len(), 3);
+        assert!(efforts.iter().all(|e| e.effort_type == "power"));
+        assert_eq!(efforts[2].target, 1200.0);
+        assert!((efforts[2].value - (1194.0 * 200.0 + 60.0 * 200.0) / 1200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn targets_labels_and_ordering() {
+        let defaults = EffortTargets::from_settings(None, None);
+        assert_eq!(defaults.distances, DEFAULT_DISTANCES);
+        let parsed = EffortTargets::from_settings(Some("5000, 1000,abc,-1,1000"), Some(""));
+        assert_eq!(parsed.distances, vec![1000.0, 5000.0]);
+        assert!(parsed.durations.is_empty());
+        assert_eq!(parsed.key(), "d=1000,5000;t=");
+        assert_eq!(
+            parsed.pairs(),
+            vec![("distance", 1000.0), ("distance", 5000.0)]
+        );
+
+        assert_eq!(effort_label("distance", 400.0), "400 m");
+        assert_eq!(effort_label("distance", 5000.0), "5 km");
+        assert_eq!(effort_label("distance", 1609.344), "1 mile");
+        assert_eq!(effort_label("distance", 42_195.0), "Marathon");
+        assert_eq!(effort_label("power", 1200.0), "20 min power");
+        assert_eq!(effort_label("heartrate", 3600.0), "60 min HR");
+        assert_eq!(effort_label("power", 5.0), "5 s power");
+        assert_eq!(effort_label("power", 7200.0), "2 h power");
+
+        assert!(beats("distance", (1190.0, 10), (1200.0, 5)));
+        assert!(beats("power", (310.0, 10), (300.0, 5)));
+        assert!(beats("power", (300.0, 5), (300.0, 10)));
+        assert!(!beats("heartrate", (160.0, 5), (170.0, 1)));
+    }
+}
@@ -0,0 +1,440 @@
+//! Critical power (CP / W') models for cycling.
+//!
+//! Best efforts at fixed durations come from the power curve stored by sync,
+//! or - when no curve has been fetched for the window - from the watts
+//! streams of the window's activities. Two models are fitted:
+//!
+//! - 2-parameter: `P = CP + W'/t`, linear in `1/t`, over 2-20 minutes.
+//! - 3-parameter (Morton): `P = CP + W'/(t + k)` with `k = W'/(Pmax - CP)`,
+//!   over 15 s - 30 min, solved by a linear fit for each `k` on a grid.
+//!
+//! The 3-parameter fit is kept when it is sound (positive parameters, R²
+//! above `MIN_THREE_PARAM_R2`), the 2-parameter fit otherwise. Each fit is
+//! stored as that day's snapshot in `cp_history`, which feeds the trend.
+//! Sync fits the 42-day window after refreshing the power curves.
+
+use chrono::{Datelike, Days, Local, NaiveDate};
+use rusqlite::{Result as SqlResult, params};
+
+use crate::{FfiCpTrend, FfiCriticalPowerFit};
+
+use super::super::PersistentRouteEngine;
+use super::super::curves::power_at;
+use super::local_midnight;
+
+/// Durations (seconds) at which best efforts are sampled.
+const EFFORT_DURATIONS: &[u32] = &[5, 10, 15, 30, 60, 120, 180, 300, 420, 600, 900, 1200, 1800];
+/// Duration range of the 2-parameter fit.
+const TWO_PARAM_RANGE: (f64, f64) = (120.0, 1200.0);
+/// Duration range of the 3-parameter fit.
+const THREE_PARAM_RANGE: (f64, f64) = (15.0, 1800.0);
+/// Largest time offset `k` (seconds) tried by the 3-parameter fit.
+const MAX_K: u32 = 300;
+/// Below this the 3-parameter fit is not trusted over the 2-parameter one.
+const MIN_THREE_PARAM_R2: f64 = 0.95;
+/// Gaps in a watts stream up to this long hold the last value; longer ones
+/// are treated as stopped (0 W).
+const MAX_HOLD_S: i64 = 5;
+/// Durations of the modelled power-duration curve returned with the trend.
+const MODEL_DURATIONS: &[i64] = &[
+    1, 5, 15, 30, 60, 120, 300, 600, 1200, 1800, 3600, 5400, 7200,
+];
+/// CP change (W) needed before a snapshot counts as a different value.
+const CP_TREND_THRESHOLD: f64 = 1.0;
+
+/// A fitted model, before it is tagged with its source and date.
+#[derive(Debug, Clone, PartialEq)]
+struct CpFit {
+    model: &'static str,
+    cp: f64,
+    w_prime: f64,
+    p_max: Option<f64>,
+    r2: f64,
+}
+
+/// Least squares `y = slope * x + intercept`, with R².
+fn linear_fit(xs: &[f64], ys: &[f64]) -> Option<(f64, f64, f64)> {
+    let n = xs.len() as f64;
+    if xs.len() < 3 {
+        return None;
+    }
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        sxx += (x - mean_x).powi(2);
+        sxy += (x - mean_x) * (y - mean_y);
+        syy += (y - mean_y).powi(2);
+    }
+    if sxx <= 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let r2 = if syy > 0.0 {
+        (sxy * sxy) / (sxx * syy)
+    } else {
+        1.0
+    };
+    Some((slope, intercept, r2))
+}
+
+fn in_range(efforts: &[(f64, f64)], (lo, hi): (f64, f64)) -> (Vec<f64>, Vec<f64>) {
+    efforts
+        .iter()
+        .filter(|(t, p)| *t >= lo && *t <= hi && *p > 0.0)
+        .copied()
+        .unzip()
+}
+
+/// 2-parameter model over `(seconds, watts)` best efforts.
+fn fit_two_param(efforts: &[(f64, f64)]) -> Option<CpFit> {
+    let (secs, watts) = in_range(efforts, TWO_PARAM_RANGE);
+    let inv: Vec<f64> = secs.iter().map(|t| 1.0 / t).collect();
+    let (w_prime, cp, r2) = linear_fit(&inv, &watts)?;
+    (cp > 0.0 && w_prime > 0.0).then_some(CpFit {
+        model: "2p",
+        cp,
+        w_prime,
+        p_max: None,
+        r2,
+    })
+}
+
+/// 3-parameter model: best R² over `k` = 1..=MAX_K seconds.
+fn fit_three_param(efforts: &[(f64, f64)]) -> Option<CpFit> {
+    let (secs, watts) = in_range(efforts, THREE_PARAM_RANGE);
+    if secs.len() < 4 {
+        return None;
+    }
+    (1..=MAX_K)
+        .filter_map(|k| {
+            let k = k as f64;
+            let inv: Vec<f64> = secs.iter().map(|t| 1.0 / (t + k)).collect();
+            let (w_prime, cp, r2) = linear_fit(&inv, &watts)?;
+            (cp > 0.0 && w_prime > 0.0).then_some(CpFit {
+                model: "3p",
+                cp,
+                w_prime,
+                p_max: Some(cp + w_prime / k),
+                r2,
+            })
+        })
+        .max_by(|a, b| a.r2.total_cmp(&b.r2))
+}
+
+fn fit_cp(efforts: &[(f64, f64)]) -> Option<CpFit> {
+    fit_three_param(efforts)
+        .filter(|f| f.r2 >= MIN_THREE_PARAM_R2)
+        .or_else(|| fit_two_param(efforts))
+}
+
+/// Modelled power (W) for `secs`. The 2-parameter model is only defined
+/// from the shortest fitted duration up.
+fn model_power(model: &str, cp: f64, w_prime: f64, p_max: Option<f64>, secs: f64) -> Option<f64> {
+    match (model, p_max) {
+        ("3p", Some(p_max)) if p_max > cp => Some(cp + w_prime / (secs + w_prime / (p_max - cp))),
+        _ if secs >= TWO_PARAM_RANGE.0 => Some(cp + w_prime / secs),
+        _ => None,
+    }
+}
+
+/// Best average power for each of `EFFORT_DURATIONS` in one activity, from
+/// its time and watts streams resampled to 1 Hz. None where the activity is
+/// shorter than the duration.
+fn mean_max_power(times: &[f64], watts: &[f64]) -> Vec<Option<f64>> {
+    let n = times.len().min(watts.len());
+    let mut per_second: Vec<f64> = Vec::new();
+    for (pair, &w) in times[..n].windows(2).zip(&watts[..n]) {
+        let w = if w.is_finite() { w } else { 0.0 };
+        let gap = (pair[1] - pair[0]).round() as i64;
+        for s in 0..gap.max(0) {
+            per_second.push(if s < MAX_HOLD_S { w } else { 0.0 });
+        }
+    }
+
+    let mut prefix = Vec::with_capacity(per_second.len() + 1);
+    prefix.push(0.0);
+    for w in &per_second {
+        prefix.push(prefix[prefix.len() - 1] + w);
+    }
+    EFFORT_DURATIONS
+        .iter()
+        .map(|&d| {
+            let d = d as usize;
+            (per_second.len() >= d).then(|| {
+                (d..prefix.len())
+                    .map(|end| prefix[end] - prefix[end - d])
+                    .fold(0.0, f64::max)
+                    / d as f64
+            })
+        })
+        .collect()
+}
+
+/// Local midnight of the first day covered by a curve window: "42d" style
+/// windows count back from today, "s0" is the current season (calendar
+/// year), anything else ("all") has no limit.
+fn window_start(window: &str, today: NaiveDate) -> Option<i64> {
+    let first = if let Some(days) = window.strip_suffix('d') {
+        today.checked_sub_days(Days::new(days.parse().ok()?))?
+    } else if window == "s0" {
+        NaiveDate::from_ymd_opt(today.year(), 1, 1)?
+    } else {
+        return None;
+    };
+    local_midnight(first)
+}
+
+impl PersistentRouteEngine {
+    /// Fit a CP model for `sport_type` over a curve window ("42d", "s0",
+    /// "all") and store it as today's snapshot. None when there are not
+    /// enough best efforts for either model.
+    pub fn fit_critical_power(
+        &self,
+        sport_type: &str,
+        window: &str,
+    ) -> SqlResult<Option<FfiCriticalPowerFit>> {
+        let (efforts, source) = match self.get_power_curve(sport_type, window)? {
+            Some((curve, _)) => (
+                EFFORT_DURATIONS
+                    .iter()
+                    .filter_map(|&d| power_at(&curve, d as f64).map(|p| (d as f64, p)))
+                    .collect::<Vec<_>>(),
+                "curve",
+            ),
+            None => (self.stream_best_efforts(sport_type, window)?, "streams"),
+        };
+        let Some(fit) = fit_cp(&efforts) else {
+            return Ok(None);
+        };
+
+        let now = Local::now();
+        let date = local_midnight(now.date_naive()).unwrap_or_else(|| now.timestamp());
+        self.db.execute(
+            "INSERT OR REPLACE INTO cp_history
+                (date, sport_type, model, cp, w_prime, p_max, r2, source, effort_count)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                date,
+                sport_type,
+                fit.model,
+                fit.cp,
+                fit.w_prime,
+                fit.p_max,
+                fit.r2,
+                source,
+                efforts.len() as u32
+            ],
+        )?;
+        log::info!(
+            "tracematch: [CriticalPower] {} {} fit from {}: CP {:.0} W, W' {:.0} J, R² {:.3}",
+            sport_type,
+            fit.model,
+            source,
+            fit.cp,
+            fit.w_prime,
+            fit.r2
+        );
+
+        Ok(Some(FfiCriticalPowerFit {
+            sport_type: sport_type.to_string(),
+            model: fit.model.to_string(),
+            cp: fit.cp,
+            w_prime: fit.w_prime,
+            p_max: fit.p_max,
+            r2: fit.r2,
+            source: source.to_string(),
+            effort_count: efforts.len() as u32,
+            date,
+        }))
+    }
+
+    /// Best efforts across the window's activities, from their watts streams.
+    fn stream_best_efforts(&self, sport_type: &str, window: &str) -> SqlResult<Vec<(f64, f64)>> {
+        let start = window_start(window, Local::now().date_naive()).unwrap_or(i64::MIN);
+        let ids: Vec<String> = {
+            let mut stmt = self.db.prepare(
+                "SELECT activity_id FROM activity_metrics
+                 WHERE sport_type = ? AND date >= ?",
+            )?;
+            stmt.query_map(params![sport_type, start], |row| row.get(0))?
+                .collect::<SqlResult<_>>()?
+        };
+
+        let mut best: Vec<Option<f64>> = vec![None; EFFORT_DURATIONS.len()];
+        for id in &ids {
+            let (Some(times), Some(watts)) = (
+                self.get_activity_stream(id, "time")?,
+                self.get_activity_stream(id, "watts")?,
+            ) else {
+                continue;
+            };
+            if times.len() != watts.len() {
+                continue;
+            }
+            for (slot, power) in best.iter_mut().zip(mean_max_power(&times, &watts)) {
+                if let Some(p) = power.filter(|p| *p > 0.0) {
+                    *slot = Some(slot.map_or(p, |b| b.max(p)));
+                }
+            }
+        }
+        Ok(EFFORT_DURATIONS
+            .iter()
+            .zip(best)
+            .filter_map(|(&d, p)| p.map(|p| (d as f64, p)))
+            .collect())
+    }
+
+    /// CP trend: latest and previous distinct CP snapshots, plus the
+    /// power-duration curve of the latest model.
+    pub fn get_cp_trend(&self, sport_type: &str) -> FfiCpTrend {
+        let mut trend = FfiCpTrend {
+            latest_cp: None,
+            latest_w_prime: None,
+            latest_p_max: None,
+            latest_r2: None,
+            latest_model: None,
+            latest_date: None,
+            previous_cp: None,
+            previous_w_prime: None,
+            previous_date: None,
+            model_secs: Vec::new(),
+            model_watts: Vec::new(),
+        };
+
+        type SnapshotRow = (i64, String, f64, f64, Option<f64>, Option<f64>);
+        let rows: Vec<SnapshotRow> = match self.db.prepare(
+            "SELECT date, model, cp, w_prime, p_max, r2 FROM cp_history
+             WHERE sport_type = ?
+             ORDER BY date DESC
+             LIMIT 20",
+        ) {
+            Ok(mut stmt) => stmt
+                .query_map(params![sport_type], |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                    ))
+                })
+                .ok()
+                .map(|iter| iter.flatten().collect())
+                .unwrap_or_default(),
+            Err(_) => return trend,
+        };
+
+        let Some((date, model, cp, w_prime, p_max, r2)) = rows.first().cloned() else {
+            return trend;
+        };
+        let previous = rows
+            .iter()
+            .find(|(_, _, prev_cp, ..)| (prev_cp - cp).abs() > CP_TREND_THRESHOLD);
+
+        for &secs in MODEL_DURATIONS {
+            if let Some(watts) = model_power(&model, cp, w_prime, p_max, secs as f64) {
+                trend.model_secs.push(secs);
+                trend.model_watts.push(watts);
+            }
+        }
+        trend.latest_cp = Some(cp);
+        trend.latest_w_prime = Some(w_prime);
+        trend.latest_p_max = p_max;
+        trend.latest_r2 = r2;
+        trend.latest_model = Some(model);
+        trend.latest_date = Some(date);
+        trend.previous_cp = previous.map(|p| p.2);
+        trend.previous_w_prime = previous.map(|p| p.3);
+        trend.previous_date = previous.map(|p| p.0);
+        trend
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn efforts(power: impl Fn(f64) -> f64) -> Vec<(f64, f64)> {
+        EFFORT_DURATIONS
+            .iter()
+            .map(|&d| (d as f64, power(d as f64)))
+            .collect()
+    }
+
+    #[test]
+    fn three_param_fit_recovers_model() {
+        // CP 250 W, W' 20 kJ, k = 20 s -> Pmax 1250 W
+        let fit = fit_cp(&efforts(|t| 250.0 + 20_000.0 / (t + 20.0))).unwrap();
+        assert_eq!(fit.model, "3p");
+        assert!((fit.cp - 250.0).abs() < 1e-6);
+        assert!((fit.w_prime - 20_000.0).abs() < 1e-3);
+        assert!((fit.p_max.unwrap() - 1250.0).abs() < 1e-3);
+        assert!(fit.r2 > 0.999_999);
+    }
+
+    #[test]
+    fn two_param_fit_and_fallback() {
+        let data = efforts(|t| 280.0 + 15_000.0 / t);
+        let two = fit_two_param(&data).unwrap();
+        assert!((two.cp - 280.0).abs() < 1e-6);
+        assert!((two.w_prime - 15_000.0).abs() < 1e-3);
+        assert!((two.r2 - 1.0).abs() < 1e-9);
+
+        // Too few efforts in the 3-parameter range -> 2-parameter model
+        let sparse: Vec<(f64, f64)> = data
+            .into_iter()
+            .filter(|(t, _)| (120.0..=600.0).contains(t))
+            .take(3)
+            .collect();
+        assert_eq!(fit_cp(&sparse).unwrap().model, "2p");
+        assert!(fit_cp(&sparse[..2]).is_none());
+
+        // 2-parameter curve starts at its shortest fitted duration
+        assert_eq!(model_power("2p", 280.0, 15_000.0, None, 60.0), None);
+        assert_eq!(model_power("2p", 280.0, 15_000.0, None, 300.0), Some(330.0));
+        let p1 = model_power("3p", 250.0, 20_000.0, Some(1250.0), 0.0).unwrap();
+        assert!((p1 - 1250.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mean_max_power_from_stream() {
+        // 20 min at 200 W with a 60 s block at 400 W, 1 Hz
+        let times: Vec<f64> = (0..1201).map(|s| s as f64).collect();
+        let watts: Vec<f64> = (0..1201)
+            .map(|s| {
+                if (300..360).contains(&s) {
+                    400.0
+                } else {
+                    200.0
+                }
+            })
+            .collect();
+        let mmp = mean_max_power(&times, &watts);
+        let at = |d: u32| mmp[EFFORT_DURATIONS.iter().position(|&x| x == d).unwrap()];
+        assert_eq!(at(5), Some(400.0));
+        assert_eq!(at(60), Some(400.0));
+        assert_eq!(at(120), Some(300.0));
+        assert_eq!(at(1200), Some(210.0));
+        assert_eq!(at(1800), None);
+    }
+
+    #[test]
+    fn window_start_dates() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let day = |y, m, d| {
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .timestamp()
+        };
+        assert_eq!(window_start("42d", today), Some(day(2026, 9, 5)));
+        assert_eq!(window_start("s0", today), Some(day(2026, 1, 1)));
+        assert_eq!(window_start("all", today), None);
+    }
+}
@@ -0,0 +1,386 @@
+//! Per-traversal effort metrics for section leaderboards.
+//!
+//! Average / normalized power, W/kg, cadence, HR drift and VAM for each
+//! `section_activities` row, computed from the stored activity streams over
+//! the portion's GPS index range and persisted next to `lap_time`. Stream
+//! samples only line up with GPS indices when the activity's time stream
+//! was stored (same length as the GPS track), so the streams are read
+//! through that time stream and ignored otherwise.
+
+use crate::SectionEffortMetrics;
+use crate::net::types::ParsedStreams;
+use chrono::Local;
+use rusqlite::{Result as SqlResult, params};
+
+use super::super::PersistentRouteEngine;
+
+/// Rolling window for normalized power.
+const NP_WINDOW_S: u32 = 30;
+/// Shortest effort that gets an HR drift figure; below this the two halves
+/// are dominated by HR lag.
+const MIN_DRIFT_S: u32 = 300;
+/// Altitude change needed before a rise counts towards VAM, to keep
+/// barometer noise out of the ascent.
+const ASCENT_HYSTERESIS_M: f64 = 2.0;
+
+/// `series[start..=end]` if the series has one sample per time-stream
+/// point, None otherwise.
+fn portion(series: &[f64], len: usize, start: usize, end: usize) -> Option<&[f64]> {
+    (series.len() == len && end < len).then(|| &series[start..=end])
+}
+
+/// Mean of the finite samples, optionally skipping zeros (coasting
+/// cadence, HR dropouts). None if nothing is left.
+fn mean(values: &[f64], skip_zero: bool) -> Option<f64> {
+    let (sum, n) = values
+        .iter()
+        .filter(|v| v.is_finite() && !(skip_zero && **v <= 0.0))
+        .fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+/// Normalized power: 30 s rolling average, raised to the 4th power,
+/// averaged, 4th root. Missing samples count as zero watts. None when the
+/// effort is shorter than one window.
+fn normalized_power(times: &[u32], watts: &[f64]) -> Option<f64> {
+    let mut lo = 0;
+    let mut window_sum = 0.0;
+    let mut acc = 0.0;
+    let mut count = 0usize;
+    for (i, &w) in watts.iter().enumerate() {
+        window_sum += if w.is_finite() { w } else { 0.0 };
+        while times[i].saturating_sub(times[lo]) >= NP_WINDOW_S {
+            let dropped = watts[lo];
+            window_sum -= if dropped.is_finite() { dropped } else { 0.0 };
+            lo += 1;
+        }
+        if times[i].saturating_sub(times[0]) >= NP_WINDOW_S {
+            acc += (window_sum / (i - lo + 1) as f64).powi(4);
+            count += 1;
+        }
+    }
+    (count > 0).then(|| (acc / count as f64).powf(0.25))
+}
+
+/// Output:HR decoupling in percent: how much the output per heartbeat fell
+/// from the first half of the effort (by time) to the second. Positive =
+/// HR drifted up relative to output.
+fn hr_drift(times: &[u32], output: &[f64], heartrate: &[f64]) -> Option<f64> {
+    let duration = times.last()?.saturating_sub(times[0]);
+    if duration < MIN_DRIFT_S {
+        return None;
+    }
+    let midpoint = times[0] + duration / 2;
+    let ratio = |second_half: bool| {
+        let (out_sum, hr_sum, n) = times
+            .iter()
+            .zip(output.iter().zip(heartrate))
+            .filter(|(t, (o, hr))| {
+                (**t >= midpoint) == second_half && o.is_finite() && hr.is_finite() && **hr > 0.0
+            })
+            .fold((0.0, 0.0, 0usize), |(o_s, h_s, n), (_, (o, hr))| {
+                (o_s + o, h_s + hr, n + 1)
+            });
+        (n > 0 && out_sum > 0.0).then(|| out_sum / hr_sum)
+    };
+    let (first, second) = (ratio(false)?, ratio(true)?);
+    Some((first - second) / first * 100.0)
+}
+
+/// Ascent in meters, counting rises only once they clear the hysteresis.
+fn ascent(altitude: &[f64]) -> Option<f64> {
+    let mut anchor = *altitude.iter().find(|a| a.is_finite())?;
+    let mut total = 0.0;
+    for &ele in altitude.iter().filter(|a| a.is_finite()) {
+        if ele - anchor >= ASCENT_HYSTERESIS_M {
+            total += ele - anchor;
+            anchor = ele;
+        } else if anchor - ele >= ASCENT_HYSTERESIS_M {
+            anchor = ele;
+        }
+    }
+    Some(total)
+}
+
+/// Metrics for the portion `start..=end` of an activity. `times` is the
+/// activity's time stream (one entry per GPS point); a sensor stream is
+/// used only when it has the same length.
+fn effort_metrics(
+    times: &[u32],
+    streams: &ParsedStreams,
+    start: usize,
+    end: usize,
+    weight_kg: Option<f64>,
+) -> SectionEffortMetrics {
+    if start >= end || end >= times.len() || times[end] <= times[start] {
+        return SectionEffortMetrics::default();
+    }
+    let len = times.len();
+    let t = &times[start..=end];
+    let duration = (times[end] - times[start]) as f64;
+
+    let heartrate = portion(&streams.heartrate, len, start, end);
+    let watts = portion(&streams.watts, len, start, end)
+        .filter(|w| w.iter().any(|v| v.is_finite() && *v > 0.0));
+    let cadence = portion(&streams.cadence, len, start, end);
+    let altitude = portion(&streams.altitude, len, start, end);
+    let velocity = portion(&streams.velocity_smooth, len, start, end);
+
+    let avg_power = watts.and_then(|w| mean(w, false));
+    SectionEffortMetrics {
+        avg_hr: heartrate.and_then(|hr| mean(hr, true)),
+        avg_power,
+        normalized_power: watts.and_then(|w| normalized_power(t, w)),
+        watts_per_kg: avg_power
+            .zip(weight_kg.filter(|w| *w > 0.0))
+            .map(|(p, kg)| p / kg),
+        avg_cadence: cadence.and_then(|c| mean(c, true)),
+        hr_drift: heartrate
+            .zip(watts.or(velocity))
+            .and_then(|(hr, output)| hr_drift(t, output, hr)),
+        vam: altitude
+            .and_then(ascent)
+            .map(|gain| gain * 3600.0 / duration),
+    }
+}
+
+/// Read the seven metric columns (`avg_hr` .. `vam`, in `SectionEffortMetrics`
+/// field order) starting at column `first`.
+pub(super) fn effort_metrics_from_row(
+    row: &rusqlite::Row,
+    first: usize,
+) -> rusqlite::Result<SectionEffortMetrics> {
+    Ok(SectionEffortMetrics {
+        avg_hr: row.get(first)?,
+        avg_power: row.get(first + 1)?,
+        normalized_power: row.get(first + 2)?,
+        watts_per_kg: row.get(first + 3)?,
+        avg_cadence: row.get(first + 4)?,
+        hr_drift: row.get(first + 5)?,
+        vam: row.get(first + 6)?,
+    })
+}
+
+impl PersistentRouteEngine {
+    /// Compute and persist effort metrics for section traversals the pass
+    /// has not seen yet, for one section or all of them. Rows whose activity
+    /// has no stored streams, or no time stream covering the traversal, are
+    /// left for a later pass. Returns the number of rows written.
+    pub fn update_section_effort_metrics(&mut self, section_id: Option<&str>) -> SqlResult<u32> {
+        let pending: Vec<(String, String, u32, u32, Option<i64>)> = {
+            let mut stmt = self.db.prepare(
+                "SELECT sa.section_id, sa.activity_id, sa.start_index, sa.end_index, am.date
+                 FROM section_activities sa
+                 LEFT JOIN activity_metrics am ON am.activity_id = sa.activity_id
+                 WHERE sa.metrics_computed = 0 AND (?1 IS NULL OR sa.section_id = ?1)
+                 ORDER BY sa.activity_id",
+            )?;
+            stmt.query_map(params![section_id], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })?
+            .collect::<SqlResult<_>>()?
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let mut written = 0u32;
+        let mut loaded: Option<(String, Option<(Vec<u32>, ParsedStreams)>)> = None;
+        for (sid, activity_id, start, end, date) in &pending {
+            if loaded.as_ref().map(|(id, _)| id) != Some(activity_id) {
+                loaded = Some((activity_id.clone(), self.effort_streams(activity_id)?));
+            }
+            let Some((times, streams)) = loaded.as_ref().and_then(|(_, s)| s.as_ref()) else {
+                continue;
+            };
+            let (lo, hi) = (*start.min(end) as usize, *start.max(end) as usize);
+            if hi >= times.len() {
+                continue;
+            }
+            let weight = date.and_then(|d| self.weight_on(d));
+            let m = effort_metrics(times, streams, lo, hi, weight);
+            self.db.execute(
+                "UPDATE section_activities
+                 SET avg_hr = ?, avg_power = ?, normalized_power = ?, watts_per_kg = ?,
+                     avg_cadence = ?, hr_drift = ?, vam = ?, metrics_computed = 1
+                 WHERE section_id = ? AND activity_id = ? AND start_index = ?",
+                params![
+                    m.avg_hr,
+                    m.avg_power,
+                    m.normalized_power,
+                    m.watts_per_kg,
+                    m.avg_cadence,
+                    m.hr_drift,
+                    m.vam,
+                    sid,
+                    activity_id,
+                    start
+                ],
+            )?;
+            written += 1;
+        }
+
+        if written > 0 {
+            log::info!(
+                "tracematch: [EffortMetrics] Computed metrics for {}/{} section traversals",
+                written,
+                pending.len()
+            );
+        }
+        Ok(written)
+    }
+
+    /// Stored streams for an activity with the time stream that maps them to
+    /// GPS indices. None if no streams are stored yet, or the time stream is
+    /// missing or doesn't line up with the streams.
+    fn effort_streams(&self, activity_id: &str) -> SqlResult<Option<(Vec<u32>, ParsedStreams)>> {
+        let Some(streams) = self.get_activity_streams(activity_id)? else {
+            return Ok(None);
+        };
+        Ok(self
+            .time_streams
+            .peek(activity_id)
+            .cloned()
+            .or_else(|| self.load_time_stream(activity_id))
+            .filter(|t| !t.is_empty() && t.len() == streams.time.len())
+            .map(|times| (times, streams)))
+    }
+
+    /// Body weight from wellness for the local day of `date`: the latest
+    /// entry on or before it, else the earliest one after.
+    fn weight_on(&self, date: i64) -> Option<f64> {
+        let day = chrono::DateTime::from_timestamp(date, 0)?
+            .with_timezone(&Local)
+            .date_naive()
+            .format("%Y-%m-%d")
+            .to_string();
+        self.db
+            .query_row(
+                "SELECT weight FROM wellness
+                 WHERE weight > 0
+                 ORDER BY date > ?1, CASE WHEN date <= ?1 THEN date END DESC, date
+                 LIMIT 1",
+                params![day],
+                |row| row.get(0),
+            )
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1 Hz effort of `n` samples.
+    fn times(n: u32) -> Vec<u32> {
+        (0..n).collect()
+    }
+
+    #[test]
+    fn steady_power_normalizes_to_itself() {
+        let t = times(600);
+        let streams = ParsedStreams {
+            time: t.iter().map(|&s| s as i64).collect(),
+            watts: vec![250.0; 600],
+            cadence: (0..600)
+                .map(|i| if i % 10 == 0 { 0.0 } else { 90.0 })
+                .collect(),
+            ..Default::default()
+        };
+        let m = effort_metrics(&t, &streams, 0, 599, Some(70.0));
+        assert!((m.avg_power.unwrap() - 250.0).abs() < 1e-9);
+        assert!((m.normalized_power.unwrap() - 250.0).abs() < 1e-6);
+        assert!((m.watts_per_kg.unwrap() - 250.0 / 70.0).abs() < 1e-9);
+        // Coasting zeros don't pull the cadence down
+        assert_eq!(m.avg_cadence, Some(90.0));
+        // No HR or altitude recorded
+        assert_eq!((m.avg_hr, m.hr_drift, m.vam), (None, None, None));
+    }
+
+    #[test]
+    fn surging_power_raises_normalized_power() {
+        let t = times(600);
+        // 60 s at 400 W, 60 s at 100 W: average 250 W
+        let watts: Vec<f64> = (0..600)
+            .map(|i| if (i / 60) % 2 == 0 { 400.0 } else { 100.0 })
+            .collect();
+        let np = normalized_power(&t, &watts).unwrap();
+        assert!((mean(&watts, false).unwrap() - 250.0).abs() < 1e-9);
+        assert!(np > 280.0, "NP {np} should sit well above the average");
+        assert!(normalized_power(&times(20), &[300.0; 20]).is_none());
+    }
+
+    #[test]
+    fn drift_and_vam_from_hr_and_altitude() {
+        let t = times(1200);
+        let streams = ParsedStreams {
+            time: t.iter().map(|&s| s as i64).collect(),
+            watts: vec![200.0; 1200],
+            // HR climbs from 140 to 154 in the second half
+            heartrate: (0..1200)
+                .map(|i| if i < 600 { 140.0 } else { 154.0 })
+                .collect(),
+            // 200 m of climbing over 20 minutes, with sub-hysteresis jitter
+            altitude: (0..1200)
+                .map(|i| 500.0 + i as f64 / 6.0 + if i % 2 == 0 { 0.5 } else { 0.0 })
+                .collect(),
+            ..Default::default()
+        };
+        let m = effort_metrics(&t, &streams, 0, 1199, None);
+        // Output per beat fell by ~1 - 140/154
+        assert!((m.hr_drift.unwrap() - 9.09).abs() < 0.05);
+        assert!((m.avg_hr.unwrap() - 147.0).abs() < 1e-9);
+        assert!(m.watts_per_kg.is_none());
+        // ~600 m/h, within one hysteresis step of the true gain
+        let vam = m.vam.unwrap();
+        assert!((vam - 600.0).abs() < 10.0, "VAM {vam}");
+
+        // Streams that don't line up with the time stream are ignored
+        let short = ParsedStreams {
+            heartrate: vec![150.0; 10],
+            ..Default::default()
+        };
+        assert_eq!(
+            effort_metrics(&t, &short, 0, 1199, None),
+            SectionEffortMetrics::default()
+        );
+    }
+
+    #[test]
+    fn traversals_without_a_time_stream_stay_pending() {
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        engine
+            .db
+            .execute_batch(
+                "INSERT INTO sections (id, section_type, sport_type, polyline_json, distance_meters)
+                 VALUES ('s1', 'auto', 'Ride', '[]', 1000.0);
+                 INSERT INTO section_activities (section_id, activity_id, start_index, end_index)
+                 VALUES ('s1', 'a1', 0, 9);",
+            )
+            .unwrap();
+        let streams = ParsedStreams {
+            time: (0..10).collect(),
+            watts: vec![200.0; 10],
+            ..Default::default()
+        };
+        engine.store_activity_streams("a1", &streams).unwrap();
+
+        // No GPS time stream was stored, so the samples can't be placed
+        assert_eq!(engine.update_section_effort_metrics(None).unwrap(), 0);
+        let computed: bool = engine
+            .db
+            .query_row(
+                "SELECT metrics_computed FROM section_activities WHERE activity_id = 'a1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!computed);
+    }
+}
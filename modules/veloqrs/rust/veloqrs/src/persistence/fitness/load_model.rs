@@ -0,0 +1,305 @@
+//! Local fitness (CTL) / fatigue (ATL) / form (TSB) model.
+//!
+//! Banister-style impulse-response over `training_load` (intervals.icu's
+//! `icu_training_load`) summed per local day: each day's load is folded into
+//! exponentially weighted averages with CTL / ATL time constants. Form is
+//! CTL - ATL of the same day, as in the wellness sparklines.
+//!
+//! The run is seeded from the server values stored in `wellness` - the last
+//! day on or before the first local load, so history older than the synced
+//! activities is carried in - and the mean difference to the server on the
+//! days it reported is returned alongside. Planned daily loads extend the
+//! same run past today for taper planning.
+
+use chrono::{Days, Local, NaiveDate};
+use rusqlite::Result as SqlResult;
+use std::collections::BTreeMap;
+
+use crate::{FfiFitnessModel, FfiFitnessModelDay};
+
+use super::super::PersistentRouteEngine;
+
+/// Fitness time constant in days (intervals.icu / TrainingPeaks default).
+const DEFAULT_CTL_DAYS: f64 = 42.0;
+/// Fatigue time constant in days.
+const DEFAULT_ATL_DAYS: f64 = 7.0;
+
+/// Server-reported (ctl, atl) for a day.
+type ServerValues = (Option<f64>, Option<f64>);
+
+/// Exponentially weighted model over daily loads.
+struct LoadModel {
+    ctl_decay: f64,
+    atl_decay: f64,
+}
+
+impl LoadModel {
+    fn new(ctl_days: f64, atl_days: f64) -> Self {
+        Self {
+            ctl_decay: (-1.0 / ctl_days).exp(),
+            atl_decay: (-1.0 / atl_days).exp(),
+        }
+    }
+
+    /// State after one more day with `load`.
+    fn step(&self, (ctl, atl): (f64, f64), load: f64) -> (f64, f64) {
+        (
+            ctl * self.ctl_decay + load * (1.0 - self.ctl_decay),
+            atl * self.atl_decay + load * (1.0 - self.atl_decay),
+        )
+    }
+
+    /// Day-by-day model from the seed day through `today`. The seed day
+    /// takes the server values when there are any, otherwise the run starts
+    /// from zero the day before the first load.
+    fn history(
+        &self,
+        loads: &BTreeMap<NaiveDate, f64>,
+        server: &BTreeMap<NaiveDate, ServerValues>,
+        today: NaiveDate,
+    ) -> Vec<FfiFitnessModelDay> {
+        let seeded = |(day, values): (&NaiveDate, &ServerValues)| match values {
+            (Some(ctl), Some(atl)) => Some((*day, (*ctl, *atl))),
+            _ => None,
+        };
+        let first_load = loads.keys().next().copied();
+        let server_seed = match first_load {
+            Some(first) => server
+                .range(..=first)
+                .rev()
+                .find_map(seeded)
+                .or_else(|| server.iter().find_map(seeded)),
+            None => server.iter().find_map(seeded),
+        };
+        let (seed_day, mut state) = match (server_seed, first_load) {
+            (Some(seed), _) => seed,
+            (None, Some(first)) => match first.pred_opt() {
+                Some(day) => (day, (0.0, 0.0)),
+                None => return Vec::new(),
+            },
+            (None, None) => return Vec::new(),
+        };
+        let last = loads
+            .keys()
+            .next_back()
+            .copied()
+            .map_or(today, |d| d.max(today));
+
+        let mut days = Vec::new();
+        let mut day = seed_day;
+        loop {
+            let load = loads.get(&day).copied().unwrap_or(0.0);
+            if day > seed_day {
+                state = self.step(state, load);
+            }
+            let (server_ctl, server_atl) = server.get(&day).copied().unwrap_or((None, None));
+            days.push(model_day(day, load, state, server_ctl, server_atl));
+            match day.succ_opt() {
+                Some(next) if next <= last => day = next,
+                _ => break,
+            }
+        }
+        days
+    }
+
+    /// Continue from `(day, state)` with one planned load per following day.
+    fn project(
+        &self,
+        (mut day, mut state): (NaiveDate, (f64, f64)),
+        planned: &[f64],
+    ) -> Vec<FfiFitnessModelDay> {
+        let mut days = Vec::with_capacity(planned.len());
+        for &load in planned {
+            let Some(next) = day.checked_add_days(Days::new(1)) else {
+                break;
+            };
+            day = next;
+            let load = if load.is_finite() { load.max(0.0) } else { 0.0 };
+            state = self.step(state, load);
+            days.push(model_day(day, load, state, None, None));
+        }
+        days
+    }
+}
+
+fn model_day(
+    day: NaiveDate,
+    load: f64,
+    (ctl, atl): (f64, f64),
+    server_ctl: Option<f64>,
+    server_atl: Option<f64>,
+) -> FfiFitnessModelDay {
+    FfiFitnessModelDay {
+        date: day.format("%Y-%m-%d").to_string(),
+        load,
+        ctl,
+        atl,
+        tsb: ctl - atl,
+        server_ctl,
+        server_atl,
+    }
+}
+
+/// Mean absolute difference between model and server over the days the
+/// server reported a value.
+fn mean_error(
+    days: &[FfiFitnessModelDay],
+    pick: impl Fn(&FfiFitnessModelDay) -> (f64, Option<f64>),
+) -> Option<f64> {
+    let diffs: Vec<f64> = days
+        .iter()
+        .filter_map(|d| {
+            let (model, server) = pick(d);
+            server.map(|s| (model - s).abs())
+        })
+        .collect();
+    (!diffs.is_empty()).then(|| diffs.iter().sum::<f64>() / diffs.len() as f64)
+}
+
+impl PersistentRouteEngine {
+    /// Local CTL/ATL/TSB model with a what-if projection. Time constants
+    /// default to 42 / 7 days. `history_days` trims the returned history to
+    /// the trailing window (the model always runs from its seed);
+    /// `planned_loads` are daily loads starting tomorrow.
+    pub fn get_fitness_model(
+        &self,
+        ctl_days: Option<f64>,
+        atl_days: Option<f64>,
+        history_days: u32,
+        planned_loads: &[f64],
+    ) -> SqlResult<FfiFitnessModel> {
+        let ctl_days = ctl_days
+            .filter(|d| d.is_finite() && *d >= 1.0)
+            .unwrap_or(DEFAULT_CTL_DAYS);
+        let atl_days = atl_days
+            .filter(|d| d.is_finite() && *d >= 1.0)
+            .unwrap_or(DEFAULT_ATL_DAYS);
+        let model = LoadModel::new(ctl_days, atl_days);
+
+        let mut loads: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        {
+            let mut stmt = self.db.prepare(
+                "SELECT date, training_load FROM activity_metrics
+                 WHERE training_load IS NOT NULL AND training_load > 0",
+            )?;
+            let rows =
+                stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?)))?;
+            for row in rows {
+                let (ts, load) = row?;
+                if let Some(dt) = chrono::DateTime::from_timestamp(ts, 0) {
+                    *loads
+                        .entry(dt.with_timezone(&Local).date_naive())
+                        .or_default() += load;
+                }
+            }
+        }
+
+        let mut server: BTreeMap<NaiveDate, ServerValues> = BTreeMap::new();
+        {
+            let mut stmt = self.db.prepare(
+                "SELECT date, ctl, atl FROM wellness
+                 WHERE ctl IS NOT NULL OR atl IS NOT NULL",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<f64>>(1)?,
+                    row.get::<_, Option<f64>>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (date, ctl, atl) = row?;
+                if let Ok(day) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    server.insert(day, (ctl, atl));
+                }
+            }
+        }
+
+        let today = Local::now().date_naive();
+        let history = model.history(&loads, &server, today);
+        // With nothing to model yet, plan from a blank slate today
+        let from = history
+            .last()
+            .and_then(|d| {
+                let day = NaiveDate::parse_from_str(&d.date, "%Y-%m-%d").ok()?;
+                Some((day, (d.ctl, d.atl)))
+            })
+            .unwrap_or((today, (0.0, 0.0)));
+        let projection = model.project(from, planned_loads);
+        let ctl_error = mean_error(&history, |d| (d.ctl, d.server_ctl));
+        let atl_error = mean_error(&history, |d| (d.atl, d.server_atl));
+        let keep = (history_days as usize).min(history.len());
+
+        Ok(FfiFitnessModel {
+            ctl_time_constant: ctl_days,
+            atl_time_constant: atl_days,
+            history: history[history.len() - keep..].to_vec(),
+            projection,
+            ctl_error,
+            atl_error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn constant_load_converges_and_form_recovers_in_taper() {
+        let model = LoadModel::new(DEFAULT_CTL_DAYS, DEFAULT_ATL_DAYS);
+        let start = day("2026-01-01");
+        let loads: BTreeMap<NaiveDate, f64> = (0..300)
+            .map(|i| (start.checked_add_days(Days::new(i)).unwrap(), 80.0))
+            .collect();
+        let today = day("2026-10-27");
+        let history = model.history(&loads, &BTreeMap::new(), today);
+
+        // Zero seed the day before the first load, then one row per day
+        assert_eq!(history[0].date, "2025-12-31");
+        assert_eq!(history[0].ctl, 0.0);
+        assert_eq!(history.last().unwrap().date, "2026-10-27");
+        let last = history.last().unwrap();
+        assert!((last.ctl - 80.0).abs() < 0.1);
+        assert!((last.atl - 80.0).abs() < 1e-6);
+
+        // A week of rest: fatigue drops much faster than fitness
+        let taper = model.project((today, (last.ctl, last.atl)), &[0.0; 7]);
+        assert_eq!(taper.len(), 7);
+        assert_eq!(taper[0].date, "2026-10-28");
+        let end = taper.last().unwrap();
+        assert!((end.atl - 80.0 * (-1.0f64).exp()).abs() < 0.01);
+        assert!(end.ctl > 60.0);
+        assert!(end.tsb > 30.0);
+    }
+
+    #[test]
+    fn seeds_from_server_and_reports_error() {
+        let model = LoadModel::new(DEFAULT_CTL_DAYS, DEFAULT_ATL_DAYS);
+        let loads: BTreeMap<NaiveDate, f64> =
+            [(day("2026-03-02"), 100.0), (day("2026-03-04"), 50.0)].into();
+        let server: BTreeMap<NaiveDate, ServerValues> = [
+            (day("2026-02-20"), (Some(10.0), Some(5.0))),
+            (day("2026-03-01"), (Some(40.0), Some(30.0))),
+            (day("2026-03-02"), (Some(41.0), None)),
+        ]
+        .into();
+        let history = model.history(&loads, &server, day("2026-03-05"));
+
+        // Latest server day on or before the first load is the seed
+        assert_eq!(history[0].date, "2026-03-01");
+        assert_eq!((history[0].ctl, history[0].atl), (40.0, 30.0));
+        assert_eq!(history.len(), 5);
+        let expected = model.step((40.0, 30.0), 100.0);
+        assert!((history[1].ctl - expected.0).abs() < 1e-9);
+        assert!((history[1].tsb - (expected.0 - expected.1)).abs() < 1e-9);
+
+        let ctl_error = mean_error(&history, |d| (d.ctl, d.server_ctl)).unwrap();
+        assert!((ctl_error - (41.0 - expected.0).abs() / 2.0).abs() < 1e-9);
+        assert_eq!(mean_error(&history[1..], |d| (d.atl, d.server_atl)), None);
+    }
+}
@@ -1,16 +1,39 @@
 //! Fitness core: activity-metric storage and cached athlete/sport settings.
 //!
 //! Derived fitness data (trends, aggregates, calendars, highlights) lives in
-//! [`derivations`]. Route and section performance queries live in [`performances`].
+//! [`derivations`]. Route and section performance queries live in [`performances`],
+//! finish-time forecasts built on them in [`predictions`]. The local
+//! CTL/ATL/TSB model with what-if projections lives in [`load_model`],
+//! critical power fits and their history in [`critical_power`], running
+//! race-time predictions in [`race_predictor`]. Stream best efforts over
+//! standard distances and durations live in [`best_efforts`], time-in-zone
+//! computed from streams and the athlete's zones in [`zone_times`].
 
+mod best_efforts;
+mod critical_power;
 mod derivations;
+mod effort_metrics;
+mod load_model;
 mod performances;
+mod predictions;
+mod race_predictor;
+mod zone_times;
 
 use crate::ActivityMetrics;
+use chrono::{Local, NaiveDate};
 use rusqlite::{Result as SqlResult, params};
 
 use super::PersistentRouteEngine;
 
+/// Start of `day` in the device timezone, on the clock activity dates are
+/// stored on.
+fn local_midnight(day: NaiveDate) -> Option<i64> {
+    day.and_hms_opt(0, 0, 0)?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
 impl PersistentRouteEngine {
     // ========================================================================
     // Activity Metrics & Route Performances
@@ -21,6 +44,15 @@ impl PersistentRouteEngine {
         self.activity_metrics.keys().cloned().collect()
     }
 
+    /// IDs of activities whose metrics date falls in `[start_ts, end_ts)`.
+    pub fn get_activity_ids_between(&self, start_ts: i64, end_ts: i64) -> Vec<String> {
+        self.activity_metrics
+            .values()
+            .filter(|m| m.date >= start_ts && m.date < end_ts)
+            .map(|m| m.activity_id.clone())
+            .collect()
+    }
+
     /// Set activity metrics for performance calculations.
     /// This persists the metrics to the database and keeps them in memory.
     pub fn set_activity_metrics(&mut self, metrics: Vec<ActivityMetrics>) -> SqlResult<()> {
@@ -237,15 +269,22 @@ impl PersistentRouteEngine {
     }
 
     /// Store sport settings JSON blob for instant startup rendering.
+    /// Changed settings queue local time-in-zone for recompute against the
+    /// new zones on the next zone read.
     pub fn set_sport_settings(&self, json: &str) {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs() as i64;
+        let previous = self.get_sport_settings();
         let _ = self.db.execute(
             "INSERT OR REPLACE INTO sport_settings (id, data, updated_at) VALUES ('current', ?1, ?2)",
             rusqlite::params![json, now],
         );
+        // The next sync recomputes what this queues
+        if let Err(e) = self.invalidate_zone_times_if_changed(previous.as_deref(), json) {
+            log::warn!("tracematch: [ZoneTimes] Invalidation failed: {}", e);
+        }
     }
 
     /// Get cached sport settings JSON blob. Returns None if not cached.
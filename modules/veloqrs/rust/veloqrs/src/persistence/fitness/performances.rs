@@ -6,12 +6,13 @@
 use crate::persistence::codec;
 use crate::{
     ActivityMetrics, Direction, DirectionStats, RoutePerformance, RoutePerformanceResult,
-    SectionLap, SectionPerformanceRecord, SectionPerformanceResult,
+    SectionEffortMetrics, SectionLap, SectionPerformanceRecord, SectionPerformanceResult,
 };
 use rusqlite::params;
 use std::collections::HashMap;
 
 use super::super::PersistentRouteEngine;
+use super::effort_metrics::effort_metrics_from_row;
 
 impl PersistentRouteEngine {
     /// Set time streams for activities from flat buffer.
@@ -214,7 +215,8 @@ impl PersistentRouteEngine {
             match sport_type_filter {
                 Some(st) => (
                     "SELECT sa.activity_id, sa.direction, sa.start_index, sa.end_index,
-                        sa.distance_meters, sa.lap_time, sa.lap_pace
+                        sa.distance_meters, sa.lap_time, sa.lap_pace, sa.avg_hr, sa.avg_power,
+                        sa.normalized_power, sa.watts_per_kg, sa.avg_cadence, sa.hr_drift, sa.vam
                  FROM section_activities sa
                  JOIN activity_metrics am ON sa.activity_id = am.activity_id
                  WHERE sa.section_id = ? AND am.sport_type = ? AND sa.excluded = 0
@@ -227,7 +229,8 @@ impl PersistentRouteEngine {
                 ),
                 None => (
                     "SELECT sa.activity_id, sa.direction, sa.start_index, sa.end_index,
-                        sa.distance_meters, sa.lap_time, sa.lap_pace
+                        sa.distance_meters, sa.lap_time, sa.lap_pace, sa.avg_hr, sa.avg_power,
+                        sa.normalized_power, sa.watts_per_kg, sa.avg_cadence, sa.hr_drift, sa.vam
                  FROM section_activities sa
                  WHERE sa.section_id = ? AND sa.excluded = 0
                  ORDER BY sa.activity_id, sa.start_index"
@@ -258,6 +261,7 @@ impl PersistentRouteEngine {
             distance_meters: f64,
             lap_time: Option<f64>,
             lap_pace: Option<f64>,
+            metrics: SectionEffortMetrics,
         }
 
         let params_refs: Vec<&dyn rusqlite::types::ToSql> =
@@ -271,6 +275,7 @@ impl PersistentRouteEngine {
                 distance_meters: row.get(4)?,
                 lap_time: row.get(5)?,
                 lap_pace: row.get(6)?,
+                metrics: effort_metrics_from_row(row, 7)?,
             })
         }) {
             Ok(iter) => match iter.collect::<Result<Vec<_>, _>>() {
@@ -410,6 +415,7 @@ impl PersistentRouteEngine {
                             direction: portion.direction.clone(),
                             start_index: portion.start_index,
                             end_index: portion.end_index,
+                            metrics: portion.metrics.clone(),
                         })
                     })
                     .collect();
@@ -446,6 +452,7 @@ impl PersistentRouteEngine {
                 let (best_time, best_pace) = best_lap
                     .map(|lap| (lap.time, lap.pace))
                     .unwrap_or((0.0, 0.0));
+                let metrics = best_lap.map(|lap| lap.metrics.clone()).unwrap_or_default();
                 let avg_time = laps.iter().map(|l| l.time).sum::<f64>() / lap_count as f64;
                 let avg_pace = laps.iter().map(|l| l.pace).sum::<f64>() / lap_count as f64;
                 let direction = laps
@@ -466,6 +473,7 @@ impl PersistentRouteEngine {
                     avg_pace,
                     direction,
                     section_distance,
+                    metrics,
                 })
             })
             .collect();
@@ -667,7 +675,8 @@ impl PersistentRouteEngine {
 
         let mut stmt = match self.db.prepare(
             "SELECT sa.activity_id, sa.direction, sa.start_index, sa.end_index,
-                    sa.distance_meters, sa.lap_time, sa.lap_pace
+                    sa.distance_meters, sa.lap_time, sa.lap_pace, sa.avg_hr, sa.avg_power,
+                    sa.normalized_power, sa.watts_per_kg, sa.avg_cadence, sa.hr_drift, sa.vam
              FROM section_activities sa
              JOIN activity_metrics am ON sa.activity_id = am.activity_id
              WHERE sa.section_id = ? AND am.sport_type = ? AND sa.excluded = 1
@@ -685,6 +694,7 @@ impl PersistentRouteEngine {
             distance_meters: f64,
             lap_time: Option<f64>,
             lap_pace: Option<f64>,
+            metrics: SectionEffortMetrics,
         }
 
         let portions: Vec<Portion> = match stmt.query_map(params![section_id, &sport_type], |row| {
@@ -696,6 +706,7 @@ impl PersistentRouteEngine {
                 distance_meters: row.get(4)?,
                 lap_time: row.get(5)?,
                 lap_pace: row.get(6)?,
+                metrics: effort_metrics_from_row(row, 7)?,
             })
         }) {
             Ok(iter) => match iter.collect::<Result<Vec<_>, _>>() {
@@ -778,6 +789,7 @@ impl PersistentRouteEngine {
                             direction: p.direction.clone(),
                             start_index: p.start_index,
                             end_index: p.end_index,
+                            metrics: p.metrics.clone(),
                         })
                     })
                     .collect();
@@ -794,6 +806,7 @@ impl PersistentRouteEngine {
                 });
                 let (best_time, best_pace) =
                     best_lap.map(|l| (l.time, l.pace)).unwrap_or((0.0, 0.0));
+                let metrics = best_lap.map(|l| l.metrics.clone()).unwrap_or_default();
                 let avg_time = laps.iter().map(|l| l.time).sum::<f64>() / lap_count as f64;
                 let avg_pace = laps.iter().map(|l| l.pace).sum::<f64>() / lap_count as f64;
                 let direction = laps
@@ -813,6 +826,7 @@ impl PersistentRouteEngine {
                     avg_pace,
                     direction,
                     section_distance,
+                    metrics,
                 })
             })
             .collect();
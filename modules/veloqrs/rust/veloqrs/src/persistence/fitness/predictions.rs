@@ -0,0 +1,313 @@
+//! Finish-time predictions for routes and sections.
+//!
+//! Each past effort becomes a speed, scaled by how today's fitness marker
+//! compares with the marker on the day of the effort - FTP for the cycling
+//! family, critical speed for running and swimming. The adjusted speeds are
+//! averaged with exponential recency weights; their weighted spread gives
+//! the interval.
+
+use crate::FfiFinishTimePrediction;
+
+use super::super::PersistentRouteEngine;
+
+/// Weight of an effort halves every this many days.
+const HALF_LIFE_DAYS: f64 = 60.0;
+/// z-score of the reported interval (~80%).
+const INTERVAL_Z: f64 = 1.28;
+/// Relative spread used when there is a single effort to go on.
+const SINGLE_EFFORT_SPREAD: f64 = 0.05;
+/// Floor on the relative spread - no two efforts are ever identical.
+const MIN_RELATIVE_SPREAD: f64 = 0.02;
+
+/// Sports whose FTP lives in `ftp_history`. Matches `get_ftp_trend`.
+const CYCLING_SPORTS: [&str; 9] = [
+    "Ride",
+    "VirtualRide",
+    "MountainBikeRide",
+    "GravelRide",
+    "TrackRide",
+    "Cyclocross",
+    "Handcycle",
+    "Velomobile",
+    "EBikeRide",
+];
+
+/// One past effort on the route or section.
+struct Effort {
+    date: i64,
+    /// m/s
+    speed: f64,
+}
+
+/// Fitness marker history for a sport, newest first, and how speed scales
+/// with the marker: roughly the cube root of power on a bike (air drag),
+/// linear with critical speed on foot or in the water.
+struct FitnessMarker {
+    history: Vec<(i64, f64)>,
+    exponent: f64,
+}
+
+impl FitnessMarker {
+    fn latest(&self) -> Option<f64> {
+        self.history.first().map(|&(_, v)| v)
+    }
+
+    /// Marker in force on `date`: the newest entry at or before it.
+    fn at(&self, date: i64) -> Option<f64> {
+        self.history
+            .iter()
+            .find(|&&(d, _)| d <= date)
+            .map(|&(_, v)| v)
+    }
+}
+
+/// Recency-weighted, fitness-adjusted prediction over `efforts`.
+fn predict(
+    efforts: &[Effort],
+    distance: f64,
+    marker: Option<&FitnessMarker>,
+    now: i64,
+) -> Option<FfiFinishTimePrediction> {
+    let efforts: Vec<&Effort> = efforts
+        .iter()
+        .filter(|e| e.speed.is_finite() && e.speed > 0.0)
+        .collect();
+    if efforts.is_empty() || distance <= 0.0 {
+        return None;
+    }
+
+    let today = marker.and_then(|m| m.latest());
+    let mut sum_w = 0.0;
+    let mut sum_w2 = 0.0;
+    let mut sum_ws = 0.0;
+    let mut sum_wf = 0.0;
+    let mut weighted: Vec<(f64, f64)> = Vec::with_capacity(efforts.len());
+    for effort in &efforts {
+        let age_days = ((now - effort.date).max(0)) as f64 / 86_400.0;
+        let w = 0.5f64.powf(age_days / HALF_LIFE_DAYS);
+        let factor = match (marker, today) {
+            (Some(m), Some(today)) => m
+                .at(effort.date)
+                .filter(|&then| then > 0.0)
+                .map(|then| (today / then).powf(m.exponent))
+                .unwrap_or(1.0),
+            _ => 1.0,
+        };
+        let speed = effort.speed * factor;
+        sum_w += w;
+        sum_w2 += w * w;
+        sum_ws += w * speed;
+        sum_wf += w * factor;
+        weighted.push((w, speed));
+    }
+    if sum_w <= 0.0 {
+        return None;
+    }
+
+    let speed = sum_ws / sum_w;
+    let relative_spread = if weighted.len() < 2 {
+        SINGLE_EFFORT_SPREAD
+    } else {
+        let variance = weighted
+            .iter()
+            .map(|&(w, s)| w * (s - speed).powi(2))
+            .sum::<f64>()
+            / sum_w;
+        let effective_n = sum_w * sum_w / sum_w2;
+        (variance.sqrt() / speed * (1.0 + 1.0 / effective_n).sqrt()).max(MIN_RELATIVE_SPREAD)
+    };
+
+    let time = distance / speed;
+    let margin = time * relative_spread * INTERVAL_Z;
+    Some(FfiFinishTimePrediction {
+        predicted_time: time,
+        margin,
+        lower_time: time - margin,
+        upper_time: time + margin,
+        predicted_speed: speed,
+        distance_meters: distance,
+        effort_count: weighted.len() as u32,
+        fitness_adjustment: sum_wf / sum_w,
+    })
+}
+
+fn marker_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, f64)> {
+    Ok((row.get(0)?, row.get(1)?))
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    Some(values[values.len() / 2])
+}
+
+impl PersistentRouteEngine {
+    /// Predict today's time on a route from its efforts in the route's sport.
+    /// None if the route has no usable efforts.
+    pub fn predict_route_time(&mut self, route_id: &str) -> Option<FfiFinishTimePrediction> {
+        let sport = self
+            .get_groups()
+            .iter()
+            .find(|g| g.group_id == route_id)?
+            .sport_type
+            .clone();
+        let result = self.get_route_performances(route_id, None, Some(sport.as_str()));
+        let distance = median(
+            result
+                .performances
+                .iter()
+                .map(|p| p.distance)
+                .filter(|&d| d > 0.0)
+                .collect(),
+        )?;
+        let efforts: Vec<Effort> = result
+            .performances
+            .iter()
+            .map(|p| Effort {
+                date: p.date,
+                speed: p.speed,
+            })
+            .collect();
+        let marker = self.fitness_marker(&sport);
+        predict(
+            &efforts,
+            distance,
+            marker.as_ref(),
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
+    /// Predict today's time on a section from each activity's average lap
+    /// in the section's sport. None if the section has no usable efforts.
+    pub fn predict_section_time(&mut self, section_id: &str) -> Option<FfiFinishTimePrediction> {
+        let sport = match self.sections.iter().find(|s| s.id == section_id) {
+            Some(s) => s.sport_type.clone(),
+            None => self.get_section_by_id(section_id)?.sport_type,
+        };
+        let result = self.get_section_performances_filtered(section_id, Some(sport.as_str()));
+        let distance = median(
+            result
+                .records
+                .iter()
+                .map(|r| r.section_distance)
+                .filter(|&d| d > 0.0)
+                .collect(),
+        )?;
+        let efforts: Vec<Effort> = result
+            .records
+            .iter()
+            .map(|r| Effort {
+                date: r.activity_date,
+                speed: r.avg_pace,
+            })
+            .collect();
+        let marker = self.fitness_marker(&sport);
+        predict(
+            &efforts,
+            distance,
+            marker.as_ref(),
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
+    /// FTP history for the cycling family, critical-speed history for
+    /// running and swimming. The latest entry agrees with `get_ftp_trend` /
+    /// `get_pace_trend`.
+    fn fitness_marker(&self, sport_type: &str) -> Option<FitnessMarker> {
+        let (mut stmt, key, exponent) = if CYCLING_SPORTS.contains(&sport_type) {
+            let stmt = self
+                .db
+                .prepare(
+                    "SELECT date, ftp FROM ftp_history
+                     WHERE sport_type IN ('Ride', 'VirtualRide', 'MountainBikeRide', 'GravelRide', 'TrackRide', 'Cyclocross', 'Handcycle', 'Velomobile', 'EBikeRide')
+                     ORDER BY date DESC",
+                )
+                .ok()?;
+            (stmt, None, 1.0 / 3.0)
+        } else {
+            let key = match sport_type {
+                "Run" | "VirtualRun" | "TrailRun" => "Run",
+                "Swim" | "OpenWaterSwim" => "Swim",
+                _ => return None,
+            };
+            let stmt = self
+                .db
+                .prepare(
+                    "SELECT date, critical_speed FROM pace_history
+                     WHERE sport_type = ?
+                     ORDER BY date DESC",
+                )
+                .ok()?;
+            (stmt, Some(key), 1.0)
+        };
+
+        let history: Vec<(i64, f64)> = match key {
+            Some(key) => stmt.query_map(rusqlite::params![key], marker_row),
+            None => stmt.query_map([], marker_row),
+        }
+        .ok()?
+        .flatten()
+        .collect();
+        if history.is_empty() {
+            return None;
+        }
+        Some(FitnessMarker { history, exponent })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    const NOW: i64 = 1_760_000_000;
+
+    fn effort(days_ago: i64, speed: f64) -> Effort {
+        Effort {
+            date: NOW - days_ago * DAY,
+            speed,
+        }
+    }
+
+    #[test]
+    fn recent_efforts_dominate() {
+        // Old slow efforts, recent fast ones
+        let efforts = [
+            effort(365, 5.0),
+            effort(300, 5.0),
+            effort(10, 6.0),
+            effort(5, 6.0),
+        ];
+        let p = predict(&efforts, 6000.0, None, NOW).unwrap();
+        assert!(p.predicted_speed > 5.9 && p.predicted_speed < 6.0);
+        assert!((p.predicted_time - 6000.0 / p.predicted_speed).abs() < 1e-9);
+        assert!(p.lower_time < p.predicted_time && p.upper_time > p.predicted_time);
+        assert_eq!(p.effort_count, 4);
+        assert_eq!(p.fitness_adjustment, 1.0);
+    }
+
+    #[test]
+    fn fitness_gain_speeds_up_the_prediction() {
+        let efforts = [effort(30, 8.0), effort(20, 8.0)];
+        // FTP went from 200 W to 270 W since those rides: (1.35)^(1/3) ≈ 1.105
+        let marker = FitnessMarker {
+            history: vec![(NOW - DAY, 270.0), (NOW - 100 * DAY, 200.0)],
+            exponent: 1.0 / 3.0,
+        };
+        let p = predict(&efforts, 8000.0, Some(&marker), NOW).unwrap();
+        assert!((p.fitness_adjustment - 1.35f64.cbrt()).abs() < 1e-9);
+        assert!((p.predicted_speed - 8.0 * 1.35f64.cbrt()).abs() < 1e-9);
+        // Identical efforts still get the minimum spread
+        assert!((p.margin - p.predicted_time * MIN_RELATIVE_SPREAD * INTERVAL_Z).abs() < 1e-6);
+    }
+
+    #[test]
+    fn single_effort_uses_default_spread() {
+        let p = predict(&[effort(3, 4.0)], 1000.0, None, NOW).unwrap();
+        assert_eq!(p.predicted_time, 250.0);
+        assert!((p.margin - 250.0 * SINGLE_EFFORT_SPREAD * INTERVAL_Z).abs() < 1e-9);
+        assert!(predict(&[], 1000.0, None, NOW).is_none());
+    }
+}
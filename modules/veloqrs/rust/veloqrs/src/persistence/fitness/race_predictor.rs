@@ -0,0 +1,348 @@
+//! Race time predictions for running: 5k, 10k, half and full marathon.
+//!
+//! Candidate efforts are the fastest traversal of each run section, the
+//! fastest activity on each run route (both over the last
+//! `LOOKBACK_DAYS`), and the latest critical speed / D' snapshot from
+//! `pace_history`. Each candidate predicts every race distance - Riegel's
+//! `t2 = t1 * (d2 / d1)^1.06` for efforts, `t = (d - D') / CS` for critical
+//! speed while the race lasts under an hour - and is weighted by recency and
+//! by how close its distance is to the race. The best-weighted few are
+//! averaged; their weight and agreement give the confidence.
+
+use chrono::Utc;
+use rusqlite::params;
+use std::collections::HashMap;
+
+use crate::{FfiRaceEffort, FfiRacePrediction};
+
+use super::super::PersistentRouteEngine;
+
+/// Race distances predicted, with their labels.
+const RACE_DISTANCES: [(&str, f64); 4] = [
+    ("5k", 5000.0),
+    ("10k", 10_000.0),
+    ("half", 21_097.5),
+    ("marathon", 42_195.0),
+];
+const RIEGEL_EXPONENT: f64 = 1.06;
+/// Shorter efforts are too sprint-like to scale to race distances.
+const MIN_EFFORT_M: f64 = 1500.0;
+const LOOKBACK_DAYS: i64 = 180;
+/// Weight of an effort halves every this many days.
+const HALF_LIFE_DAYS: f64 = 60.0;
+/// Efforts averaged into each prediction.
+const MAX_EFFORTS: usize = 3;
+/// Faster than this (m/s) over 1.5 km+ is a GPS or sport-type error.
+const MAX_RUN_SPEED: f64 = 6.5;
+/// The critical speed model holds up to about an hour of running.
+const CS_MAX_SECS: f64 = 3600.0;
+/// Confidence labels by score.
+const HIGH_CONFIDENCE: f64 = 0.6;
+const MEDIUM_CONFIDENCE: f64 = 0.3;
+
+const RUN_SPORTS: &str = "('Run', 'VirtualRun', 'TrailRun')";
+
+/// One candidate effort. Critical speed is stored as its one-hour
+/// equivalent: `distance = CS * 3600 + D'`, `time = 3600`.
+#[derive(Debug, Clone)]
+struct RaceEffort {
+    source: &'static str,
+    source_id: Option<String>,
+    activity_id: Option<String>,
+    distance: f64,
+    time: f64,
+    date: i64,
+    /// (critical speed, D') for the "critical_speed" source
+    critical_speed: Option<(f64, f64)>,
+}
+
+impl RaceEffort {
+    fn predict(&self, target: f64) -> f64 {
+        if let Some((cs, d_prime)) = self.critical_speed {
+            let t = (target - d_prime) / cs;
+            if t > 0.0 && t <= CS_MAX_SECS {
+                return t;
+            }
+        }
+        self.time * (target / self.distance).powf(RIEGEL_EXPONENT)
+    }
+
+    /// 1.0 at the race distance, halving for every doubling away from it.
+    /// Critical speed counts as exact for any race inside its range.
+    fn proximity(&self, target: f64) -> f64 {
+        if self.critical_speed.is_some() && target <= self.distance {
+            return 1.0;
+        }
+        (-(target / self.distance).ln().abs()).exp()
+    }
+}
+
+/// Prediction for one race distance from the best-weighted efforts.
+fn predict_race(
+    efforts: &[RaceEffort],
+    label: &str,
+    target: f64,
+    now: i64,
+) -> Option<FfiRacePrediction> {
+    let mut ranked: Vec<(&RaceEffort, f64)> = efforts
+        .iter()
+        .filter(|e| e.distance > 0.0 && e.time > 0.0)
+        .map(|e| {
+            let age_days = (now - e.date).max(0) as f64 / 86_400.0;
+            (
+                e,
+                0.5f64.powf(age_days / HALF_LIFE_DAYS) * e.proximity(target),
+            )
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(MAX_EFFORTS);
+    let (top, top_weight) = *ranked.first()?;
+    let sum_w: f64 = ranked.iter().map(|(_, w)| w).sum();
+    if sum_w <= 0.0 {
+        return None;
+    }
+
+    let predictions: Vec<f64> = ranked.iter().map(|(e, _)| e.predict(target)).collect();
+    let time = ranked
+        .iter()
+        .zip(&predictions)
+        .map(|((_, w), t)| w * t)
+        .sum::<f64>()
+        / sum_w;
+    let variance = ranked
+        .iter()
+        .zip(&predictions)
+        .map(|((_, w), t)| w * (t - time).powi(2))
+        .sum::<f64>()
+        / sum_w;
+    // 10% disagreement between the efforts halves the score
+    let agreement = 1.0 - (variance.sqrt() / time * 5.0).min(0.5);
+    let score = top_weight * agreement;
+
+    Some(FfiRacePrediction {
+        label: label.to_string(),
+        distance_meters: target,
+        predicted_time: time,
+        method: if top.critical_speed.is_some() {
+            "critical_speed"
+        } else {
+            "riegel"
+        }
+        .to_string(),
+        confidence: if score >= HIGH_CONFIDENCE {
+            "high"
+        } else if score >= MEDIUM_CONFIDENCE {
+            "medium"
+        } else {
+            "low"
+        }
+        .to_string(),
+        confidence_score: score,
+        efforts: ranked
+            .iter()
+            .zip(&predictions)
+            .map(|((e, w), t)| FfiRaceEffort {
+                source: e.source.to_string(),
+                source_id: e.source_id.clone(),
+                activity_id: e.activity_id.clone(),
+                distance_meters: e.distance,
+                time_secs: e.time,
+                date: e.date,
+                predicted_time: *t,
+                weight: *w,
+            })
+            .collect(),
+    })
+}
+
+/// Keep the fastest effort per section / route.
+fn fastest_per_source(efforts: Vec<RaceEffort>) -> Vec<RaceEffort> {
+    let mut best: HashMap<Option<String>, RaceEffort> = HashMap::new();
+    for effort in efforts {
+        let speed = effort.distance / effort.time;
+        if !(speed > 0.0 && speed <= MAX_RUN_SPEED) {
+            continue;
+        }
+        match best.get(&effort.source_id) {
+            Some(b) if b.distance / b.time >= speed => {}
+            _ => {
+                best.insert(effort.source_id.clone(), effort);
+            }
+        }
+    }
+    best.into_values().collect()
+}
+
+impl PersistentRouteEngine {
+    /// Predicted 5k / 10k / half / marathon times with confidence and the
+    /// efforts behind each. Empty when there is nothing recent to go on.
+    pub fn get_race_predictions(&self) -> Vec<FfiRacePrediction> {
+        let now = Utc::now().timestamp();
+        let since = now - LOOKBACK_DAYS * 86_400;
+
+        let mut efforts = fastest_per_source(self.section_race_efforts(since));
+        efforts.extend(fastest_per_source(self.route_race_efforts(since)));
+        if let Some(cs) = self.critical_speed_effort(since) {
+            efforts.push(cs);
+        }
+
+        RACE_DISTANCES
+            .iter()
+            .filter_map(|&(label, distance)| predict_race(&efforts, label, distance, now))
+            .collect()
+    }
+
+    fn section_race_efforts(&self, since: i64) -> Vec<RaceEffort> {
+        let sql = format!(
+            "SELECT sa.section_id, sa.activity_id, sa.distance_meters, sa.lap_time, am.date
+             FROM section_activities sa
+             JOIN activity_metrics am ON am.activity_id = sa.activity_id
+             WHERE am.sport_type IN {RUN_SPORTS} AND sa.excluded = 0
+               AND sa.direction != 'partial' AND sa.lap_time > 0
+               AND sa.distance_meters >= ? AND am.date >= ?"
+        );
+        self.query_race_efforts(&sql, "section", since)
+    }
+
+    fn route_race_efforts(&self, since: i64) -> Vec<RaceEffort> {
+        let sql = format!(
+            "SELECT m.route_id, am.activity_id, am.distance, am.moving_time, am.date
+             FROM activity_matches m
+             JOIN activity_metrics am ON am.activity_id = m.activity_id
+             WHERE am.sport_type IN {RUN_SPORTS} AND m.excluded = 0
+               AND am.moving_time > 0 AND am.distance >= ? AND am.date >= ?"
+        );
+        self.query_race_efforts(&sql, "route", since)
+    }
+
+    /// Rows of (source id, activity id, distance, time, date).
+    fn query_race_efforts(&self, sql: &str, source: &'static str, since: i64) -> Vec<RaceEffort> {
+        let mut stmt = match self.db.prepare(sql) {
+            Ok(s) => s,
+            Err(e) => {
+                log::warn!("[fitness] Failed to prepare {} race efforts: {}", source, e);
+                return Vec::new();
+            }
+        };
+        stmt.query_map(params![MIN_EFFORT_M, since], |row| {
+            Ok(RaceEffort {
+                source,
+                source_id: Some(row.get(0)?),
+                activity_id: Some(row.get(1)?),
+                distance: row.get(2)?,
+                time: row.get(3)?,
+                date: row.get(4)?,
+                critical_speed: None,
+            })
+        })
+        .ok()
+        .map(|iter| iter.flatten().collect())
+        .unwrap_or_default()
+    }
+
+    /// Latest running critical speed snapshot as its one-hour equivalent.
+    fn critical_speed_effort(&self, since: i64) -> Option<RaceEffort> {
+        let (date, cs, d_prime): (i64, f64, Option<f64>) = self
+            .db
+            .query_row(
+                "SELECT date, critical_speed, d_prime FROM pace_history
+                 WHERE sport_type = 'Run' AND date >= ?
+                 ORDER BY date DESC
+                 LIMIT 1",
+                params![since],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .ok()?;
+        if !(cs > 0.0 && cs <= MAX_RUN_SPEED) {
+            return None;
+        }
+        let d_prime = d_prime.filter(|d| d.is_finite() && *d > 0.0).unwrap_or(0.0);
+        Some(RaceEffort {
+            source: "critical_speed",
+            source_id: None,
+            activity_id: None,
+            distance: cs * CS_MAX_SECS + d_prime,
+            time: CS_MAX_SECS,
+            date,
+            critical_speed: Some((cs, d_prime)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    const NOW: i64 = 1_760_000_000;
+
+    fn effort(source_id: &str, distance: f64, time: f64, days_ago: i64) -> RaceEffort {
+        RaceEffort {
+            source: "section",
+            source_id: Some(source_id.to_string()),
+            activity_id: Some(format!("a_{source_id}")),
+            distance,
+            time,
+            date: NOW - days_ago * DAY,
+            critical_speed: None,
+        }
+    }
+
+    #[test]
+    fn riegel_from_a_single_effort() {
+        // 20:00 5k -> 10k in 1200 * 2^1.06
+        let efforts = [effort("s1", 5000.0, 1200.0, 0)];
+        let p = predict_race(&efforts, "10k", 10_000.0, NOW).unwrap();
+        assert!((p.predicted_time - 1200.0 * 2f64.powf(1.06)).abs() < 1e-6);
+        assert_eq!(p.method, "riegel");
+        assert_eq!(p.efforts.len(), 1);
+        // Same distance, today: full weight
+        let p5 = predict_race(&efforts, "5k", 5000.0, NOW).unwrap();
+        assert!((p5.predicted_time - 1200.0).abs() < 1e-9);
+        assert_eq!(p5.confidence, "high");
+        // Marathon from a 5k is a long stretch
+        let pm = predict_race(&efforts, "marathon", 42_195.0, NOW).unwrap();
+        assert_eq!(pm.confidence, "low");
+    }
+
+    #[test]
+    fn critical_speed_covers_races_under_an_hour() {
+        let cs = RaceEffort {
+            source: "critical_speed",
+            source_id: None,
+            activity_id: None,
+            distance: 4.0 * CS_MAX_SECS + 200.0,
+            time: CS_MAX_SECS,
+            date: NOW,
+            critical_speed: Some((4.0, 200.0)),
+        };
+        assert!((cs.predict(10_000.0) - 2450.0).abs() < 1e-9);
+        assert_eq!(cs.proximity(10_000.0), 1.0);
+        // Beyond an hour, Riegel from the one-hour equivalent
+        let half = cs.predict(21_097.5);
+        assert!((half - CS_MAX_SECS * (21_097.5 / 14_600.0f64).powf(1.06)).abs() < 1e-6);
+
+        let p = predict_race(
+            &[cs, effort("s1", 5000.0, 1300.0, 120)],
+            "10k",
+            10_000.0,
+            NOW,
+        )
+        .unwrap();
+        assert_eq!(p.method, "critical_speed");
+        assert_eq!(p.efforts[0].source, "critical_speed");
+        assert!(p.efforts[0].weight > p.efforts[1].weight);
+    }
+
+    #[test]
+    fn fastest_effort_per_source_and_sanity_filter() {
+        let kept = fastest_per_source(vec![
+            effort("s1", 3000.0, 900.0, 1),
+            effort("s1", 3000.0, 800.0, 2),
+            effort("s2", 3000.0, 300.0, 1), // 10 m/s
+        ]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].time, 800.0);
+    }
+}
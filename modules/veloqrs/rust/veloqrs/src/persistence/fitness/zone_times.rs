@@ -0,0 +1,357 @@
+//! Local time-in-zone from stored streams and the athlete's zones.
+//!
+//! The server's `icu_zone_times` are missing for activities synced without
+//! stats and go stale when the athlete changes zones. This pass buckets the
+//! stored `heartrate`, `watts` and `velocity_smooth` series into the HR /
+//! power / pace zones of the sport's entry in the cached sport settings
+//! (`SportSettingsRecord`) and stores seconds per zone in
+//! `activity_zone_times`. `activity_metrics.zones_computed` marks activities
+//! the pass has seen; it is cleared when an activity's streams are replaced
+//! or the settings change, and the pass runs after each sync. A single
+//! activity's read computes just that activity.
+
+use rusqlite::{Result as SqlResult, params};
+
+use crate::FfiActivityZoneTimes;
+use crate::net::types::SportSettingsRecord;
+
+use super::super::PersistentRouteEngine;
+use super::super::streams::EMPTY_STREAMS_MARKER;
+
+/// Zone type and the stream it is computed from.
+const ZONE_STREAMS: &[(&str, &str)] = &[
+    ("hr", "heartrate"),
+    ("power", "watts"),
+    ("pace", "velocity_smooth"),
+];
+/// Longest interval (seconds) one sample is credited with; the rest of a
+/// longer gap is a pause.
+const MAX_SAMPLE_GAP_S: f64 = 10.0;
+
+/// The sport settings blob as cached by the app (intervals.icu JSON).
+fn parse_sport_settings(json: &str) -> Option<Vec<SportSettingsRecord>> {
+    serde_json::from_str(json).ok()
+}
+
+/// Upper bound of each zone in stream units: bpm for HR, watts from the
+/// %FTP power zones (indoor FTP for virtual sports), m/s from the
+/// %threshold-pace pace zones. `None` when the zones or their threshold are
+/// not set.
+fn zone_bounds(
+    settings: &SportSettingsRecord,
+    sport_type: &str,
+    zone_type: &str,
+) -> Option<Vec<f64>> {
+    let (zones, scale) = match zone_type {
+        "hr" => (&settings.hr_zones, 1.0),
+        "power" => {
+            let ftp = if sport_type.starts_with("Virtual") {
+                settings.indoor_ftp.or(settings.ftp)
+            } else {
+                settings.ftp
+            };
+            (&settings.power_zones, ftp? / 100.0)
+        }
+        "pace" => (&settings.pace_zones, settings.threshold_pace? / 100.0),
+        _ => return None,
+    };
+    (!zones.is_empty() && scale > 0.0).then(|| zones.iter().map(|z| z * scale).collect())
+}
+
+/// 0-based zone of `value`: the first zone whose upper bound it does not
+/// exceed, the top zone above every bound.
+fn zone_index(bounds: &[f64], value: f64) -> usize {
+    bounds
+        .iter()
+        .position(|&b| value <= b)
+        .unwrap_or(bounds.len() - 1)
+}
+
+/// Seconds per zone. Each sample is credited with the interval to the next
+/// one; missing or zero samples (no sensor, stopped) count nowhere.
+fn zone_times(times: &[f64], values: &[f64], bounds: &[f64]) -> Vec<f64> {
+    let mut secs = vec![0.0; bounds.len()];
+    if bounds.is_empty() {
+        return secs;
+    }
+    for (pair, &v) in times.windows(2).zip(values) {
+        if v.is_finite() && v > 0.0 {
+            secs[zone_index(bounds, v)] += (pair[1] - pair[0]).clamp(0.0, MAX_SAMPLE_GAP_S);
+        }
+    }
+    secs
+}
+
+impl PersistentRouteEngine {
+    /// Compute time-in-zone for activities with streams that have not been
+    /// computed against the current sport settings, or just `activity_id`.
+    /// Returns the number of activities computed. Nothing is marked while no
+    /// sport settings are cached.
+    pub fn update_zone_times(&self, activity_id: Option<&str>) -> SqlResult<u32> {
+        let Some(settings) = self
+            .get_sport_settings()
+            .as_deref()
+            .and_then(parse_sport_settings)
+        else {
+            return Ok(0);
+        };
+        let pending: Vec<(String, String)> = {
+            let mut stmt = self.db.prepare(
+                "SELECT m.activity_id, m.sport_type FROM activity_metrics m
+                 WHERE m.zones_computed = 0
+                   AND (?1 IS NULL OR m.activity_id = ?1)
+                   AND EXISTS (SELECT 1 FROM activity_streams s
+                               WHERE s.activity_id = m.activity_id AND s.stream_type != ?2)",
+            )?;
+            stmt.query_map(params![activity_id, EMPTY_STREAMS_MARKER], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<SqlResult<_>>()?
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = self.db.unchecked_transaction()?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO activity_zone_times (activity_id, zone_type, zone, secs)
+                 VALUES (?, ?, ?, ?)",
+            )?;
+            for (id, sport_type) in &pending {
+                tx.execute(
+                    "DELETE FROM activity_zone_times WHERE activity_id = ?",
+                    params![id],
+                )?;
+                let sport = settings
+                    .iter()
+                    .find(|s| s.types.iter().any(|t| t == sport_type));
+                let times = match sport {
+                    Some(_) => self.get_activity_stream(id, "time")?,
+                    None => None,
+                };
+                if let (Some(sport), Some(times)) = (sport, times) {
+                    for &(zone_type, stream_type) in ZONE_STREAMS {
+                        let Some(bounds) = zone_bounds(sport, sport_type, zone_type) else {
+                            continue;
+                        };
+                        let Some(values) = self.get_activity_stream(id, stream_type)? else {
+                            continue;
+                        };
+                        if values.len() != times.len() {
+                            continue;
+                        }
+                        let secs = zone_times(&times, &values, &bounds);
+                        // No usable samples: leave the server's numbers in charge
+                        if secs.iter().sum::<f64>() <= 0.0 {
+                            continue;
+                        }
+                        for (zone, s) in secs.iter().enumerate() {
+                            insert.execute(params![id, zone_type, zone as i64, s])?;
+                        }
+                    }
+                }
+                tx.execute(
+                    "UPDATE activity_metrics SET zones_computed = 1 WHERE activity_id = ?",
+                    params![id],
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        log::info!(
+            "tracematch: [ZoneTimes] Computed time-in-zone for {} activities",
+            pending.len()
+        );
+        Ok(pending.len() as u32)
+    }
+
+    /// Queue every activity for a fresh time-in-zone pass if the sport
+    /// settings changed from `previous`. Returns whether they did.
+    pub(super) fn invalidate_zone_times_if_changed(
+        &self,
+        previous: Option<&str>,
+        json: &str,
+    ) -> SqlResult<bool> {
+        if previous.and_then(parse_sport_settings) == parse_sport_settings(json) {
+            return Ok(false);
+        }
+        self.db.execute(
+            "UPDATE activity_metrics SET zones_computed = 0 WHERE zones_computed = 1",
+            [],
+        )?;
+        Ok(true)
+    }
+
+    /// Locally computed seconds per zone, summed over `sport_type`, as
+    /// `(zone, secs)` pairs in the athlete's own zones.
+    pub(super) fn local_zone_distribution(
+        &self,
+        sport_type: &str,
+        zone_type: &str,
+    ) -> SqlResult<Vec<(usize, f64)>> {
+        let mut stmt = self.db.prepare(
+            "SELECT z.zone, SUM(z.secs) FROM activity_zone_times z
+             JOIN activity_metrics m ON m.activity_id = z.activity_id
+             WHERE m.sport_type = ? AND z.zone_type = ?
+             GROUP BY z.zone",
+        )?;
+        stmt.query_map(params![sport_type, zone_type], |row| {
+            Ok((row.get::<_, i64>(0)? as usize, row.get(1)?))
+        })?
+        .collect()
+    }
+
+    /// Locally computed time-in-zone for one activity, computing it first if
+    /// needed. Zone types without zones or a stream come back empty.
+    pub fn get_activity_zone_times(&self, activity_id: &str) -> SqlResult<FfiActivityZoneTimes> {
+        self.update_zone_times(Some(activity_id))?;
+        let mut out = FfiActivityZoneTimes {
+            activity_id: activity_id.to_string(),
+            hr_secs: Vec::new(),
+            power_secs: Vec::new(),
+            pace_secs: Vec::new(),
+        };
+        let mut stmt = self.db.prepare(
+            "SELECT zone_type, zone, secs FROM activity_zone_times
+             WHERE activity_id = ? ORDER BY zone_type, zone",
+        )?;
+        let rows = stmt.query_map(params![activity_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)? as usize,
+                row.get::<_, f64>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (zone_type, zone, secs) = row?;
+            let slot = match zone_type.as_str() {
+                "hr" => &mut out.hr_secs,
+                "power" => &mut out.power_secs,
+                _ => &mut out.pace_secs,
+            };
+            if slot.len() <= zone {
+                slot.resize(zone + 1, 0.0);
+            }
+            slot[zone] = secs;
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ActivityMetrics;
+    use crate::net::types::ParsedStreams;
+
+    #[test]
+    fn bounds_scale_with_thresholds() {
+        let settings = SportSettingsRecord {
+            types: vec!["Ride".into(), "VirtualRide".into()],
+            ftp: Some(200.0),
+            indoor_ftp: Some(180.0),
+            threshold_pace: Some(4.0),
+            hr_zones: vec![120.0, 140.0, 160.0],
+            power_zones: vec![55.0, 75.0, 90.0, 105.0],
+            pace_zones: vec![80.0, 100.0, 120.0],
+            ..Default::default()
+        };
+        assert_eq!(
+            zone_bounds(&settings, "Ride", "power"),
+            Some(vec![110.0, 150.0, 180.0, 210.0])
+        );
+        assert_eq!(
+            zone_bounds(&settings, "VirtualRide", "power"),
+            Some(vec![99.0, 135.0, 162.0, 189.0])
+        );
+        assert_eq!(
+            zone_bounds(&settings, "Ride", "pace"),
+            Some(vec![3.2, 4.0, 4.8])
+        );
+        assert_eq!(
+            zone_bounds(&settings, "Ride", "hr"),
+            Some(vec![120.0, 140.0, 160.0])
+        );
+
+        let no_ftp = SportSettingsRecord {
+            ftp: None,
+            ..settings
+        };
+        assert_eq!(zone_bounds(&no_ftp, "Ride", "power"), None);
+    }
+
+    #[test]
+    fn zone_times_credit_intervals() {
+        let bounds = [120.0, 140.0, 160.0];
+        assert_eq!(zone_index(&bounds, 120.0), 0);
+        assert_eq!(zone_index(&bounds, 120.5), 1);
+        assert_eq!(zone_index(&bounds, 190.0), 2);
+
+        // 1 Hz, then a 60 s pause after the fourth sample; dropouts skipped
+        let times = [0.0, 1.0, 2.0, 3.0, 63.0, 64.0];
+        let hr = [100.0, 130.0, f64::NAN, 150.0, 0.0, 170.0];
+        assert_eq!(zone_times(&times, &hr, &bounds), vec![1.0, 1.0, 10.0]);
+    }
+
+    #[test]
+    fn distribution_uses_local_zones_and_follows_settings() {
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        let ride = |id: &str| ActivityMetrics {
+            activity_id: id.into(),
+            name: "Ride".into(),
+            date: 1_700_000_000,
+            distance: 1000.0,
+            moving_time: 10,
+            elapsed_time: 10,
+            elevation_gain: 0.0,
+            avg_hr: None,
+            avg_power: None,
+            sport_type: "Ride".into(),
+        };
+        // a2 has no streams, only the server's zone times
+        engine
+            .set_activity_metrics(vec![ride("a1"), ride("a2")])
+            .unwrap();
+        let streams = ParsedStreams {
+            time: (0..=10).collect(),
+            heartrate: vec![
+                100.0, 100.0, 100.0, 130.0, 130.0, 150.0, 150.0, 150.0, 150.0, 170.0, 170.0,
+            ],
+            ..Default::default()
+        };
+        engine.store_activity_streams("a1", &streams).unwrap();
+
+        // No settings cached yet: nothing computed, server columns only
+        assert_eq!(engine.update_zone_times(None).unwrap(), 0);
+        assert_eq!(engine.get_zone_distribution("Ride", "hr"), vec![0.0; 5]);
+
+        // Changing settings only queues the work for the next sync
+        engine.set_sport_settings(
+            r#"[{"types": ["Ride"], "hr_zones": [120, 140, 160, 175, 185, 195, 205]}]"#,
+        );
+        assert_eq!(engine.get_zone_distribution("Ride", "hr"), vec![0.0; 5]);
+        assert_eq!(engine.update_zone_times(None).unwrap(), 1);
+        // Seven local zones fold into the server's five
+        assert_eq!(
+            engine.get_zone_distribution("Ride", "hr"),
+            vec![3.0, 2.0, 4.0, 1.0, 0.0]
+        );
+        // Server zone columns count only for the activity without local zones
+        engine
+            .db
+            .execute("UPDATE activity_metrics SET hr_z1 = 60, hr_z5 = 30", [])
+            .unwrap();
+        assert_eq!(
+            engine.get_zone_distribution("Ride", "hr"),
+            vec![63.0, 2.0, 4.0, 1.0, 30.0]
+        );
+
+        engine.set_sport_settings(
+            r#"[{"types": ["Ride"], "hr_zones": [140, 160, 175, 185, 195, 200, 205]}]"#,
+        );
+        let times = engine.get_activity_zone_times("a1").unwrap();
+        assert_eq!(times.hr_secs, vec![5.0, 4.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+        assert!(times.power_secs.is_empty());
+    }
+}
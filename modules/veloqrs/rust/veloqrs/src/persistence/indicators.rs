@@ -10,7 +10,7 @@ use super::{PersistentRouteEngine, codec};
 
 /// Bump this when the indicator computation algorithm changes.
 /// On next read, a version mismatch triggers a full clean recompute.
-const INDICATOR_ALGORITHM_VERSION: i32 = 4;
+const INDICATOR_ALGORITHM_VERSION: i32 = 5;
 
 impl PersistentRouteEngine {
     /// Recompute all activity indicators (PRs and trends) from scratch.
@@ -25,7 +25,8 @@ impl PersistentRouteEngine {
     /// 1. Clear the table
     /// 2. For each (section, direction) pair: find PR + compute per-activity trends
     /// 3. For each route group: find PR + compute per-activity trends
-    /// 4. Bulk-insert all indicators
+    /// 4. For each (sport, best-effort target): find all-time and season bests
+    /// 5. Bulk-insert all indicators
     pub fn recompute_activity_indicators(&self) -> SqlResult<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -43,12 +44,16 @@ impl PersistentRouteEngine {
             );
         }
 
+        // Best efforts for activities whose streams have not been scanned yet
+        self.update_best_efforts()?;
+
         let tx = self.db.unchecked_transaction()?;
         tx.execute("DELETE FROM activity_indicators", [])?;
 
-        // Section indicators only - route highlights are computed inline
-        // from in-memory groups + activity_metrics (no table needed).
+        // Section and best-effort indicators only - route highlights are
+        // computed inline from in-memory groups + activity_metrics (no table needed).
         let section_count = self.compute_section_indicators(&tx, now)?;
+        let effort_count = self.compute_effort_indicators(&tx, now)?;
 
         // Stamp the algorithm version so we don't recompute until it changes
         tx.execute(
@@ -59,8 +64,9 @@ impl PersistentRouteEngine {
         tx.commit()?;
 
         log::info!(
-            "tracematch: [indicators] Recomputed {} section indicators (v{})",
+            "tracematch: [indicators] Recomputed {} section and {} best-effort indicators (v{})",
             section_count,
+            effort_count,
             INDICATOR_ALGORITHM_VERSION
         );
 
@@ -35,15 +35,23 @@ use rstar::{AABB, RTree, RTreeObject};
 use rusqlite::{Connection, Result as SqlResult};
 
 mod activities;
+pub(crate) mod climbs;
 pub(crate) mod codec;
+pub(crate) mod curves;
+mod elevation;
 pub(crate) mod export;
 mod fitness;
 mod indicators;
+pub(crate) mod route_deviation;
+pub(crate) mod route_overrides;
+pub(crate) mod route_shape;
+mod route_suggest;
 mod routes;
 mod schema;
 pub mod sections;
 pub mod settings;
 pub use settings::settings_keys;
+mod streams;
 mod strength;
 mod tiles;
 pub(crate) mod wellness;
@@ -161,6 +169,18 @@ pub struct GroupSummary {
     pub bounds: Option<crate::FfiBounds>,
     /// All sport types present in this group's activities
     pub sport_types: Vec<String>,
+    /// Route shape ("loop", "out_and_back", "point_to_point", "lollipop"),
+    /// None until classified
+    pub shape: Option<String>,
+    /// Classification confidence (0.0-1.0)
+    pub shape_confidence: Option<f64>,
+    /// Total ascent in meters, None until the climb profile is computed
+    pub total_ascent: Option<f64>,
+    /// Net and steepest gradient, percent
+    pub avg_gradient: Option<f64>,
+    pub max_gradient: Option<f64>,
+    /// "cat4", "cat3", "cat2", "cat1" or "hc"; None if not a categorised climb
+    pub climb_category: Option<String>,
 }
 
 /// Complete activity data for map display.
@@ -1234,6 +1254,86 @@ where
     guard.as_ref().map(f)
 }
 
+/// File name of the throwaway demo database, created beside the engine
+/// database.
+const DEMO_DB_FILE_NAME: &str = "veloq_demo.db";
+
+/// Demo mode's engine swap. While active, the user's engine is parked here and
+/// an engine on a throwaway database sits in its slot, so replayed syncs
+/// (activities, settings, the sync watermark) never touch the user's data.
+pub(crate) struct DemoEngineSwap {
+    parked: Mutex<Option<PersistentRouteEngine>>,
+}
+
+/// The swap for `PERSISTENT_ENGINE`.
+pub(crate) static DEMO_ENGINE_SWAP: DemoEngineSwap = DemoEngineSwap::new();
+
+impl DemoEngineSwap {
+    pub(crate) const fn new() -> Self {
+        DemoEngineSwap {
+            parked: Mutex::new(None),
+        }
+    }
+
+    /// Park the engine in `slot` and install a fresh one on an empty demo
+    /// database beside it. A no-op while already active.
+    pub(crate) fn enter(&self, slot: &RwLock<Option<PersistentRouteEngine>>) -> Result<(), String> {
+        let mut parked = self.parked.lock().unwrap_or_else(|e| e.into_inner());
+        if parked.is_some() {
+            return Ok(());
+        }
+        let mut guard = slot.write().unwrap_or_else(|e| e.into_inner());
+        let real = guard
+            .as_ref()
+            .ok_or_else(|| "engine not initialized".to_string())?;
+        let path = std::path::Path::new(&real.db_path)
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .join(DEMO_DB_FILE_NAME);
+        remove_database_files(&path.to_string_lossy());
+        let demo = PersistentRouteEngine::new(&path.to_string_lossy())
+            .map_err(|e| format!("failed to open demo database: {}", e))?;
+        *parked = guard.replace(demo);
+        log::info!("tracematch: [PersistentEngine] Demo engine installed");
+        Ok(())
+    }
+
+    /// Drop the demo engine and its database and put the parked engine back.
+    /// A no-op while inactive.
+    pub(crate) fn leave(&self, slot: &RwLock<Option<PersistentRouteEngine>>) {
+        let mut parked = self.parked.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(real) = parked.take() else {
+            return;
+        };
+        let demo = slot
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(real);
+        if let Some(demo) = demo {
+            let path = demo.db_path.clone();
+            drop(demo);
+            remove_database_files(&path);
+        }
+        log::info!("tracematch: [PersistentEngine] Demo engine removed");
+    }
+
+    /// Whether a demo engine is installed.
+    pub(crate) fn is_active(&self) -> bool {
+        self.parked
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+}
+
+/// Delete a database file and its WAL / shared-memory companions. Missing
+/// files are ignored.
+fn remove_database_files(path: &str) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+    }
+}
+
 /// SQLite error codes that mean the file itself is unusable, as opposed
 /// to a transient I/O or logic error.
 pub(crate) fn is_corruption_error(e: &rusqlite::Error) -> bool {
@@ -0,0 +1,250 @@
+//! Off-route deviation: where an activity left a route's consensus line.
+//!
+//! The activity track is aligned point-by-point to the consensus polyline
+//! from `get_consensus_route`. Both are projected onto a local metric plane
+//! and the consensus segments go into an R-tree, so each track point finds
+//! its nearest segment in O(log m) - the same approach as
+//! `compute_polyline_overlap`, but measured to segments rather than vertices
+//! so sparse consensus lines don't inflate the offsets.
+
+use rstar::RTree;
+use rstar::primitives::{GeomWithData, Line};
+
+use super::PersistentRouteEngine;
+use crate::{FfiDeviationSegment, FfiRouteDeviation, GpsPoint};
+
+/// Runs shorter than this many points are GPS noise, not a deviation.
+const MIN_DEVIATION_POINTS: usize = 3;
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Equirectangular projection around a reference point. Accurate to well
+/// under a metre over the extent of a single route.
+pub(super) struct LocalPlane {
+    lat0: f64,
+    lng0: f64,
+    cos_lat0: f64,
+}
+
+impl LocalPlane {
+    pub(super) fn new(origin: &GpsPoint) -> Self {
+        Self {
+            lat0: origin.latitude,
+            lng0: origin.longitude,
+            cos_lat0: origin.latitude.to_radians().cos(),
+        }
+    }
+
+    pub(super) fn project(&self, p: &GpsPoint) -> [f64; 2] {
+        [
+            (p.longitude - self.lng0).to_radians() * self.cos_lat0 * EARTH_RADIUS_M,
+            (p.latitude - self.lat0).to_radians() * EARTH_RADIUS_M,
+        ]
+    }
+}
+
+pub(super) fn plane_distance(a: [f64; 2], b: [f64; 2]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// Align `track` to `consensus` and collect the runs of points further than
+/// `threshold_meters` from it. Returns the per-point offsets (metres) and
+/// the deviation segments; both are empty if either line has fewer than two
+/// points.
+pub(crate) fn analyze_route_deviation(
+    track: &[GpsPoint],
+    consensus: &[GpsPoint],
+    threshold_meters: f64,
+) -> (Vec<f64>, Vec<FfiDeviationSegment>) {
+    if track.len() < 2 || consensus.len() < 2 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let plane = LocalPlane::new(&consensus[0]);
+    let route: Vec<[f64; 2]> = consensus.iter().map(|p| plane.project(p)).collect();
+    let points: Vec<[f64; 2]> = track.iter().map(|p| plane.project(p)).collect();
+
+    // Cumulative distance along the consensus at each vertex
+    let mut along_route = Vec::with_capacity(route.len());
+    let mut total = 0.0;
+    along_route.push(0.0);
+    for pair in route.windows(2) {
+        total += plane_distance(pair[0], pair[1]);
+        along_route.push(total);
+    }
+
+    let rtree = RTree::bulk_load(
+        route
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| GeomWithData::new(Line::new(pair[0], pair[1]), i))
+            .collect(),
+    );
+
+    // Offset from the line and the projected position along it
+    let mut offsets = Vec::with_capacity(points.len());
+    let mut positions = Vec::with_capacity(points.len());
+    for p in &points {
+        let nearest = rtree
+            .nearest_neighbor(p)
+            .expect("consensus has at least one segment");
+        let foot = nearest.geom().nearest_point(p);
+        offsets.push(plane_distance(*p, foot));
+        positions.push(along_route[nearest.data] + plane_distance(route[nearest.data], foot));
+    }
+
+    // Every iteration consumes at least point `i`, whatever the threshold
+    // compares like (a NaN threshold matches nothing), so the scan always ends.
+    let off_route = |o: f64| o > threshold_meters;
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < offsets.len() {
+        let start = i;
+        i += 1;
+        if !off_route(offsets[start]) {
+            continue;
+        }
+        while i < offsets.len() && off_route(offsets[i]) {
+            i += 1;
+        }
+        let end = i - 1;
+        if end + 1 - start < MIN_DEVIATION_POINTS {
+            continue;
+        }
+
+        // Compare the detour with the consensus between the last on-route
+        // point before it and the first one after it.
+        let before = start.saturating_sub(1);
+        let after = (end + 1).min(points.len() - 1);
+        let travelled: f64 = points[before..=after]
+            .windows(2)
+            .map(|pair| plane_distance(pair[0], pair[1]))
+            .sum();
+        let expected = (positions[after] - positions[before]).abs();
+
+        segments.push(FfiDeviationSegment {
+            start_index: start as u32,
+            end_index: end as u32,
+            max_offset_meters: offsets[start..=end].iter().copied().fold(0.0, f64::max),
+            extra_distance_meters: (travelled - expected).max(0.0),
+        });
+    }
+
+    (offsets, segments)
+}
+
+/// Whether `threshold_meters` is usable as a deviation threshold.
+pub(crate) fn valid_threshold(threshold_meters: f64) -> bool {
+    threshold_meters.is_finite() && threshold_meters >= 0.0
+}
+
+impl PersistentRouteEngine {
+    /// Deviation of an activity from a route's consensus line. Returns None
+    /// if the route has no consensus, the activity has no GPS track or
+    /// `threshold_meters` is negative or not finite.
+    pub fn get_route_deviation(
+        &mut self,
+        group_id: &str,
+        activity_id: &str,
+        threshold_meters: f64,
+    ) -> Option<FfiRouteDeviation> {
+        if !valid_threshold(threshold_meters) {
+            return None;
+        }
+        let consensus = self.get_consensus_route(group_id)?;
+        let track = self.get_gps_track(activity_id)?;
+        let (offsets, segments) = analyze_route_deviation(&track, &consensus, threshold_meters);
+        if offsets.is_empty() {
+            return None;
+        }
+        Some(FfiRouteDeviation {
+            group_id: group_id.to_string(),
+            activity_id: activity_id.to_string(),
+            threshold_meters,
+            offsets,
+            segments,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Straight line east along the 47th parallel, ~7.6 m per point.
+    fn straight(points: usize) -> Vec<GpsPoint> {
+        (0..points)
+            .map(|i| GpsPoint::new(47.0, 7.0 + i as f64 * 0.0001))
+            .collect()
+    }
+
+    #[test]
+    fn on_route_track_has_no_deviations() {
+        let route = straight(100);
+        let track: Vec<GpsPoint> = straight(100)
+            .into_iter()
+            .map(|p| GpsPoint::new(p.latitude + 0.00005, p.longitude))
+            .collect();
+        let (offsets, segments) = analyze_route_deviation(&track, &route, 30.0);
+        assert_eq!(offsets.len(), 100);
+        assert!(offsets.iter().all(|o| (o - 5.56).abs() < 0.1));
+        assert!(segments.is_empty());
+    }
+
+    #[test]
+    fn detour_is_reported_with_offset_and_extra_distance() {
+        // Sparse consensus: only the endpoints, so vertex distance would be useless
+        let route = vec![GpsPoint::new(47.0, 7.0), GpsPoint::new(47.0, 7.01)];
+        // Points 40..=59 sit ~111 m north of the line
+        let track: Vec<GpsPoint> = straight(101)
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let lift = if (40..60).contains(&i) { 0.001 } else { 0.0 };
+                GpsPoint::new(p.latitude + lift, p.longitude)
+            })
+            .collect();
+
+        let (offsets, segments) = analyze_route_deviation(&track, &route, 30.0);
+        assert!(offsets[10] < 0.01);
+        assert_eq!(segments.len(), 1);
+        let seg = &segments[0];
+        assert_eq!((seg.start_index, seg.end_index), (40, 59));
+        assert!((seg.max_offset_meters - 111.2).abs() < 1.0);
+        // Two diagonal legs of ~111.5 m replace two 7.6 m steps on the line
+        assert!((seg.extra_distance_meters - 207.7).abs() < 2.0);
+    }
+
+    #[test]
+    fn short_blips_are_ignored() {
+        let route = straight(50);
+        let track: Vec<GpsPoint> = straight(50)
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let lift = if i == 20 || i == 21 { 0.001 } else { 0.0 };
+                GpsPoint::new(p.latitude + lift, p.longitude)
+            })
+            .collect();
+        let (offsets, segments) = analyze_route_deviation(&track, &route, 30.0);
+        assert!(offsets[20] > 100.0);
+        assert!(segments.is_empty());
+    }
+
+    #[test]
+    fn degenerate_thresholds_terminate() {
+        let route = straight(20);
+        let track = straight(20);
+        let (offsets, segments) = analyze_route_deviation(&track, &route, f64::NAN);
+        assert_eq!(offsets.len(), 20);
+        assert!(segments.is_empty());
+        // Every point is "off route" below zero; the scan still ends.
+        let (_, segments) = analyze_route_deviation(&track, &route, -1.0);
+        assert_eq!(segments.len(), 1);
+        assert_eq!((segments[0].start_index, segments[0].end_index), (0, 19));
+        assert!(!valid_threshold(f64::NAN));
+        assert!(!valid_threshold(f64::INFINITY));
+        assert!(!valid_threshold(-1.0));
+        assert!(valid_threshold(0.0));
+    }
+}
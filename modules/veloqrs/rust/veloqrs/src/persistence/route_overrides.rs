@@ -0,0 +1,401 @@
+//! User route overrides: merges and splits that survive regrouping.
+//!
+//! An override pins an activity to a user-defined route key. After every
+//! grouping pass - `recompute_groups` on the engine and the background
+//! detection thread - `apply_route_overrides` rewrites the algorithm's
+//! groups: activities sharing a key become one group whose id is the key,
+//! and unpinned activities follow the majority key of the algorithm group
+//! they landed in. New rides on a merged commute therefore join it, and a
+//! split variant stays split.
+
+use rusqlite::{Connection, Result as SqlResult, params};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::PersistentRouteEngine;
+use crate::{Bounds, RouteGroup};
+
+/// Prefix for override keys. Keeps them out of the grouping algorithm's id
+/// space, so an unpinned group can never collide with a user route.
+const ROUTE_KEY_PREFIX: &str = "user_";
+
+/// Load every pin as activity id → route key.
+pub(crate) fn load_route_overrides(conn: &Connection) -> SqlResult<HashMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT activity_id, route_key FROM route_overrides")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Rewrite algorithm groups so pinned activities form their user routes.
+/// Groups without pinned members pass through untouched. `bounds_of`
+/// supplies per-activity bounds for the rebuilt groups.
+pub(crate) fn apply_route_overrides(
+    groups: Vec<RouteGroup>,
+    pins: &HashMap<String, String>,
+    bounds_of: impl Fn(&str) -> Option<Bounds>,
+) -> Vec<RouteGroup> {
+    if pins.is_empty() {
+        return groups;
+    }
+
+    // key → (members, candidate representatives with member counts)
+    let mut clusters: BTreeMap<String, (Vec<String>, Vec<(String, usize)>)> = BTreeMap::new();
+    let mut out = Vec::with_capacity(groups.len());
+    for group in groups {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for id in &group.activity_ids {
+            if let Some(key) = pins.get(id) {
+                *counts.entry(key.as_str()).or_default() += 1;
+            }
+        }
+        // Most pinned members wins; ties go to the smallest key.
+        let Some(majority) = counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(k, _)| k.to_string())
+        else {
+            out.push(group);
+            continue;
+        };
+
+        let mut assigned: BTreeMap<String, usize> = BTreeMap::new();
+        for id in group.activity_ids {
+            let key = pins.get(&id).cloned().unwrap_or_else(|| majority.clone());
+            *assigned.entry(key.clone()).or_default() += 1;
+            clusters.entry(key).or_default().0.push(id);
+        }
+        for (key, n) in assigned {
+            if let Some(cluster) = clusters.get_mut(&key) {
+                cluster.1.push((group.representative_id.clone(), n));
+            }
+        }
+    }
+
+    for (key, (mut members, candidates)) in clusters {
+        members.sort();
+        members.dedup();
+        let member_set: HashSet<&str> = members.iter().map(String::as_str).collect();
+        let representative_id = candidates
+            .iter()
+            .filter(|(rep, _)| member_set.contains(rep.as_str()))
+            .max_by_key(|(_, n)| *n)
+            .map(|(rep, _)| rep.clone())
+            .unwrap_or_else(|| members[0].clone());
+        let bounds = members
+            .iter()
+            .filter_map(|id| bounds_of(id))
+            .reduce(|a, b| Bounds {
+                min_lat: a.min_lat.min(b.min_lat),
+                max_lat: a.max_lat.max(b.max_lat),
+                min_lng: a.min_lng.min(b.min_lng),
+                max_lng: a.max_lng.max(b.max_lng),
+            });
+        out.push(RouteGroup {
+            group_id: key,
+            representative_id,
+            activity_ids: members,
+            sport_type: String::new(),
+            bounds,
+            custom_name: None,
+            best_time: None,
+            avg_time: None,
+            best_pace: None,
+            best_activity_id: None,
+        });
+    }
+    out
+}
+
+impl PersistentRouteEngine {
+    /// Merge route groups into one user route. Every member of the given
+    /// routes is pinned to a new key; the first route's name carries over.
+    /// Returns the merged route id.
+    pub fn merge_routes(&mut self, route_ids: &[String]) -> Result<String, String> {
+        if route_ids.len() < 2 {
+            return Err("Merging needs at least two routes".to_string());
+        }
+        self.get_groups();
+        let mut members: Vec<String> = Vec::new();
+        for route_id in route_ids {
+            let group = self
+                .groups
+                .iter()
+                .find(|g| &g.group_id == route_id)
+                .ok_or_else(|| format!("Route group {} not found", route_id))?;
+            members.extend(group.activity_ids.iter().cloned());
+        }
+        let primary = &route_ids[0];
+        let seed = self
+            .groups
+            .iter()
+            .find(|g| &g.group_id == primary)
+            .map(|g| g.representative_id.clone())
+            .unwrap_or_else(|| primary.clone());
+        let key = self
+            .fresh_route_key(&seed, &[])
+            .map_err(|e| e.to_string())?;
+
+        self.write_route_overrides(&[(&key, primary, members.as_slice())])
+            .map_err(|e| format!("Failed to save route merge: {}", e))?;
+        self.regroup_after_override()
+            .map_err(|e| format!("Failed to regroup routes: {}", e))?;
+        log::info!(
+            "tracematch: [routes] Merged {} routes into {} ({} activities)",
+            route_ids.len(),
+            key,
+            members.len()
+        );
+        Ok(key)
+    }
+
+    /// Move `activity_ids` out of a route into a new user route. The rest of
+    /// the route is pinned too, so regrouping cannot fold them back together;
+    /// it keeps the original name. Returns the new route id.
+    pub fn split_route(
+        &mut self,
+        route_id: &str,
+        activity_ids: &[String],
+    ) -> Result<String, String> {
+        self.get_groups();
+        let group = self
+            .groups
+            .iter()
+            .find(|g| g.group_id == route_id)
+            .ok_or_else(|| format!("Route group {} not found", route_id))?;
+        let moving: HashSet<&str> = activity_ids.iter().map(String::as_str).collect();
+        if let Some(missing) = activity_ids
+            .iter()
+            .find(|id| !group.activity_ids.contains(id))
+        {
+            return Err(format!(
+                "Activity {} is not a member of route {}",
+                missing, route_id
+            ));
+        }
+        let (split_off, remaining): (Vec<String>, Vec<String>) = group
+            .activity_ids
+            .iter()
+            .cloned()
+            .partition(|id| moving.contains(id.as_str()));
+        if split_off.is_empty() || remaining.is_empty() {
+            return Err("A split must leave activities on both sides".to_string());
+        }
+
+        let kept_key = self
+            .fresh_route_key(&group.representative_id, &[])
+            .map_err(|e| e.to_string())?;
+        let seed = split_off.iter().min().cloned().unwrap_or_default();
+        let new_key = self
+            .fresh_route_key(&seed, &[&kept_key])
+            .map_err(|e| e.to_string())?;
+
+        self.write_route_overrides(&[
+            (&kept_key, route_id, remaining.as_slice()),
+            (&new_key, "", split_off.as_slice()),
+        ])
+        .map_err(|e| format!("Failed to save route split: {}", e))?;
+        self.regroup_after_override()
+            .map_err(|e| format!("Failed to regroup routes: {}", e))?;
+        log::info!(
+            "tracematch: [routes] Split {} activities from {} into {}",
+            split_off.len(),
+            route_id,
+            new_key
+        );
+        Ok(new_key)
+    }
+
+    /// Apply stored overrides to the in-memory groups and drop match rows
+    /// that no longer belong to their group.
+    pub(super) fn apply_route_overrides_in_memory(&mut self) {
+        let pins = match load_route_overrides(&self.db) {
+            Ok(pins) => pins,
+            Err(e) => {
+                log::warn!("tracematch: [routes] Failed to load route overrides: {}", e);
+                return;
+            }
+        };
+        if pins.is_empty() {
+            return;
+        }
+        let groups = std::mem::take(&mut self.groups);
+        let metadata = &self.activity_metadata;
+        self.groups = apply_route_overrides(groups, &pins, |id| metadata.get(id).map(|m| m.bounds));
+
+        let members: HashMap<&str, HashSet<&str>> = self
+            .groups
+            .iter()
+            .map(|g| {
+                (
+                    g.group_id.as_str(),
+                    g.activity_ids.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        self.activity_matches.retain(|route_id, matches| {
+            let Some(ids) = members.get(route_id.as_str()) else {
+                return false;
+            };
+            matches.retain(|m| ids.contains(m.activity_id.as_str()));
+            true
+        });
+    }
+
+    /// Persist pins for each (key, name source, members) triple in one
+    /// transaction. The key inherits the source route's name, and excluded
+    /// flags move with their activities.
+    fn write_route_overrides(&self, entries: &[(&str, &str, &[String])]) -> SqlResult<()> {
+        let tx = self.db.unchecked_transaction()?;
+        for (key, name_source, members) in entries {
+            for id in members.iter() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO route_overrides (activity_id, route_key) VALUES (?, ?)",
+                    params![id, key],
+                )?;
+                tx.execute(
+                    "UPDATE OR IGNORE activity_matches SET route_id = ?
+                     WHERE activity_id = ? AND excluded = 1",
+                    params![key, id],
+                )?;
+            }
+            if !name_source.is_empty() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO route_names (route_id, custom_name)
+                     SELECT ?, custom_name FROM route_names WHERE route_id = ?",
+                    params![key, name_source],
+                )?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Rebuild groups from the current ones plus overrides, then refresh
+    /// everything derived from group membership.
+    fn regroup_after_override(&mut self) -> SqlResult<()> {
+        self.apply_route_overrides_in_memory();
+        self.assign_group_sport_types();
+        self.recalculate_match_percentages_from_tracks();
+        self.save_groups()?;
+        self.load_groups()?;
+        self.consensus_cache.clear();
+        self.group_cache.clear();
+        self.invalidate_perf_cache();
+        self.refresh_group_derivations();
+        if let Err(e) = self.recompute_activity_indicators() {
+            log::warn!(
+                "tracematch: [routes] Indicator recomputation after override failed: {}",
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// An override key derived from `seed` that is neither stored, a current
+    /// group id, nor in `reserved`.
+    fn fresh_route_key(&self, seed: &str, reserved: &[&str]) -> SqlResult<String> {
+        let mut n = 0u32;
+        loop {
+            let key = if n == 0 {
+                format!("{}{}", ROUTE_KEY_PREFIX, seed)
+            } else {
+                format!("{}{}_{}", ROUTE_KEY_PREFIX, seed, n)
+            };
+            let taken: bool = self.db.query_row(
+                "SELECT EXISTS(SELECT 1 FROM route_overrides WHERE route_key = ?)",
+                params![key],
+                |row| row.get(0),
+            )?;
+            if !taken
+                && !reserved.contains(&key.as_str())
+                && !self.groups.iter().any(|g| g.group_id == key)
+            {
+                return Ok(key);
+            }
+            n += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(id: &str, members: &[&str]) -> RouteGroup {
+        RouteGroup {
+            group_id: id.to_string(),
+            representative_id: members[0].to_string(),
+            activity_ids: members.iter().map(|s| s.to_string()).collect(),
+            sport_type: "Ride".to_string(),
+            bounds: None,
+            custom_name: None,
+            best_time: None,
+            avg_time: None,
+            best_pace: None,
+            best_activity_id: None,
+        }
+    }
+
+    fn pins(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(a, k)| (a.to_string(), k.to_string()))
+            .collect()
+    }
+
+    fn members_of<'a>(groups: &'a [RouteGroup], id: &str) -> &'a [String] {
+        &groups
+            .iter()
+            .find(|g| g.group_id == id)
+            .unwrap()
+            .activity_ids
+    }
+
+    #[test]
+    fn merged_key_absorbs_groups_and_newcomers() {
+        let groups = vec![
+            group("g1", &["a", "b"]),
+            group("g2", &["c", "d", "new"]),
+            group("g3", &["x"]),
+        ];
+        let pins = pins(&[
+            ("a", "user_a"),
+            ("b", "user_a"),
+            ("c", "user_a"),
+            ("d", "user_a"),
+        ]);
+        let out = apply_route_overrides(groups, &pins, |_| None);
+        assert_eq!(out.len(), 2);
+        assert_eq!(members_of(&out, "user_a"), ["a", "b", "c", "d", "new"]);
+        assert_eq!(members_of(&out, "g3"), ["x"]);
+        // g2 contributed the most members, so its representative leads.
+        let merged = out.iter().find(|g| g.group_id == "user_a").unwrap();
+        assert_eq!(merged.representative_id, "c");
+    }
+
+    #[test]
+    fn split_keys_stay_apart_when_regrouped_together() {
+        let groups = vec![group("g1", &["a", "b", "c", "new"])];
+        let pins = pins(&[("a", "user_k"), ("b", "user_k"), ("c", "user_s")]);
+        let out = apply_route_overrides(groups, &pins, |_| None);
+        assert_eq!(members_of(&out, "user_k"), ["a", "b", "new"]);
+        assert_eq!(members_of(&out, "user_s"), ["c"]);
+        let split = out.iter().find(|g| g.group_id == "user_s").unwrap();
+        assert_eq!(split.representative_id, "c");
+    }
+
+    #[test]
+    fn bounds_cover_all_members() {
+        let groups = vec![group("g1", &["a"]), group("g2", &["b"])];
+        let pins = pins(&[("a", "user_a"), ("b", "user_a")]);
+        let out = apply_route_overrides(groups, &pins, |id| {
+            let base = if id == "a" { 46.0 } else { 47.0 };
+            Some(Bounds {
+                min_lat: base,
+                max_lat: base + 0.1,
+                min_lng: 7.0,
+                max_lng: 7.1,
+            })
+        });
+        let b = out[0].bounds.unwrap();
+        assert_eq!((b.min_lat, b.max_lat), (46.0, 47.1));
+    }
+}
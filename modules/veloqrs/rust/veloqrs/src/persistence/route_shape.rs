@@ -0,0 +1,315 @@
+//! Route shape classification: loop, out-and-back, point-to-point, lollipop.
+//!
+//! The consensus polyline is resampled to evenly spaced points, and each
+//! point is marked "shared" if the route passes within `MATCH_RADIUS_M` of it
+//! again further along. Out-and-backs retrace almost everything; lollipops
+//! retrace a stem at both ends of a closed route; loops close without
+//! retracing; point-to-point routes don't close.
+//!
+//! Results live in `route_groups.shape` / `shape_confidence`. Both group
+//! writers wipe and rebuild that table, so they snapshot the shapes first and
+//! restore them for groups whose membership is unchanged - anything else is
+//! reclassified by `classify_route_shapes` once the groups are saved. Routes
+//! too short to classify are stored as `SHAPE_NONE` so they are not retried.
+
+use std::collections::HashMap;
+
+use rusqlite::{Connection, Result as SqlResult, params};
+
+use super::PersistentRouteEngine;
+use super::route_deviation::{LocalPlane, plane_distance};
+use crate::GpsPoint;
+
+/// Points the polyline is resampled to before comparing.
+const SAMPLES: usize = 200;
+/// Two passes closer than this are the same road.
+const MATCH_RADIUS_M: f64 = 50.0;
+/// Neighbours this close along the route don't count as a second pass.
+const MIN_PASS_GAP_M: f64 = 4.0 * MATCH_RADIUS_M;
+/// Start and end within max(this, 10% of the length) make a closed route.
+const MIN_CLOSE_DISTANCE_M: f64 = 200.0;
+/// Shared fraction above which a route is an out-and-back.
+const OUT_AND_BACK_SHARED: f64 = 0.7;
+/// Retraced stem fraction above which a closed route is a lollipop.
+const MIN_STEM_FRACTION: f64 = 0.1;
+
+/// Stored `shape` of a group whose consensus line could not be classified.
+/// Readers report it as no shape.
+pub(crate) const SHAPE_NONE: &str = "none";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RouteShape {
+    Loop,
+    OutAndBack,
+    PointToPoint,
+    Lollipop,
+}
+
+impl RouteShape {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            RouteShape::Loop => "loop",
+            RouteShape::OutAndBack => "out_and_back",
+            RouteShape::PointToPoint => "point_to_point",
+            RouteShape::Lollipop => "lollipop",
+        }
+    }
+}
+
+/// Resample a projected polyline to `n` points evenly spaced by distance.
+/// Returns the points and the total length in meters.
+fn resample(points: &[[f64; 2]], n: usize) -> (Vec<[f64; 2]>, f64) {
+    let mut cumulative = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    cumulative.push(0.0);
+    for pair in points.windows(2) {
+        total += plane_distance(pair[0], pair[1]);
+        cumulative.push(total);
+    }
+
+    let mut out = Vec::with_capacity(n);
+    let mut seg = 0;
+    for k in 0..n {
+        let target = total * k as f64 / (n - 1) as f64;
+        while seg + 2 < points.len() && cumulative[seg + 1] < target {
+            seg += 1;
+        }
+        let len = cumulative[seg + 1] - cumulative[seg];
+        let t = if len > 0.0 {
+            ((target - cumulative[seg]) / len).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (a, b) = (points[seg], points[seg + 1]);
+        out.push([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]);
+    }
+    (out, total)
+}
+
+/// Classify a route polyline. Returns the shape and a heuristic confidence
+/// in 0.0-1.0, or None for polylines too short to tell.
+pub(crate) fn classify_route_shape(polyline: &[GpsPoint]) -> Option<(RouteShape, f64)> {
+    if polyline.len() < 2 {
+        return None;
+    }
+    let plane = LocalPlane::new(&polyline[0]);
+    let projected: Vec<[f64; 2]> = polyline.iter().map(|p| plane.project(p)).collect();
+    let (samples, length) = resample(&projected, SAMPLES);
+    if length < 2.0 * MIN_PASS_GAP_M {
+        return None;
+    }
+
+    let step = length / (SAMPLES - 1) as f64;
+    let min_gap = (MIN_PASS_GAP_M / step).ceil() as usize;
+    let shared: Vec<bool> = (0..SAMPLES)
+        .map(|i| {
+            (0..SAMPLES).any(|j| {
+                i.abs_diff(j) > min_gap && plane_distance(samples[i], samples[j]) <= MATCH_RADIUS_M
+            })
+        })
+        .collect();
+    let shared_fraction = shared.iter().filter(|&&s| s).count() as f64 / SAMPLES as f64;
+
+    let close_limit = (0.1 * length).max(MIN_CLOSE_DISTANCE_M);
+    let end_gap = plane_distance(samples[0], samples[SAMPLES - 1]);
+
+    if shared_fraction >= OUT_AND_BACK_SHARED {
+        return Some((RouteShape::OutAndBack, shared_fraction));
+    }
+    if end_gap > close_limit {
+        let separation = (end_gap / (2.0 * close_limit)).clamp(0.5, 1.0);
+        return Some((
+            RouteShape::PointToPoint,
+            (1.0 - shared_fraction) * separation,
+        ));
+    }
+
+    let closeness = 0.5 + 0.5 * (1.0 - end_gap / close_limit);
+    let lead = shared.iter().take_while(|&&s| s).count();
+    let trail = shared.iter().rev().take_while(|&&s| s).count();
+    let stem_fraction = (lead + trail) as f64 / SAMPLES as f64;
+    if stem_fraction >= MIN_STEM_FRACTION {
+        Some((
+            RouteShape::Lollipop,
+            (0.5 + stem_fraction).min(1.0) * closeness,
+        ))
+    } else {
+        Some((RouteShape::Loop, (1.0 - shared_fraction) * closeness))
+    }
+}
+
+/// A stored classification: group id, the membership blob it was made for,
+/// shape and confidence (None for `SHAPE_NONE`).
+pub(crate) type StoredShape = (String, Vec<u8>, String, Option<f64>);
+
+/// Read classified shapes before `route_groups` is wiped and rebuilt.
+pub(crate) fn snapshot_route_shapes(conn: &Connection) -> SqlResult<Vec<StoredShape>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, activity_ids_blob, shape, shape_confidence FROM route_groups
+         WHERE shape IS NOT NULL AND activity_ids_blob IS NOT NULL",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+    })?;
+    rows.collect()
+}
+
+/// Put snapshotted shapes back on groups whose id and membership survived.
+pub(crate) fn restore_route_shapes(conn: &Connection, shapes: &[StoredShape]) -> SqlResult<()> {
+    let mut stmt = conn.prepare(
+        "UPDATE route_groups SET shape = ?, shape_confidence = ?
+         WHERE id = ? AND activity_ids_blob = ?",
+    )?;
+    for (id, blob, shape, confidence) in shapes {
+        stmt.execute(params![shape, confidence, id, blob])?;
+    }
+    Ok(())
+}
+
+impl PersistentRouteEngine {
+    /// Classify every route group that has no stored shape yet, from its
+    /// consensus polyline. Groups too short to tell are marked `SHAPE_NONE`.
+    /// Returns how many groups were classified.
+    pub fn classify_route_shapes(&mut self) -> SqlResult<u32> {
+        let pending: Vec<String> = {
+            let mut stmt = self
+                .db
+                .prepare("SELECT id FROM route_groups WHERE shape IS NULL")?;
+            stmt.query_map([], |row| row.get(0))?
+                .collect::<SqlResult<_>>()?
+        };
+
+        let mut classified = 0u32;
+        for group_id in &pending {
+            let Some(consensus) = self.get_consensus_route(group_id) else {
+                continue;
+            };
+            let (shape, confidence) = match classify_route_shape(&consensus) {
+                Some((shape, confidence)) => (shape.as_str(), Some(confidence)),
+                None => (SHAPE_NONE, None),
+            };
+            self.db.execute(
+                "UPDATE route_groups SET shape = ?, shape_confidence = ? WHERE id = ?",
+                params![shape, confidence, group_id],
+            )?;
+            if confidence.is_some() {
+                classified += 1;
+            }
+        }
+
+        if classified > 0 {
+            log::info!(
+                "tracematch: [PersistentEngine] Classified {} of {} unclassified route shapes",
+                classified,
+                pending.len()
+            );
+        }
+        Ok(classified)
+    }
+
+    /// Shape and confidence of every classified route group, keyed by group id.
+    pub fn get_route_shapes(&self) -> SqlResult<HashMap<String, (String, f64)>> {
+        let mut stmt = self.db.prepare(
+            "SELECT id, shape, shape_confidence FROM route_groups
+             WHERE shape != ? AND shape_confidence IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![SHAPE_NONE], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?)))
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walk `(dx, dy)` legs in metres from a fixed origin, 10 m per point.
+    fn path(legs: &[(f64, f64)]) -> Vec<GpsPoint> {
+        let m_per_deg_lat = 111_195.0;
+        let m_per_deg_lng = m_per_deg_lat * 47.0f64.to_radians().cos();
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut points = vec![GpsPoint::new(47.0, 7.0)];
+        for &(dx, dy) in legs {
+            let steps = ((dx * dx + dy * dy).sqrt() / 10.0).ceil() as usize;
+            for _ in 0..steps {
+                x += dx / steps as f64;
+                y += dy / steps as f64;
+                points.push(GpsPoint::new(
+                    47.0 + y / m_per_deg_lat,
+                    7.0 + x / m_per_deg_lng,
+                ));
+            }
+        }
+        points
+    }
+
+    fn shape_of(legs: &[(f64, f64)]) -> RouteShape {
+        classify_route_shape(&path(legs)).expect("classifiable").0
+    }
+
+    #[test]
+    fn classifies_the_four_shapes() {
+        let square = [(2000.0, 0.0), (0.0, 2000.0), (-2000.0, 0.0), (0.0, -2000.0)];
+        assert_eq!(shape_of(&square), RouteShape::Loop);
+
+        assert_eq!(
+            shape_of(&[(3000.0, 0.0), (0.0, 1000.0), (0.0, -1000.0), (-3000.0, 0.0)]),
+            RouteShape::OutAndBack
+        );
+
+        assert_eq!(
+            shape_of(&[(5000.0, 0.0), (0.0, 3000.0)]),
+            RouteShape::PointToPoint
+        );
+
+        // 1.5 km stem out to a 2 km square, then back down the stem
+        let lollipop = [
+            (1500.0, 0.0),
+            (2000.0, 0.0),
+            (0.0, 2000.0),
+            (-2000.0, 0.0),
+            (0.0, -2000.0),
+            (-1500.0, 0.0),
+        ];
+        assert_eq!(shape_of(&lollipop), RouteShape::Lollipop);
+    }
+
+    #[test]
+    fn confidence_is_a_fraction_and_tiny_routes_are_skipped() {
+        let (_, confidence) =
+            classify_route_shape(&path(&[(1000.0, 0.0), (-1000.0, 0.0)])).unwrap();
+        assert!(confidence > 0.9 && confidence <= 1.0);
+
+        assert!(classify_route_shape(&path(&[(100.0, 0.0)])).is_none());
+    }
+
+    #[test]
+    fn unclassifiable_routes_are_marked_and_read_as_no_shape() {
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        engine
+            .add_activity("a1".to_string(), path(&[(100.0, 0.0)]), "Ride".to_string())
+            .unwrap();
+        engine
+            .db
+            .execute(
+                "INSERT INTO route_groups (id, representative_id, activity_ids, sport_type)
+                 VALUES ('r1', 'a1', '[\"a1\"]', 'Ride')",
+                [],
+            )
+            .unwrap();
+
+        assert_eq!(engine.classify_route_shapes().unwrap(), 0);
+        let stored: Option<String> = engine
+            .db
+            .query_row(
+                "SELECT shape FROM route_groups WHERE id = 'r1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored.as_deref(), Some(SHAPE_NONE));
+        assert!(engine.get_route_shapes().unwrap().is_empty());
+        assert_eq!(engine.get_group_summaries()[0].shape, None);
+    }
+}
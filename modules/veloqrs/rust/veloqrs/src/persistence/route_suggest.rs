@@ -0,0 +1,389 @@
+//! Route suggestions: rides of a target distance built from known routes.
+//!
+//! Every candidate starts and finishes near the requested point and is
+//! stitched together from consensus routes and detected sections, so it only
+//! uses roads the athlete has actually ridden:
+//! - a loop route on its own,
+//! - a loop with one stretch swapped for a section that leaves and rejoins it,
+//! - two loops back to back (including the same loop twice).
+//!
+//! Loops are found through the activity R-tree: groups with an activity near
+//! the start whose consensus begins and ends within `START_RADIUS_M` of it.
+//! Loops and the sections near them get their missing elevation filled from
+//! their representative activity, which the elevation gain is summed from.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::PersistentRouteEngine;
+use crate::{Bounds, FfiGpsPoint, FfiRouteSuggestion, FrequentSection, GpsPoint, geo_utils};
+
+/// Loops must start and finish this close to the requested point.
+const START_RADIUS_M: f64 = 1000.0;
+/// A section joins a loop if both its ends are this close to it.
+const JOIN_RADIUS_M: f64 = 100.0;
+/// Most-ridden nearby loops considered.
+const MAX_LOOPS: usize = 20;
+const MAX_SUGGESTIONS: usize = 10;
+/// Rides after which a route or section counts as fully familiar.
+const FAMILIAR_RIDES: f64 = 10.0;
+
+/// A nearby loop route with its consensus polyline.
+struct Loop {
+    route_id: String,
+    points: Arc<Vec<GpsPoint>>,
+    distance: f64,
+    familiarity: f64,
+}
+
+/// An assembled candidate before ranking.
+struct Candidate {
+    route_ids: Vec<String>,
+    section_ids: Vec<String>,
+    points: Vec<GpsPoint>,
+    distance: f64,
+    /// Distance weighted by the familiarity of the piece it came from
+    familiar_distance: f64,
+}
+
+fn path_distance(points: &[GpsPoint]) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| geo_utils::haversine_distance(&pair[0], &pair[1]))
+        .sum()
+}
+
+/// Sum of positive elevation changes, skipping points without elevation.
+fn elevation_gain(points: &[GpsPoint]) -> f64 {
+    let mut gain = 0.0;
+    let mut last: Option<f64> = None;
+    for ele in points.iter().filter_map(|p| p.elevation) {
+        if let Some(prev) = last
+            && ele > prev
+        {
+            gain += ele - prev;
+        }
+        last = Some(ele);
+    }
+    gain
+}
+
+/// Whether a section's bounds come within `JOIN_RADIUS_M` of a loop's.
+fn near_loop(route_bounds: &Bounds, section: &[GpsPoint]) -> bool {
+    let section_bounds = geo_utils::compute_bounds(section);
+    let ref_lat = (route_bounds.min_lat + route_bounds.max_lat) / 2.0;
+    geo_utils::bounds_overlap(route_bounds, &section_bounds, JOIN_RADIUS_M, ref_lat)
+}
+
+/// Index of the polyline point nearest to `p`, with its distance.
+fn nearest_index(points: &[GpsPoint], p: &GpsPoint) -> (usize, f64) {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, q)| (i, geo_utils::haversine_distance(p, q)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, f64::INFINITY))
+}
+
+fn familiarity(rides: u32) -> f64 {
+    (rides as f64 / FAMILIAR_RIDES).min(1.0)
+}
+
+/// Swap the stretch of `route` between the section's ends for the section.
+/// Returns the new path and the route distance it replaced, or None if the
+/// section doesn't leave and rejoin the route.
+fn splice_section(route: &[GpsPoint], section: &[GpsPoint]) -> Option<(Vec<GpsPoint>, f64)> {
+    let first = section.first()?;
+    let last = section.last()?;
+    let (ia, da) = nearest_index(route, first);
+    let (ib, db) = nearest_index(route, last);
+    if da > JOIN_RADIUS_M || db > JOIN_RADIUS_M || ia == ib {
+        return None;
+    }
+    // A section already on the route isn't a detour
+    let (_, dmid) = nearest_index(route, &section[section.len() / 2]);
+    if dmid <= JOIN_RADIUS_M {
+        return None;
+    }
+
+    let (from, to, forward) = if ia < ib {
+        (ia, ib, true)
+    } else {
+        (ib, ia, false)
+    };
+    let mut points = Vec::with_capacity(route.len() + section.len());
+    points.extend_from_slice(&route[..=from]);
+    if forward {
+        points.extend_from_slice(section);
+    } else {
+        points.extend(section.iter().rev().copied());
+    }
+    points.extend_from_slice(&route[to..]);
+    Some((points, path_distance(&route[from..=to])))
+}
+
+/// Assemble every candidate from the loops and sections.
+fn build_candidates(loops: &[Loop], sections: &[FrequentSection]) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+
+    for lp in loops {
+        candidates.push(Candidate {
+            route_ids: vec![lp.route_id.clone()],
+            section_ids: Vec::new(),
+            points: lp.points.to_vec(),
+            distance: lp.distance,
+            familiar_distance: lp.distance * lp.familiarity,
+        });
+
+        let route_bounds = geo_utils::compute_bounds(&lp.points);
+        for section in sections {
+            if section.polyline.len() < 2 || !near_loop(&route_bounds, &section.polyline) {
+                continue;
+            }
+            let Some((points, replaced)) = splice_section(&lp.points, &section.polyline) else {
+                continue;
+            };
+            let section_distance = path_distance(&section.polyline);
+            candidates.push(Candidate {
+                route_ids: vec![lp.route_id.clone()],
+                section_ids: vec![section.id.clone()],
+                distance: path_distance(&points),
+                familiar_distance: (lp.distance - replaced).max(0.0) * lp.familiarity
+                    + section_distance * familiarity(section.visit_count),
+                points,
+            });
+        }
+    }
+
+    for (i, a) in loops.iter().enumerate() {
+        for b in &loops[i..] {
+            let mut points = Vec::with_capacity(a.points.len() + b.points.len());
+            points.extend_from_slice(&a.points);
+            points.extend_from_slice(&b.points);
+            candidates.push(Candidate {
+                route_ids: vec![a.route_id.clone(), b.route_id.clone()],
+                section_ids: Vec::new(),
+                distance: path_distance(&points),
+                familiar_distance: a.distance * a.familiarity + b.distance * b.familiarity,
+                points,
+            });
+        }
+    }
+
+    candidates
+}
+
+/// Keep candidates within `tolerance` of `target` and rank them. The score
+/// weighs distance match and familiarity equally.
+fn rank_candidates(
+    candidates: Vec<Candidate>,
+    target: f64,
+    tolerance: f64,
+) -> Vec<FfiRouteSuggestion> {
+    let tolerance = tolerance.max(1.0);
+    let mut ranked: Vec<FfiRouteSuggestion> = candidates
+        .into_iter()
+        .filter(|c| c.distance > 0.0 && (c.distance - target).abs() <= tolerance)
+        .map(|c| {
+            let familiarity = (c.familiar_distance / c.distance).clamp(0.0, 1.0);
+            let fit = 1.0 - (c.distance - target).abs() / tolerance;
+            FfiRouteSuggestion {
+                route_ids: c.route_ids,
+                section_ids: c.section_ids,
+                distance_meters: c.distance,
+                elevation_gain_meters: elevation_gain(&c.points),
+                familiarity,
+                score: 0.5 * fit + 0.5 * familiarity,
+                points: c.points.into_iter().map(FfiGpsPoint::from).collect(),
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked.truncate(MAX_SUGGESTIONS);
+    ranked
+}
+
+impl PersistentRouteEngine {
+    /// Suggest rides of about `target_distance_meters` (± `tolerance_meters`)
+    /// that start and finish near the given point, built from the athlete's
+    /// own routes and sections for `sport_type`. Best first.
+    pub fn suggest_routes(
+        &mut self,
+        start_lat: f64,
+        start_lng: f64,
+        sport_type: &str,
+        target_distance_meters: f64,
+        tolerance_meters: f64,
+    ) -> Vec<FfiRouteSuggestion> {
+        let start = GpsPoint::new(start_lat, start_lng);
+        let loops = self.nearby_loops(&start, sport_type);
+        if loops.is_empty() {
+            return Vec::new();
+        }
+        let sections = self.nearby_sections(&loops, sport_type);
+        let candidates = build_candidates(&loops, &sections);
+        let candidate_count = candidates.len();
+        let ranked = rank_candidates(candidates, target_distance_meters, tolerance_meters);
+
+        log::info!(
+            "tracematch: [RouteSuggest] {} loops, {} candidates, {} within {:.0}±{:.0} m",
+            loops.len(),
+            candidate_count,
+            ranked.len(),
+            target_distance_meters,
+            tolerance_meters
+        );
+        ranked
+    }
+
+    /// Most-ridden route groups of `sport_type` whose consensus starts and
+    /// finishes near `start`.
+    fn nearby_loops(&mut self, start: &GpsPoint, sport_type: &str) -> Vec<Loop> {
+        let lat_deg = START_RADIUS_M / 111_000.0;
+        let lng_deg = lat_deg / start.latitude.to_radians().cos().max(0.01);
+        let near: HashSet<String> = self
+            .query_viewport(&Bounds {
+                min_lat: start.latitude - lat_deg,
+                max_lat: start.latitude + lat_deg,
+                min_lng: start.longitude - lng_deg,
+                max_lng: start.longitude + lng_deg,
+            })
+            .into_iter()
+            .collect();
+
+        let mut groups: Vec<(String, u32)> = self
+            .get_groups()
+            .iter()
+            .filter(|g| g.sport_type == sport_type)
+            .filter(|g| g.activity_ids.iter().any(|id| near.contains(id)))
+            .map(|g| (g.group_id.clone(), g.activity_ids.len() as u32))
+            .collect();
+        groups.sort_by_key(|g| std::cmp::Reverse(g.1));
+        groups.truncate(MAX_LOOPS);
+
+        groups
+            .into_iter()
+            .filter_map(|(route_id, rides)| {
+                let consensus = self.get_consensus_route(&route_id)?;
+                let first = consensus.first()?;
+                let last = consensus.last()?;
+                if geo_utils::haversine_distance(start, first) > START_RADIUS_M
+                    || geo_utils::haversine_distance(start, last) > START_RADIUS_M
+                {
+                    return None;
+                }
+                let points = Arc::new(self.route_with_elevation(&route_id)?);
+                Some(Loop {
+                    route_id,
+                    distance: path_distance(&points),
+                    familiarity: familiarity(rides),
+                    points,
+                })
+            })
+            .collect()
+    }
+
+    /// Sections of `sport_type` close enough to one of the loops to be
+    /// spliced into it, with elevation filled.
+    fn nearby_sections(&self, loops: &[Loop], sport_type: &str) -> Vec<FrequentSection> {
+        let loop_bounds: Vec<Bounds> = loops
+            .iter()
+            .map(|lp| geo_utils::compute_bounds(&lp.points))
+            .collect();
+        let mut sections: Vec<FrequentSection> = self
+            .get_sections_filtered(Some(sport_type), None)
+            .into_iter()
+            .filter(|s| {
+                s.polyline.len() >= 2 && loop_bounds.iter().any(|b| near_loop(b, &s.polyline))
+            })
+            .cloned()
+            .collect();
+        for section in &mut sections {
+            let polyline = std::mem::take(&mut section.polyline);
+            section.polyline =
+                self.with_elevation(polyline, Some(&section.representative_activity_id));
+        }
+        sections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Square loop from the origin with `side` metres per side, ~10 m spacing.
+    fn square(side: f64) -> Vec<GpsPoint> {
+        let m_lat = 111_195.0;
+        let m_lng = m_lat * 47.0f64.to_radians().cos();
+        let corners = [
+            (0.0, 0.0),
+            (side, 0.0),
+            (side, side),
+            (0.0, side),
+            (0.0, 0.0),
+        ];
+        let mut points = Vec::new();
+        for pair in corners.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            let steps = (side / 10.0) as usize;
+            for s in 0..steps {
+                let t = s as f64 / steps as f64;
+                let (x, y) = (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+                points.push(GpsPoint::new(47.0 + y / m_lat, 7.0 + x / m_lng));
+            }
+        }
+        points.push(points[0]);
+        points
+    }
+
+    fn make_loop(id: &str, side: f64, rides: u32) -> Loop {
+        let points = Arc::new(square(side));
+        Loop {
+            route_id: id.to_string(),
+            distance: path_distance(&points),
+            familiarity: familiarity(rides),
+            points,
+        }
+    }
+
+    #[test]
+    fn pairs_loops_to_reach_the_target() {
+        let loops = vec![make_loop("short", 2500.0, 10), make_loop("long", 5000.0, 2)];
+        let candidates = build_candidates(&loops, &[]);
+        // two singles + short+short, short+long, long+long
+        assert_eq!(candidates.len(), 5);
+
+        let ranked = rank_candidates(candidates, 20_000.0, 1_000.0);
+        let ids: Vec<Vec<String>> = ranked.iter().map(|s| s.route_ids.clone()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                vec!["short".to_string(), "short".to_string()],
+                vec!["long".to_string()],
+            ]
+        );
+        assert!(ranked[0].familiarity > ranked[1].familiarity);
+        assert!((ranked[0].distance_meters - 20_000.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn section_detour_replaces_the_stretch_it_bypasses() {
+        let route = square(1000.0);
+        // Cut across the square from the middle of the bottom edge to the
+        // middle of the top edge
+        let m_lat = 111_195.0;
+        let m_lng = m_lat * 47.0f64.to_radians().cos();
+        let section: Vec<GpsPoint> = (0..=100)
+            .map(|i| GpsPoint::new(47.0 + i as f64 * 10.0 / m_lat, 7.0 + 500.0 / m_lng))
+            .collect();
+
+        let (points, replaced) = splice_section(&route, &section).expect("joins the loop");
+        // Bottom half-edge + right edge + top half-edge replaced by 1 km
+        assert!((replaced - 2000.0).abs() < 30.0);
+        assert!((path_distance(&points) - 3000.0).abs() < 50.0);
+
+        // A section lying on the route itself is not a detour
+        let on_route = route[10..60].to_vec();
+        assert!(splice_section(&route, &on_route).is_none());
+    }
+}
@@ -7,7 +7,7 @@ use rusqlite::{Result as SqlResult, params, types::Type};
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use super::{GroupSummary, PersistentRouteEngine, codec, get_route_word};
+use super::{GroupSummary, PersistentRouteEngine, climbs, codec, get_route_word, route_shape};
 
 impl PersistentRouteEngine {
     // ========================================================================
@@ -429,6 +429,23 @@ impl PersistentRouteEngine {
         if let Err(e) = self.load_groups() {
             log::warn!("[reload_groups_from_db] Failed: {}", e);
         }
+        self.consensus_cache.clear();
+        self.refresh_group_derivations();
+    }
+
+    /// Fill in what is derived from each group's consensus line (shape,
+    /// climb profile) for groups that lost it in a regroup, so reads only see
+    /// stored values. Called after every group save.
+    pub(crate) fn refresh_group_derivations(&mut self) {
+        if let Err(e) = self.classify_route_shapes() {
+            log::warn!(
+                "tracematch: [routes] Route shape classification failed: {}",
+                e
+            );
+        }
+        if let Err(e) = self.update_route_climb_profiles() {
+            log::warn!("tracematch: [routes] Climb profile update failed: {}", e);
+        }
     }
 
     /// Recompute route groups.
@@ -538,6 +555,9 @@ impl PersistentRouteEngine {
             self.activity_matches = result.activity_matches;
         }
 
+        // User merges / splits override the algorithm's grouping
+        self.apply_route_overrides_in_memory();
+
         // Phase 3: Recalculate match percentages using ORIGINAL GPS tracks (not simplified signatures)
         // This captures actual GPS variation that was smoothed out by Douglas-Peucker
         // NOTE: This is the BOTTLENECK - see PERF logs inside this function
@@ -551,19 +571,7 @@ impl PersistentRouteEngine {
             total_matches
         );
 
-        // Populate sport_type for each group from the representative activity
-        for group in &mut self.groups {
-            if let Some(meta) = self.activity_metadata.get(&group.representative_id) {
-                group.sport_type = if meta.sport_type.is_empty() {
-                    "Ride".to_string() // Default for empty sport type
-                } else {
-                    meta.sport_type.clone()
-                };
-            } else {
-                // Representative activity not found - use default
-                group.sport_type = "Ride".to_string();
-            }
-        }
+        self.assign_group_sport_types();
 
         // Phase 4: Save to database
         let save_start = Instant::now();
@@ -572,6 +580,7 @@ impl PersistentRouteEngine {
         }
         let save_ms = save_start.elapsed().as_millis();
         self.groups_dirty = false;
+        self.refresh_group_derivations();
 
         // Recompute materialized PR/trend indicators with updated route groups
         if let Err(e) = self.recompute_activity_indicators() {
@@ -592,9 +601,25 @@ impl PersistentRouteEngine {
         );
     }
 
+    /// Populate sport_type for each group from the representative activity.
+    pub(super) fn assign_group_sport_types(&mut self) {
+        for group in &mut self.groups {
+            if let Some(meta) = self.activity_metadata.get(&group.representative_id) {
+                group.sport_type = if meta.sport_type.is_empty() {
+                    "Ride".to_string() // Default for empty sport type
+                } else {
+                    meta.sport_type.clone()
+                };
+            } else {
+                // Representative activity not found - use default
+                group.sport_type = "Ride".to_string();
+            }
+        }
+    }
+
     /// Recalculate match percentages using original GPS tracks instead of simplified signatures.
     /// Uses AMD (Average Minimum Distance) for accurate track comparison.
-    fn recalculate_match_percentages_from_tracks(&mut self) {
+    pub(super) fn recalculate_match_percentages_from_tracks(&mut self) {
         use crate::matching::{amd_to_percentage, average_min_distance};
         use std::collections::HashMap;
         use std::time::Instant;
@@ -775,7 +800,7 @@ impl PersistentRouteEngine {
         Ok(())
     }
 
-    fn save_groups(&self) -> SqlResult<()> {
+    pub(super) fn save_groups(&self) -> SqlResult<()> {
         // The DELETE + rebuild below must be atomic: a failure mid-way would
         // otherwise permanently drop every route group and activity match.
         self.db.execute_batch("BEGIN IMMEDIATE")?;
@@ -791,6 +816,10 @@ impl PersistentRouteEngine {
                     .collect()
             };
 
+            // Shapes depend only on membership - keep them for unchanged groups
+            let shapes = route_shape::snapshot_route_shapes(&self.db)?;
+            let memberships = climbs::snapshot_group_memberships(&self.db)?;
+
             self.db.execute("DELETE FROM route_groups", [])?;
             self.db.execute("DELETE FROM activity_matches", [])?;
 
@@ -941,6 +970,9 @@ impl PersistentRouteEngine {
                 }
             }
 
+            route_shape::restore_route_shapes(&self.db, &shapes)?;
+            climbs::drop_changed_route_profiles(&self.db, &memberships)?;
+
             // Insert activity matches
             let mut match_stmt = self.db.prepare(
                 "INSERT OR IGNORE INTO activity_matches (route_id, activity_id, match_percentage, direction)
@@ -1018,8 +1050,11 @@ impl PersistentRouteEngine {
         let mut stmt = match self.db.prepare(
             "SELECT id, representative_id, sport_type, activity_ids,
                     bounds_min_lat, bounds_max_lat, bounds_min_lng, bounds_max_lng,
-                    activity_count, activity_ids_blob
-             FROM route_groups",
+                    activity_count, activity_ids_blob, shape, shape_confidence,
+                    cp.total_ascent, cp.avg_gradient, cp.max_gradient, cp.climb_category
+             FROM route_groups
+             LEFT JOIN climb_profiles cp
+                    ON cp.target_type = 'route' AND cp.target_id = route_groups.id",
         ) {
             Ok(s) => s,
             Err(e) => {
@@ -1091,6 +1126,14 @@ impl PersistentRouteEngine {
                         custom_name,
                         bounds,
                         sport_types: vec![], // populated below
+                        shape: row
+                            .get::<_, Option<String>>(10)?
+                            .filter(|s| s != route_shape::SHAPE_NONE),
+                        shape_confidence: row.get(11)?,
+                        total_ascent: row.get(12)?,
+                        avg_gradient: row.get(13)?,
+                        max_gradient: row.get(14)?,
+                        climb_category: row.get(15)?,
                     },
                     activity_ids,
                 ))
@@ -9,13 +9,23 @@ use super::{PersistentRouteEngine, codec};
 
 impl PersistentRouteEngine {
     /// App-level schema version for post-migration Rust hooks.
-    /// Independent of rusqlite_migration's PRAGMA user_version (currently 12).
+    /// Independent of rusqlite_migration's PRAGMA user_version (currently 22).
     /// Hooks <= 7 are dead code for any user on 0.2.2+.
-    pub(super) const SCHEMA_VERSION: i32 = 12;
+    pub(super) const SCHEMA_VERSION: i32 = 22;
 
     /// Database migrations, tracked in `__rusqlite_migrations` table.
     /// M1–M11: shipped in 0.2.2 (PRAGMA user_version = 11).
     /// M12: consolidated 0.2.2 → 0.3.0 upgrade.
+    /// M13: columnar activity stream store.
+    /// M14: persisted power / pace curves.
+    /// M15: user route merge / split overrides.
+    /// M16: route shape classification.
+    /// M17: section checkpoints.
+    /// M18: section / route climb profiles.
+    /// M19: per-traversal section effort metrics.
+    /// M20: critical power model history.
+    /// M21: stream best efforts.
+    /// M22: local time-in-zone.
     pub(super) fn migrations() -> Migrations<'static> {
         Migrations::new(vec![
             M::up(include_str!("../migrations/001_initial_schema.sql")),
@@ -38,6 +48,16 @@ impl PersistentRouteEngine {
             )),
             M::up(include_str!("../migrations/011_pace_history.sql")),
             M::up(include_str!("../migrations/012_v030.sql")),
+            M::up(include_str!("../migrations/013_activity_streams.sql")),
+            M::up(include_str!("../migrations/014_fitness_curves.sql")),
+            M::up(include_str!("../migrations/015_route_overrides.sql")),
+            M::up(include_str!("../migrations/016_route_shapes.sql")),
+            M::up(include_str!("../migrations/017_section_checkpoints.sql")),
+            M::up(include_str!("../migrations/018_climb_profiles.sql")),
+            M::up(include_str!("../migrations/019_section_effort_metrics.sql")),
+            M::up(include_str!("../migrations/020_cp_history.sql")),
+            M::up(include_str!("../migrations/021_best_efforts.sql")),
+            M::up(include_str!("../migrations/022_zone_times.sql")),
         ])
     }
 
@@ -0,0 +1,466 @@
+//! Section checkpoints and per-effort split times.
+//!
+//! Checkpoints are intermediate points on a section polyline, stored in
+//! section order as coordinates (`section_checkpoints`). They are placed by
+//! the user as polyline indices, or generated evenly by distance or by
+//! climbed elevation. Each traversal in `section_activities` is cut at the
+//! GPS points nearest the checkpoints, and the time stream gives the split
+//! times. Splits are always reported in section order, so split `k` is the
+//! same stretch of road for forward and reverse efforts; best splits and the
+//! sum of best are kept per direction since the two aren't comparable.
+
+use rusqlite::params;
+
+use crate::{FfiEffortSplits, FfiSectionCheckpoint, FfiSectionSplitTable, FfiSplitBests, GpsPoint};
+
+use super::super::PersistentRouteEngine;
+use super::haversine_distance;
+
+/// A traversal must pass within this distance of a checkpoint to be split
+/// there. Efforts that miss a checkpoint are left out of the table.
+const CHECKPOINT_MATCH_RADIUS_M: f64 = 50.0;
+
+fn point_distance(a: &GpsPoint, b: &GpsPoint) -> f64 {
+    haversine_distance(a.latitude, a.longitude, b.latitude, b.longitude)
+}
+
+/// Cumulative distance in meters at each point of `points`.
+fn cumulative_distances(points: &[GpsPoint]) -> Vec<f64> {
+    let mut distances = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    distances.push(0.0);
+    for pair in points.windows(2) {
+        total += point_distance(&pair[0], &pair[1]);
+        distances.push(total);
+    }
+    distances
+}
+
+/// Interior polyline indices closest to `count` evenly spaced fractions of
+/// `profile` (a non-decreasing cumulative measure). Duplicates collapse, so
+/// fewer indices come back on short polylines.
+fn even_indices(profile: &[f64], count: u32) -> Vec<u32> {
+    let total = profile.last().copied().unwrap_or(0.0);
+    if profile.len() < 3 || total <= 0.0 {
+        return Vec::new();
+    }
+    let mut indices: Vec<u32> = (1..=count)
+        .map(|k| {
+            let target = total * k as f64 / (count + 1) as f64;
+            let i = profile.partition_point(|&v| v < target);
+            let i = if i > 0 && target - profile[i - 1] < profile[i] - target {
+                i - 1
+            } else {
+                i
+            };
+            i.clamp(1, profile.len() - 2) as u32
+        })
+        .collect();
+    indices.dedup();
+    indices
+}
+
+/// Cumulative climbed elevation at each point. None if the polyline has no
+/// elevation data or never climbs.
+fn cumulative_climb(points: &[GpsPoint]) -> Option<Vec<f64>> {
+    let mut climb = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    let mut last: Option<f64> = None;
+    for p in points {
+        if let Some(ele) = p.elevation.filter(|e| e.is_finite()) {
+            if let Some(prev) = last {
+                total += (ele - prev).max(0.0);
+            }
+            last = Some(ele);
+        }
+        climb.push(total);
+    }
+    (total > 0.0).then_some(climb)
+}
+
+/// Index in `track[from..=to]` where the track passes a checkpoint: the
+/// closest point of the first run of points within the match radius. Using
+/// the first pass rather than the global nearest keeps switchbacks that come
+/// back near the checkpoint later from stealing the match.
+fn match_checkpoint(
+    track: &[GpsPoint],
+    from: usize,
+    to: usize,
+    checkpoint: &GpsPoint,
+) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (i, p) in track.iter().enumerate().take(to + 1).skip(from) {
+        let d = point_distance(p, checkpoint);
+        if d <= CHECKPOINT_MATCH_RADIUS_M {
+            if best.is_none_or(|(_, bd)| d < bd) {
+                best = Some((i, d));
+            }
+        } else if best.is_some() {
+            break;
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// Split times for one traversal, in section order. None if the traversal
+/// misses a checkpoint or the time stream doesn't cover it.
+fn traversal_splits(
+    track: &[GpsPoint],
+    times: &[u32],
+    start: usize,
+    end: usize,
+    reverse: bool,
+    checkpoints: &[GpsPoint],
+) -> Option<Vec<f64>> {
+    if start >= end || end >= track.len() || end >= times.len() {
+        return None;
+    }
+    // Boundaries in travel order: start, each checkpoint as passed, end
+    let mut boundaries = vec![start];
+    let ordered: Vec<&GpsPoint> = if reverse {
+        checkpoints.iter().rev().collect()
+    } else {
+        checkpoints.iter().collect()
+    };
+    for checkpoint in ordered {
+        let from = *boundaries.last().unwrap();
+        boundaries.push(match_checkpoint(track, from, end, checkpoint)?);
+    }
+    boundaries.push(end);
+
+    let mut splits: Vec<f64> = boundaries
+        .windows(2)
+        .map(|pair| times[pair[1]] as f64 - times[pair[0]] as f64)
+        .collect();
+    if splits.iter().any(|&t| t < 0.0) {
+        return None;
+    }
+    if reverse {
+        splits.reverse();
+    }
+    Some(splits)
+}
+
+/// Best time per split, who set it, and the sum of best, over efforts in
+/// one direction. None when there are no efforts.
+fn split_bests(efforts: &[&FfiEffortSplits]) -> Option<FfiSplitBests> {
+    let first = efforts.first()?;
+    let mut best_splits = first.split_times.clone();
+    let mut best_split_activity_ids = vec![first.activity_id.clone(); best_splits.len()];
+    let mut best_time = first.total_time;
+    let mut best_time_activity_id = first.activity_id.clone();
+    for effort in &efforts[1..] {
+        for (k, &t) in effort.split_times.iter().enumerate() {
+            if t < best_splits[k] {
+                best_splits[k] = t;
+                best_split_activity_ids[k] = effort.activity_id.clone();
+            }
+        }
+        if effort.total_time < best_time {
+            best_time = effort.total_time;
+            best_time_activity_id = effort.activity_id.clone();
+        }
+    }
+    Some(FfiSplitBests {
+        sum_of_best: best_splits.iter().sum(),
+        best_splits,
+        best_split_activity_ids,
+        best_time,
+        best_time_activity_id,
+        effort_count: efforts.len() as u32,
+    })
+}
+
+impl PersistentRouteEngine {
+    /// Checkpoints of a section in section order. Empty if none are set.
+    pub fn get_section_checkpoints(&self, section_id: &str) -> Vec<FfiSectionCheckpoint> {
+        let mut stmt = match self.db.prepare(
+            "SELECT position, latitude, longitude, distance_meters
+             FROM section_checkpoints WHERE section_id = ? ORDER BY position",
+        ) {
+            Ok(s) => s,
+            Err(_) => return Vec::new(),
+        };
+        stmt.query_map(params![section_id], |row| {
+            Ok(FfiSectionCheckpoint {
+                position: row.get(0)?,
+                latitude: row.get(1)?,
+                longitude: row.get(2)?,
+                distance_meters: row.get(3)?,
+            })
+        })
+        .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        .unwrap_or_default()
+    }
+
+    /// Replace a section's checkpoints with the given polyline indices.
+    /// Indices are sorted and deduplicated; an empty list clears them.
+    pub fn set_section_checkpoints(
+        &mut self,
+        section_id: &str,
+        polyline_indices: &[u32],
+    ) -> Result<Vec<FfiSectionCheckpoint>, String> {
+        let section = self
+            .get_section(section_id)
+            .ok_or_else(|| format!("Section not found: {}", section_id))?;
+        let polyline = &section.polyline;
+
+        let mut indices = polyline_indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if let Some(&bad) = indices
+            .iter()
+            .find(|&&i| i == 0 || i as usize + 1 >= polyline.len())
+        {
+            return Err(format!(
+                "Checkpoint index {} is not inside the section polyline ({} points)",
+                bad,
+                polyline.len()
+            ));
+        }
+
+        let distances = cumulative_distances(polyline);
+        let checkpoints: Vec<FfiSectionCheckpoint> = indices
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| FfiSectionCheckpoint {
+                position: position as u32,
+                latitude: polyline[i as usize].latitude,
+                longitude: polyline[i as usize].longitude,
+                distance_meters: distances[i as usize],
+            })
+            .collect();
+
+        let tx = self
+            .db
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        tx.execute(
+            "DELETE FROM section_checkpoints WHERE section_id = ?",
+            params![section_id],
+        )
+        .map_err(|e| format!("Failed to clear checkpoints: {}", e))?;
+        for c in &checkpoints {
+            tx.execute(
+                "INSERT INTO section_checkpoints (section_id, position, latitude, longitude, distance_meters)
+                 VALUES (?, ?, ?, ?, ?)",
+                params![section_id, c.position, c.latitude, c.longitude, c.distance_meters],
+            )
+            .map_err(|e| format!("Failed to save checkpoint: {}", e))?;
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to save checkpoints: {}", e))?;
+
+        log::info!(
+            "tracematch: [Checkpoints] Set {} checkpoints on section {}",
+            checkpoints.len(),
+            section_id
+        );
+        Ok(checkpoints)
+    }
+
+    /// Generate `count` checkpoints spaced evenly along the section, by
+    /// `mode` "distance" or "elevation" (equal shares of the total climb,
+    /// with elevation filled from the representative activity where the
+    /// polyline has none). Replaces any existing checkpoints.
+    pub fn generate_section_checkpoints(
+        &mut self,
+        section_id: &str,
+        count: u32,
+        mode: &str,
+    ) -> Result<Vec<FfiSectionCheckpoint>, String> {
+        let section = self
+            .get_section(section_id)
+            .ok_or_else(|| format!("Section not found: {}", section_id))?;
+        let profile = match mode {
+            "distance" => cumulative_distances(&section.polyline),
+            "elevation" => {
+                let points = self.with_elevation(
+                    section.polyline,
+                    section.representative_activity_id.as_deref(),
+                );
+                cumulative_climb(&points)
+                    .ok_or_else(|| "Section has no elevation gain to split by".to_string())?
+            }
+            _ => return Err(format!("Unknown checkpoint mode: {}", mode)),
+        };
+        let indices = even_indices(&profile, count);
+        self.set_section_checkpoints(section_id, &indices)
+    }
+
+    /// Split times for every traversal of a section, with per-direction best
+    /// splits and sum of best. `sport_type` filters efforts like
+    /// `get_section_performances_filtered`. None if the section doesn't exist.
+    pub fn get_section_split_table(
+        &mut self,
+        section_id: &str,
+        sport_type: Option<&str>,
+    ) -> Option<FfiSectionSplitTable> {
+        self.get_section(section_id)?;
+        let checkpoints = self.get_section_checkpoints(section_id);
+        let checkpoint_points: Vec<GpsPoint> = checkpoints
+            .iter()
+            .map(|c| GpsPoint::new(c.latitude, c.longitude))
+            .collect();
+
+        struct Traversal {
+            activity_id: String,
+            activity_name: Option<String>,
+            activity_date: Option<i64>,
+            direction: String,
+            start_index: u32,
+            end_index: u32,
+        }
+        let traversals: Vec<Traversal> = {
+            let mut stmt = self
+                .db
+                .prepare(
+                    "SELECT sa.activity_id, am.name, am.date, sa.direction, sa.start_index, sa.end_index
+                     FROM section_activities sa
+                     LEFT JOIN activity_metrics am ON sa.activity_id = am.activity_id
+                     WHERE sa.section_id = ?1 AND sa.excluded = 0
+                       AND (?2 IS NULL OR am.sport_type = ?2)
+                     ORDER BY am.date, sa.start_index",
+                )
+                .ok()?;
+            stmt.query_map(params![section_id, sport_type], |row| {
+                Ok(Traversal {
+                    activity_id: row.get(0)?,
+                    activity_name: row.get(1)?,
+                    activity_date: row.get(2)?,
+                    direction: row.get(3)?,
+                    start_index: row.get(4)?,
+                    end_index: row.get(5)?,
+                })
+            })
+            .ok()?
+            .filter_map(|r| r.ok())
+            .collect()
+        };
+
+        let mut efforts = Vec::with_capacity(traversals.len());
+        let mut track: Option<(String, Vec<GpsPoint>)> = None;
+        for t in traversals {
+            if track.as_ref().is_none_or(|(id, _)| *id != t.activity_id) {
+                track = self
+                    .get_gps_track(&t.activity_id)
+                    .map(|points| (t.activity_id.clone(), points));
+            }
+            let Some((_, points)) = track.as_ref() else {
+                continue;
+            };
+            if !self.ensure_time_stream_loaded(&t.activity_id) {
+                continue;
+            }
+            let Some(times) = self.time_streams.peek(&t.activity_id) else {
+                continue;
+            };
+            let reverse = matches!(t.direction.as_str(), "reverse" | "backward");
+            let (start, end) = (
+                t.start_index.min(t.end_index) as usize,
+                t.start_index.max(t.end_index) as usize,
+            );
+            let Some(split_times) =
+                traversal_splits(points, times, start, end, reverse, &checkpoint_points)
+            else {
+                continue;
+            };
+            efforts.push(FfiEffortSplits {
+                total_time: split_times.iter().sum(),
+                activity_id: t.activity_id,
+                activity_name: t.activity_name.unwrap_or_default(),
+                activity_date: t.activity_date.unwrap_or(0),
+                direction: if reverse { "reverse" } else { "same" }.to_string(),
+                split_times,
+            });
+        }
+
+        let (reverse, forward): (Vec<&FfiEffortSplits>, Vec<&FfiEffortSplits>) =
+            efforts.iter().partition(|e| e.direction == "reverse");
+        Some(FfiSectionSplitTable {
+            section_id: section_id.to_string(),
+            forward: split_bests(&forward),
+            reverse: split_bests(&reverse),
+            checkpoints,
+            efforts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Straight track north from 47°N, ~100 m per point.
+    fn track(points: usize) -> Vec<GpsPoint> {
+        (0..points)
+            .map(|i| GpsPoint::new(47.0 + i as f64 * 0.000_899_3, 7.0))
+            .collect()
+    }
+
+    #[test]
+    fn splits_follow_section_order_in_both_directions() {
+        let forward = track(11);
+        let times = [0, 10, 20, 30, 45, 60, 75, 90, 95, 100, 105];
+        let checkpoints = [forward[3], forward[7]];
+
+        let splits = traversal_splits(&forward, &times, 0, 10, false, &checkpoints).unwrap();
+        assert_eq!(splits, vec![30.0, 60.0, 15.0]);
+
+        // Same road ridden south: split 0 is still the southern stretch
+        let mut backward = forward.clone();
+        backward.reverse();
+        let splits = traversal_splits(&backward, &times, 0, 10, true, &checkpoints).unwrap();
+        assert_eq!(
+            splits,
+            vec![30.0, 60.0, 15.0].into_iter().rev().collect::<Vec<_>>()
+        );
+
+        // A checkpoint off the road can't be matched
+        let off_road = [GpsPoint::new(47.003, 7.01)];
+        assert!(traversal_splits(&forward, &times, 0, 10, false, &off_road).is_none());
+    }
+
+    #[test]
+    fn sum_of_best_combines_splits_across_efforts() {
+        let effort = |id: &str, split_times: Vec<f64>| FfiEffortSplits {
+            activity_id: id.to_string(),
+            activity_name: String::new(),
+            activity_date: 0,
+            direction: "same".to_string(),
+            total_time: split_times.iter().sum(),
+            split_times,
+        };
+        let a = effort("a", vec![30.0, 60.0, 15.0]);
+        let b = effort("b", vec![35.0, 50.0, 16.0]);
+        let bests = split_bests(&[&a, &b]).unwrap();
+        assert_eq!(bests.best_splits, vec![30.0, 50.0, 15.0]);
+        assert_eq!(bests.best_split_activity_ids, vec!["a", "b", "a"]);
+        assert_eq!(bests.sum_of_best, 95.0);
+        assert_eq!(
+            (bests.best_time, bests.best_time_activity_id.as_str()),
+            (101.0, "b")
+        );
+        assert!(split_bests(&[]).is_none());
+    }
+
+    #[test]
+    fn checkpoints_generated_by_distance_and_climb() {
+        let mut points = track(11);
+        assert_eq!(
+            even_indices(&cumulative_distances(&points), 4),
+            vec![2, 4, 6, 8]
+        );
+
+        // Flat for the first half, then 10 m per point: the climb splits the
+        // second half only
+        for (i, p) in points.iter_mut().enumerate() {
+            p.elevation = Some(500.0 + 10.0 * i.saturating_sub(5) as f64);
+        }
+        let climb = cumulative_climb(&points).unwrap();
+        assert_eq!(even_indices(&climb, 4), vec![6, 7, 8, 9]);
+
+        let flat: Vec<GpsPoint> = track(5);
+        assert!(cumulative_climb(&flat).is_none());
+    }
+}
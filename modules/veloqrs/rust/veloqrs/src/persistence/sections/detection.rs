@@ -1,6 +1,9 @@
 //! Background section detection and application.
 
+use crate::persistence::climbs::{drop_changed_route_profiles, snapshot_group_memberships};
 use crate::persistence::codec;
+use crate::persistence::route_overrides::{apply_route_overrides, load_route_overrides};
+use crate::persistence::route_shape::{restore_route_shapes, snapshot_route_shapes};
 use crate::{FrequentSection, GpsPoint};
 use rusqlite::{Connection, Result as SqlResult, params};
 use std::collections::{HashMap, HashSet};
@@ -105,6 +108,18 @@ fn recompute_and_save_groups(
 
     let mut groups = result.groups;
 
+    // User merges / splits override the algorithm's grouping
+    match load_route_overrides(conn) {
+        Ok(pins) => {
+            let bounds: HashMap<&str, Bounds> = signatures
+                .iter()
+                .map(|s| (s.activity_id.as_str(), s.bounds))
+                .collect();
+            groups = apply_route_overrides(groups, &pins, |id| bounds.get(id).copied());
+        }
+        Err(e) => log::warn!("[BG Groups] Failed to load route overrides: {}", e),
+    }
+
     for group in &mut groups {
         if let Some(sport) = activity_metadata.get(&group.representative_id) {
             group.sport_type = if sport.is_empty() {
@@ -133,6 +148,8 @@ fn recompute_and_save_groups(
 
 /// Save route groups to DB (standalone, no engine needed).
 fn save_groups_to_db(conn: &Connection, groups: &[RouteGroup]) -> SqlResult<()> {
+    let shapes = snapshot_route_shapes(conn)?;
+    let memberships = snapshot_group_memberships(conn)?;
     conn.execute("DELETE FROM route_groups", [])?;
     let mut stmt = conn.prepare(
         "INSERT INTO route_groups (id, representative_id, activity_ids, sport_type,
@@ -164,6 +181,8 @@ fn save_groups_to_db(conn: &Connection, groups: &[RouteGroup]) -> SqlResult<()>
             activity_ids_blob,
         ])?;
     }
+    restore_route_shapes(conn, &shapes)?;
+    drop_changed_route_profiles(conn, &memberships)?;
     Ok(())
 }
 
@@ -1121,6 +1140,18 @@ impl PersistentRouteEngine {
                 e
             );
         }
+        if let Err(e) = self.update_section_climb_profiles() {
+            log::warn!(
+                "tracematch: [apply_sections_finalize] Climb profile update failed: {}",
+                e
+            );
+        }
+        if let Err(e) = self.update_section_effort_metrics(None) {
+            log::warn!(
+                "tracematch: [apply_sections_finalize] Effort metrics update failed: {}",
+                e
+            );
+        }
         if let Some(p) = progress {
             p.increment();
             p.set_phase("recomputing_indicators", 1);
@@ -0,0 +1,228 @@
+//! Ghost comparison: time gained or lost between two efforts on a section.
+//!
+//! Each effort is a section portion (activity + GPS index range). Distance
+//! along the portion comes from the GPS track and elapsed time from the
+//! stored time stream. Both efforts are stretched to the ghost's length, so
+//! small differences in recorded distance don't show up as a gap at the
+//! finish, then sampled at fixed distance steps.
+
+use crate::{FfiGhostComparison, FfiGhostSplit, FfiSectionPortion, GpsPoint};
+
+use super::super::PersistentRouteEngine;
+use super::haversine_distance;
+
+/// The section is cut into this many equal splits for the gain/loss summary.
+const GHOST_SPLITS: usize = 10;
+/// Sample step (metres) used when the caller's step is not a number.
+const DEFAULT_STEP_M: f64 = 10.0;
+
+/// Cumulative distance and elapsed time at each point of one effort.
+struct EffortProfile {
+    distances: Vec<f64>,
+    times: Vec<f64>,
+}
+
+impl EffortProfile {
+    /// Slice one portion out of a track and its time stream. None if the
+    /// indices fall outside either, or the portion has no length or duration.
+    fn new(track: &[GpsPoint], times: &[u32], start: usize, end: usize) -> Option<Self> {
+        if start >= end || end >= track.len() || end >= times.len() {
+            return None;
+        }
+        let mut distances = Vec::with_capacity(end - start + 1);
+        let mut total = 0.0;
+        distances.push(0.0);
+        for pair in track[start..=end].windows(2) {
+            total += haversine_distance(
+                pair[0].latitude,
+                pair[0].longitude,
+                pair[1].latitude,
+                pair[1].longitude,
+            );
+            distances.push(total);
+        }
+        let t0 = times[start] as f64;
+        let times: Vec<f64> = times[start..=end].iter().map(|&t| t as f64 - t0).collect();
+        if total <= 0.0 || times.last().copied().unwrap_or(0.0) <= 0.0 {
+            return None;
+        }
+        Some(Self { distances, times })
+    }
+
+    fn length(&self) -> f64 {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Elapsed time at `fraction` (0.0-1.0) of the effort's distance.
+    fn time_at(&self, fraction: f64) -> f64 {
+        let target = fraction.clamp(0.0, 1.0) * self.length();
+        let i = self.distances.partition_point(|&d| d < target);
+        if i == 0 {
+            return self.times[0];
+        }
+        if i >= self.distances.len() {
+            return self.times[self.times.len() - 1];
+        }
+        let (d0, d1) = (self.distances[i - 1], self.distances[i]);
+        let t = if d1 > d0 {
+            (target - d0) / (d1 - d0)
+        } else {
+            0.0
+        };
+        self.times[i - 1] + (self.times[i] - self.times[i - 1]) * t
+    }
+}
+
+/// Sample the gap between `effort` and `ghost` every `step` metres of the
+/// ghost's distance, at least 1 m. Positive gap = effort behind the ghost.
+fn compare_profiles(
+    effort: &EffortProfile,
+    ghost: &EffortProfile,
+    step: f64,
+) -> FfiGhostComparison {
+    let step = if step.is_finite() {
+        step.max(1.0)
+    } else {
+        DEFAULT_STEP_M
+    };
+    let length = ghost.length();
+    let steps = ((length / step).ceil() as usize).max(1);
+
+    let mut distances = Vec::with_capacity(steps + 1);
+    let mut gaps = Vec::with_capacity(steps + 1);
+    for k in 0..=steps {
+        let d = (k as f64 * step).min(length);
+        let fraction = d / length;
+        distances.push(d);
+        gaps.push(effort.time_at(fraction) - ghost.time_at(fraction));
+    }
+
+    let splits: Vec<FfiGhostSplit> = (0..GHOST_SPLITS)
+        .map(|s| {
+            let (f0, f1) = (
+                s as f64 / GHOST_SPLITS as f64,
+                (s + 1) as f64 / GHOST_SPLITS as f64,
+            );
+            let effort_time = effort.time_at(f1) - effort.time_at(f0);
+            let ghost_time = ghost.time_at(f1) - ghost.time_at(f0);
+            FfiGhostSplit {
+                start_distance: f0 * length,
+                end_distance: f1 * length,
+                effort_time,
+                ghost_time,
+                time_delta: effort_time - ghost_time,
+            }
+        })
+        .collect();
+
+    // Most negative delta = most time won, most positive = most time lost
+    let best_split = splits
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.time_delta < 0.0)
+        .min_by(|a, b| a.1.time_delta.total_cmp(&b.1.time_delta))
+        .map(|(i, _)| i as u32);
+    let worst_split = splits
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.time_delta > 0.0)
+        .max_by(|a, b| a.1.time_delta.total_cmp(&b.1.time_delta))
+        .map(|(i, _)| i as u32);
+
+    FfiGhostComparison {
+        distances,
+        gaps,
+        effort_time: effort.time_at(1.0),
+        ghost_time: ghost.time_at(1.0),
+        splits,
+        best_split,
+        worst_split,
+    }
+}
+
+impl PersistentRouteEngine {
+    /// Compare two section efforts at fixed `step_meters` intervals. The
+    /// ghost is the reference (e.g. the PR). Returns None if either effort
+    /// lacks a GPS track or time stream covering its portion.
+    pub fn compare_section_efforts(
+        &mut self,
+        effort: &FfiSectionPortion,
+        ghost: &FfiSectionPortion,
+        step_meters: f64,
+    ) -> Option<FfiGhostComparison> {
+        let effort_profile = self.effort_profile(effort)?;
+        let ghost_profile = self.effort_profile(ghost)?;
+        Some(compare_profiles(
+            &effort_profile,
+            &ghost_profile,
+            step_meters,
+        ))
+    }
+
+    fn effort_profile(&mut self, portion: &FfiSectionPortion) -> Option<EffortProfile> {
+        let track = self.get_gps_track(&portion.activity_id)?;
+        if !self.ensure_time_stream_loaded(&portion.activity_id) {
+            return None;
+        }
+        let times = self.time_streams.get(&portion.activity_id)?.clone();
+        let (start, end) = (portion.start_index as usize, portion.end_index as usize);
+        EffortProfile::new(&track, &times, start.min(end), start.max(end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Straight 1 km effort, 11 points ~100 m apart, with the given cumulative times.
+    fn profile(times: &[u32]) -> EffortProfile {
+        let track: Vec<GpsPoint> = (0..11)
+            .map(|i| GpsPoint::new(47.0 + i as f64 * 0.000_899_3, 7.0))
+            .collect();
+        EffortProfile::new(&track, times, 0, 10).unwrap()
+    }
+
+    #[test]
+    fn gap_series_tracks_time_won_and_lost() {
+        // Ghost: steady 10 s per 100 m. Effort: slow start, fast finish.
+        let ghost = profile(&[0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+        let effort = profile(&[0, 15, 30, 45, 60, 70, 80, 85, 90, 95, 100]);
+
+        let cmp = compare_profiles(&effort, &ghost, 100.0);
+        assert_eq!(cmp.distances.len(), cmp.gaps.len());
+        assert_eq!(cmp.distances.len(), 11);
+        assert!(cmp.gaps[0].abs() < 1e-9);
+        // 20 s down after 400 m, level at the line
+        assert!((cmp.gaps[4] - 20.0).abs() < 0.5);
+        assert!(cmp.gaps[10].abs() < 1e-9);
+        assert_eq!((cmp.effort_time, cmp.ghost_time), (100.0, 100.0));
+
+        assert_eq!(cmp.splits.len(), GHOST_SPLITS);
+        let worst = cmp.worst_split.unwrap() as usize;
+        let best = cmp.best_split.unwrap() as usize;
+        assert!(worst < 4, "time lost in the first 400 m");
+        assert!(best >= 6, "time won in the last 400 m");
+        assert!((cmp.splits[best].time_delta + 5.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn out_of_range_steps_still_reach_the_finish() {
+        let ghost = profile(&[0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+        let length = ghost.length();
+        for step in [0.25, -50.0, f64::NAN] {
+            let cmp = compare_profiles(&ghost, &ghost, step);
+            assert!(cmp.distances.iter().all(|d| *d >= 0.0));
+            assert_eq!(*cmp.distances.last().unwrap(), length);
+        }
+    }
+
+    #[test]
+    fn invalid_portions_are_rejected() {
+        let track: Vec<GpsPoint> = (0..5)
+            .map(|i| GpsPoint::new(47.0 + i as f64 * 0.001, 7.0))
+            .collect();
+        assert!(EffortProfile::new(&track, &[0, 1, 2], 0, 4).is_none());
+        assert!(EffortProfile::new(&track, &[0, 1, 2, 3, 4], 3, 3).is_none());
+        assert!(EffortProfile::new(&track, &[5, 5, 5, 5, 5], 0, 4).is_none());
+    }
+}
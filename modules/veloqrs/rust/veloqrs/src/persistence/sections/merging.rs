@@ -183,6 +183,10 @@ impl PersistentRouteEngine {
                 )?;
                 // Delete secondary section
                 tx.execute("DELETE FROM sections WHERE id = ?", params![secondary_id])?;
+                tx.execute(
+                    "DELETE FROM section_checkpoints WHERE section_id = ?",
+                    params![secondary_id],
+                )?;
 
                 log::info!(
                     "tracematch: [merge_cross_sport] Merged {} ({}) into {} ({})",
@@ -435,6 +439,10 @@ impl PersistentRouteEngine {
             "DELETE FROM sections WHERE id = ?",
             rusqlite::params![secondary_id],
         )?;
+        tx.execute(
+            "DELETE FROM section_checkpoints WHERE section_id = ?",
+            rusqlite::params![secondary_id],
+        )?;
 
         tx.commit()?;
 
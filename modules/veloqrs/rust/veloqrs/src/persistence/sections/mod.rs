@@ -1,6 +1,8 @@
 //! Section management: loading, queries, detection, save/apply, names.
 
+mod checkpoints;
 mod detection;
+mod ghost;
 mod merging;
 mod naming;
 mod ranking;
@@ -682,8 +684,11 @@ impl PersistentRouteEngine {
             "SELECT id, name, sport_type, distance_meters, confidence, scale,
                     bounds_min_lat, bounds_max_lat, bounds_min_lng, bounds_max_lng,
                     section_type, representative_activity_id, created_at,
-                    is_user_defined, disabled, superseded_by
+                    is_user_defined, disabled, superseded_by,
+                    cp.total_ascent, cp.avg_gradient, cp.max_gradient, cp.climb_category
              FROM sections
+             LEFT JOIN climb_profiles cp
+                    ON cp.target_type = 'section' AND cp.target_id = sections.id
              WHERE disabled = 0 AND superseded_by IS NULL",
         ) {
             Ok(s) => s,
@@ -740,6 +745,10 @@ impl PersistentRouteEngine {
                     is_user_defined: row.get::<_, Option<i32>>(13)?.unwrap_or(0) != 0,
                     disabled: row.get::<_, Option<i32>>(14)?.unwrap_or(0) != 0,
                     superseded_by: row.get(15)?,
+                    total_ascent: row.get(16)?,
+                    avg_gradient: row.get(17)?,
+                    max_gradient: row.get(18)?,
+                    climb_category: row.get(19)?,
                 })
             })
             .ok()
@@ -1172,6 +1181,10 @@ impl PersistentRouteEngine {
             "DELETE FROM sections WHERE section_type = 'auto' AND original_polyline_json IS NULL AND is_user_defined = 0",
             [],
         )?;
+        tx.execute(
+            "DELETE FROM section_checkpoints WHERE section_id NOT IN (SELECT id FROM sections)",
+            [],
+        )?;
 
         // Load bounding boxes of accepted sections to dedup new auto detections
         struct AcceptedBounds {
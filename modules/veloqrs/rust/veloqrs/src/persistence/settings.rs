@@ -25,6 +25,30 @@ pub mod settings_keys {
     pub const SECTION_MIN_ACTIVITIES: &str = "__section_min_activities";
     /// SectionConfig.detection_method (string: "corridor", "density_grid", "flow_graph").
     pub const SECTION_DETECTION_METHOD: &str = "__section_detection_method";
+
+    /// Newest local date (YYYY-MM-DD) the activity sync has fully processed.
+    /// The next `sync_now` resumes from this day.
+    pub const SYNC_ACTIVITIES_WATERMARK: &str = "__sync_activities_watermark";
+    /// Days before the watermark each activity sync re-lists to catch late
+    /// uploads (u32 stored as decimal string).
+    pub const SYNC_ACTIVITIES_OVERLAP_DAYS: &str = "__sync_activities_overlap_days";
+    /// Trailing days each sync checks for activities deleted on the server,
+    /// whatever the watermark (u32 stored as decimal string; 0 turns it off).
+    pub const SYNC_RECONCILE_DAYS: &str = "__sync_reconcile_days";
+    /// Days of already-stored wellness the sync re-fetches to catch late
+    /// edits (u32 stored as decimal string).
+    pub const SYNC_WELLNESS_OVERLAP_DAYS: &str = "__sync_wellness_overlap_days";
+    /// Oldest local date (YYYY-MM-DD) the history backfill has covered. The
+    /// next backfill month ends the day before it.
+    pub const SYNC_BACKFILL_CURSOR: &str = "__sync_backfill_cursor";
+    /// The athlete's oldest activity date, cached so a resumed backfill does
+    /// not re-pull the full history index.
+    pub const SYNC_BACKFILL_OLDEST: &str = "__sync_backfill_oldest";
+
+    /// Best-effort distance targets in metres (comma-separated f64s).
+    pub const BEST_EFFORT_DISTANCES: &str = "__best_effort_distances";
+    /// Best-effort duration targets in seconds (comma-separated u32s).
+    pub const BEST_EFFORT_DURATIONS: &str = "__best_effort_durations";
 }
 
 impl PersistentRouteEngine {
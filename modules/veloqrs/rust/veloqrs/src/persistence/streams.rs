@@ -0,0 +1,303 @@
+//! Activity streams: full per-sample series (HR, power, altitude, ...).
+//!
+//! Each stream type is stored as its own row, delta-varint encoded with
+//! `coords::encode_series` at a fixed-point scale chosen per type. Storing
+//! columns separately keeps single-series reads (e.g. just HR for zone
+//! times) from decoding the whole activity, and lets sparse sensors cost
+//! nothing when absent. GPS stays in `gps_tracks`.
+
+use rusqlite::{Result as SqlResult, params};
+
+use super::PersistentRouteEngine;
+use crate::coords;
+use crate::net::types::ParsedStreams;
+
+/// Fixed-point scale per stream type. Values are rounded to `1 / scale`
+/// before encoding, so these set the stored resolution.
+const STREAM_SCALES: &[(&str, f64)] = &[
+    ("time", 1.0),
+    ("heartrate", 1.0),
+    ("watts", 1.0),
+    ("cadence", 10.0),
+    ("altitude", 10.0),
+    ("velocity_smooth", 1000.0),
+    ("distance", 10.0),
+    ("grade_smooth", 10.0),
+    ("temp", 10.0),
+    ("w_bal", 1.0),
+    ("gap", 1000.0),
+];
+
+/// Stream type of the placeholder row written when a download carried no
+/// series, so sync can tell "fetched, empty" from "never fetched" and does
+/// not download the activity again. Readers skip it.
+pub(crate) const EMPTY_STREAMS_MARKER: &str = "_empty";
+
+/// Scale for a stream type, or `None` if the store does not know it.
+fn stream_scale(stream_type: &str) -> Option<f64> {
+    STREAM_SCALES
+        .iter()
+        .find(|(name, _)| *name == stream_type)
+        .map(|(_, scale)| *scale)
+}
+
+/// Borrow the series for `stream_type` out of a parsed streams bundle.
+/// `time` is converted since it is the only integer series.
+fn series_of(streams: &ParsedStreams, stream_type: &str) -> Vec<f64> {
+    let s: &[f64] = match stream_type {
+        "time" => return streams.time.iter().map(|&t| t as f64).collect(),
+        "heartrate" => &streams.heartrate,
+        "watts" => &streams.watts,
+        "cadence" => &streams.cadence,
+        "altitude" => &streams.altitude,
+        "velocity_smooth" => &streams.velocity_smooth,
+        "distance" => &streams.distance,
+        "grade_smooth" => &streams.grade_smooth,
+        "temp" => &streams.temp,
+        "w_bal" => &streams.wbal,
+        "gap" => &streams.gap,
+        _ => &[],
+    };
+    s.to_vec()
+}
+
+impl PersistentRouteEngine {
+    /// Persist every non-empty series in `streams` for one activity,
+    /// replacing whatever was stored before, queue the activity's section
+    /// traversals for a fresh effort-metrics and time-in-zone pass and
+    /// rescan its best efforts. Returns the number of series written. Latlng
+    /// is ignored (tracks live in `gps_tracks`). With nothing to write, an
+    /// `EMPTY_STREAMS_MARKER` row records that the activity was fetched.
+    pub fn store_activity_streams(
+        &mut self,
+        activity_id: &str,
+        streams: &ParsedStreams,
+    ) -> SqlResult<u32> {
+        let tx = self.db.transaction()?;
+        tx.execute(
+            "DELETE FROM activity_streams WHERE activity_id = ?",
+            params![activity_id],
+        )?;
+        let mut written = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO activity_streams (activity_id, stream_type, scale, point_count, data)
+                 VALUES (?, ?, ?, ?, ?)",
+            )?;
+            for &(stream_type, scale) in STREAM_SCALES {
+                let values = series_of(streams, stream_type);
+                if values.is_empty() {
+                    continue;
+                }
+                let data = coords::encode_series(&values, scale);
+                stmt.execute(params![
+                    activity_id,
+                    stream_type,
+                    scale,
+                    values.len() as i64,
+                    data
+                ])?;
+                written += 1;
+            }
+            if written == 0 {
+                stmt.execute(params![
+                    activity_id,
+                    EMPTY_STREAMS_MARKER,
+                    1.0,
+                    0,
+                    Vec::<u8>::new()
+                ])?;
+            }
+        }
+        // Section effort metrics were computed from the old streams
+        tx.execute(
+            "UPDATE section_activities SET metrics_computed = 0 WHERE activity_id = ?",
+            params![activity_id],
+        )?;
+        tx.execute(
+            "DELETE FROM best_effort_scans WHERE activity_id = ?",
+            params![activity_id],
+        )?;
+        tx.execute(
+            "UPDATE activity_metrics SET zones_computed = 0 WHERE activity_id = ?",
+            params![activity_id],
+        )?;
+        tx.commit()?;
+        self.invalidate_perf_cache();
+        self.store_best_efforts(activity_id, streams)?;
+        Ok(written)
+    }
+
+    /// Decode a single stored series. Missing samples come back as NaN.
+    /// `None` when the activity has no series of that type.
+    pub fn get_activity_stream(
+        &self,
+        activity_id: &str,
+        stream_type: &str,
+    ) -> SqlResult<Option<Vec<f64>>> {
+        if stream_scale(stream_type).is_none() {
+            return Ok(None);
+        }
+        self.db
+            .query_row(
+                "SELECT scale, data FROM activity_streams
+                 WHERE activity_id = ? AND stream_type = ?",
+                params![activity_id, stream_type],
+                |row| {
+                    let scale: f64 = row.get(0)?;
+                    let data: Vec<u8> = row.get(1)?;
+                    Ok(coords::decode_series(&data, scale))
+                },
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                other => Err(other),
+            })
+    }
+
+    /// Decode all stored series for an activity into the app-facing shape.
+    /// `None` when nothing is stored. `latlng` is always empty.
+    pub fn get_activity_streams(&self, activity_id: &str) -> SqlResult<Option<ParsedStreams>> {
+        let mut stmt = self.db.prepare(
+            "SELECT stream_type, scale, data FROM activity_streams
+             WHERE activity_id = ? AND stream_type != ?",
+        )?;
+        let rows = stmt.query_map(params![activity_id, EMPTY_STREAMS_MARKER], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, f64>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+            ))
+        })?;
+
+        let mut out = ParsedStreams::default();
+        let mut found = false;
+        for row in rows {
+            let (stream_type, scale, data) = row?;
+            let values = coords::decode_series(&data, scale);
+            found = true;
+            match stream_type.as_str() {
+                "time" => out.time = values.iter().map(|&t| t as i64).collect(),
+                "heartrate" => out.heartrate = values,
+                "watts" => out.watts = values,
+                "cadence" => out.cadence = values,
+                "altitude" => out.altitude = values,
+                "velocity_smooth" => out.velocity_smooth = values,
+                "distance" => out.distance = values,
+                "grade_smooth" => out.grade_smooth = values,
+                "temp" => out.temp = values,
+                "w_bal" => out.wbal = values,
+                "gap" => out.gap = values,
+                _ => {}
+            }
+        }
+        Ok(found.then_some(out))
+    }
+
+    /// Whether any series is stored for the activity.
+    pub fn has_activity_streams(&self, activity_id: &str) -> SqlResult<bool> {
+        self.db.query_row(
+            "SELECT EXISTS(SELECT 1 FROM activity_streams
+                           WHERE activity_id = ? AND stream_type != ?)",
+            params![activity_id, EMPTY_STREAMS_MARKER],
+            |row| row.get(0),
+        )
+    }
+
+    /// Whether streams were downloaded for the activity, including a
+    /// download that came back without any series.
+    pub fn has_fetched_streams(&self, activity_id: &str) -> SqlResult<bool> {
+        self.db.query_row(
+            "SELECT EXISTS(SELECT 1 FROM activity_streams WHERE activity_id = ?)",
+            params![activity_id],
+            |row| row.get(0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_streams() -> ParsedStreams {
+        ParsedStreams {
+            time: vec![0, 1, 2, 3],
+            heartrate: vec![120.0, 121.0, f64::NAN, 124.0],
+            watts: vec![200.0, 210.0, 190.0, 0.0],
+            altitude: vec![412.3, 412.5, 412.4, 413.0],
+            velocity_smooth: vec![8.123, 8.2, 8.25, 8.3],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn streams_round_trip() {
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        let written = engine
+            .store_activity_streams("a1", &sample_streams())
+            .unwrap();
+        assert_eq!(written, 5);
+        assert!(engine.has_activity_streams("a1").unwrap());
+        assert!(!engine.has_activity_streams("a2").unwrap());
+
+        let got = engine.get_activity_streams("a1").unwrap().unwrap();
+        assert_eq!(got.time, vec![0, 1, 2, 3]);
+        assert_eq!(got.watts, vec![200.0, 210.0, 190.0, 0.0]);
+        assert!(got.heartrate[2].is_nan());
+        assert_eq!(got.heartrate[3], 124.0);
+        assert!((got.altitude[0] - 412.3).abs() < 1e-9);
+        assert!((got.velocity_smooth[0] - 8.123).abs() < 1e-9);
+        assert!(got.cadence.is_empty());
+
+        assert_eq!(engine.get_activity_streams("a2").unwrap(), None);
+    }
+
+    #[test]
+    fn single_stream_read_and_replace() {
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        engine
+            .store_activity_streams("a1", &sample_streams())
+            .unwrap();
+        assert_eq!(
+            engine.get_activity_stream("a1", "watts").unwrap(),
+            Some(vec![200.0, 210.0, 190.0, 0.0])
+        );
+        assert_eq!(engine.get_activity_stream("a1", "cadence").unwrap(), None);
+        assert_eq!(engine.get_activity_stream("a1", "bogus").unwrap(), None);
+
+        let replacement = ParsedStreams {
+            cadence: vec![85.5, 86.0],
+            ..Default::default()
+        };
+        engine.store_activity_streams("a1", &replacement).unwrap();
+        assert_eq!(engine.get_activity_stream("a1", "watts").unwrap(), None);
+        assert_eq!(
+            engine.get_activity_stream("a1", "cadence").unwrap(),
+            Some(vec![85.5, 86.0])
+        );
+    }
+
+    #[test]
+    fn empty_download_is_marked_fetched() {
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        let written = engine
+            .store_activity_streams("a1", &ParsedStreams::default())
+            .unwrap();
+        assert_eq!(written, 0);
+        assert!(engine.has_fetched_streams("a1").unwrap());
+        assert!(!engine.has_activity_streams("a1").unwrap());
+        assert_eq!(engine.get_activity_streams("a1").unwrap(), None);
+        assert!(!engine.has_fetched_streams("a2").unwrap());
+
+        // A later download with real series replaces the marker.
+        engine
+            .store_activity_streams("a1", &sample_streams())
+            .unwrap();
+        assert!(engine.has_activity_streams("a1").unwrap());
+        assert_eq!(
+            engine.get_activity_streams("a1").unwrap().unwrap().time,
+            vec![0, 1, 2, 3]
+        );
+    }
+}
@@ -86,6 +86,12 @@ impl PersistentRouteEngine {
         tx.commit()
     }
 
+    /// Newest stored wellness date (YYYY-MM-DD), or None before the first sync.
+    pub fn latest_wellness_date(&self) -> SqlResult<Option<String>> {
+        self.db
+            .query_row("SELECT MAX(date) FROM wellness", [], |r| r.get(0))
+    }
+
     /// Trailing N-day wellness rows, oldest first. `days` includes today.
     pub fn get_wellness_window(&self, days: u32) -> SqlResult<Vec<WellnessRow>> {
         let mut stmt = self.db.prepare(
@@ -251,4 +257,33 @@ mod tests {
         assert_eq!(finite(Some(0.0)), Some(0.0));
         assert_eq!(finite(None), None);
     }
+
+    #[test]
+    fn latest_wellness_date_is_max_stored() {
+        let mut engine = PersistentRouteEngine::in_memory().unwrap();
+        assert_eq!(engine.latest_wellness_date().unwrap(), None);
+        let row = |date: &str| WellnessRow {
+            date: date.to_string(),
+            ctl: Some(50.0),
+            atl: None,
+            ramp_rate: None,
+            hrv: None,
+            resting_hr: None,
+            weight: None,
+            sleep_secs: None,
+            sleep_score: None,
+            soreness: None,
+            fatigue: None,
+            stress: None,
+            mood: None,
+            motivation: None,
+        };
+        engine
+            .upsert_wellness(&[row("2026-06-02"), row("2026-06-05"), row("2026-06-01")])
+            .unwrap();
+        assert_eq!(
+            engine.latest_wellness_date().unwrap().as_deref(),
+            Some("2026-06-05")
+        );
+    }
 }
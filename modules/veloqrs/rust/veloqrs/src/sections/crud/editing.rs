@@ -213,6 +213,7 @@ impl PersistentRouteEngine {
         // Invalidate caches
         self.invalidate_section_cache(section_id);
         self.refresh_section_in_memory(section_id);
+        self.refresh_section_climb_profile(section_id);
 
         Ok(())
     }
@@ -294,6 +295,7 @@ impl PersistentRouteEngine {
         // Invalidate caches
         self.invalidate_section_cache(section_id);
         self.refresh_section_in_memory(section_id);
+        self.refresh_section_climb_profile(section_id);
 
         Ok(())
     }
@@ -404,6 +406,7 @@ impl PersistentRouteEngine {
         // Invalidate caches
         self.invalidate_section_cache(section_id);
         self.refresh_section_in_memory(section_id);
+        self.refresh_section_climb_profile(section_id);
 
         Ok(())
     }
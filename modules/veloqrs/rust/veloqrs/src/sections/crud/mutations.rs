@@ -119,6 +119,7 @@ impl PersistentRouteEngine {
         // This ensures custom sections show all traversals, including the source activity
         // with proper portion details (direction, indices, distance)
         let _ = self.match_activities_to_section(&id, &params.polyline, &params.sport_type);
+        self.refresh_section_climb_profile(&id);
 
         // Refresh the materialized activity_indicators table so feed cards
         // pick up section_pr / section_trend chips for the new section without
@@ -474,6 +475,7 @@ impl PersistentRouteEngine {
 
         // Refresh in-memory section (for auto sections)
         self.refresh_section_in_memory(section_id);
+        self.refresh_section_climb_profile(section_id);
 
         Ok(())
     }
@@ -705,6 +707,18 @@ impl PersistentRouteEngine {
         if rows == 0 {
             return Err(format!("Section not found: {}", section_id));
         }
+        self.db
+            .execute(
+                "DELETE FROM section_checkpoints WHERE section_id = ?",
+                params![section_id],
+            )
+            .map_err(|e| format!("Failed to delete section checkpoints: {}", e))?;
+        self.db
+            .execute(
+                "DELETE FROM climb_profiles WHERE target_type = 'section' AND target_id = ?",
+                params![section_id],
+            )
+            .map_err(|e| format!("Failed to delete section climb profile: {}", e))?;
 
         // Invalidate cache
         self.invalidate_section_cache(section_id);
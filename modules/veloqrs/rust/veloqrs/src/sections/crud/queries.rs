@@ -208,25 +208,31 @@ impl PersistentRouteEngine {
         let base_cols = "id, section_type, name, sport_type, distance_meters,
                          representative_activity_id, created_at, confidence, scale,
                          bounds_min_lat, bounds_max_lat, bounds_min_lng, bounds_max_lng,
-                         is_user_defined, disabled, superseded_by";
+                         is_user_defined, disabled, superseded_by,
+                         cp.total_ascent, cp.avg_gradient, cp.max_gradient, cp.climb_category";
+        let from = "sections LEFT JOIN climb_profiles cp
+                    ON cp.target_type = 'section' AND cp.target_id = sections.id";
         let query = match (section_type, visible_only) {
             (Some(st), true) => format!(
-                "SELECT {} FROM sections WHERE section_type = '{}' AND {}",
+                "SELECT {} FROM {} WHERE section_type = '{}' AND {}",
                 base_cols,
+                from,
                 st.as_str(),
                 Self::VISIBLE_FILTER
             ),
             (Some(st), false) => format!(
-                "SELECT {} FROM sections WHERE section_type = '{}'",
+                "SELECT {} FROM {} WHERE section_type = '{}'",
                 base_cols,
+                from,
                 st.as_str()
             ),
             (None, true) => format!(
-                "SELECT {} FROM sections WHERE {}",
+                "SELECT {} FROM {} WHERE {}",
                 base_cols,
+                from,
                 Self::VISIBLE_FILTER
             ),
-            (None, false) => format!("SELECT {} FROM sections", base_cols),
+            (None, false) => format!("SELECT {} FROM {}", base_cols, from),
         };
 
         let mut stmt = match self.db.prepare(&query) {
@@ -277,6 +283,10 @@ impl PersistentRouteEngine {
                 is_user_defined: row.get::<_, Option<i32>>(13)?.unwrap_or(0) != 0,
                 disabled: row.get::<_, Option<i32>>(14)?.unwrap_or(0) != 0,
                 superseded_by: row.get(15)?,
+                total_ascent: row.get(16)?,
+                avg_gradient: row.get(17)?,
+                max_gradient: row.get(18)?,
+                climb_category: row.get(19)?,
             })
         });
 
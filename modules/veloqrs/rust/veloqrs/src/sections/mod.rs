@@ -144,4 +144,11 @@ pub struct SectionSummary {
     pub disabled: bool,
     /// If superseded by a custom section, stores its ID.
     pub superseded_by: Option<String>,
+    /// Total ascent in meters, None until the climb profile is computed
+    pub total_ascent: Option<f64>,
+    /// Net and steepest gradient, percent
+    pub avg_gradient: Option<f64>,
+    pub max_gradient: Option<f64>,
+    /// "cat4", "cat3", "cat2", "cat1" or "hc"; None if not a categorised climb
+    pub climb_category: Option<String>,
 }
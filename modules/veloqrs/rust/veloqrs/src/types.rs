@@ -116,6 +116,37 @@ pub struct SectionLap {
     /// End index in the activity's GPS track
     #[serde(alias = "end_index")]
     pub end_index: u32,
+    /// Power / HR / cadence / climbing metrics from the activity's streams
+    #[serde(default)]
+    pub metrics: SectionEffortMetrics,
+}
+
+/// Physiological metrics of one section traversal, computed from the
+/// activity's streams over the portion's index range. Each field is None
+/// when the stream it needs was not recorded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionEffortMetrics {
+    /// Average heart rate in bpm
+    #[serde(alias = "avg_hr")]
+    pub avg_hr: Option<f64>,
+    /// Average power in watts
+    #[serde(alias = "avg_power")]
+    pub avg_power: Option<f64>,
+    /// Normalized power in watts (30 s rolling average, 4th-power mean)
+    #[serde(alias = "normalized_power")]
+    pub normalized_power: Option<f64>,
+    /// Average power per kg of body weight (weight from wellness)
+    #[serde(alias = "watts_per_kg")]
+    pub watts_per_kg: Option<f64>,
+    /// Average cadence in rpm / spm, zeros excluded
+    #[serde(alias = "avg_cadence")]
+    pub avg_cadence: Option<f64>,
+    /// Output:HR decoupling between the two halves, in percent
+    #[serde(alias = "hr_drift")]
+    pub hr_drift: Option<f64>,
+    /// Vertical ascent rate in meters per hour
+    pub vam: Option<f64>,
 }
 
 /// Section performance record for an activity.
@@ -151,6 +182,9 @@ pub struct SectionPerformanceRecord {
     /// Section distance in meters
     #[serde(alias = "section_distance")]
     pub section_distance: f64,
+    /// Effort metrics of the best lap
+    #[serde(default)]
+    pub metrics: SectionEffortMetrics,
 }
 
 /// Per-direction summary statistics.
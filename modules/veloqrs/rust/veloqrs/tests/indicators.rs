@@ -11,7 +11,7 @@ use std::path::PathBuf;
 use tempfile::TempDir;
 use veloqrs::PersistentRouteEngine;
 
-const CURRENT_VERSION: i32 = 4;
+const CURRENT_VERSION: i32 = 5;
 
 struct Setup {
     engine: PersistentRouteEngine,
@@ -1,7 +1,7 @@
 //! Fresh-install schema verification test.
 //!
 //! Opens a PersistentRouteEngine against an empty database, then verifies
-//! that all 13 migrations produce the expected tables, columns, and indexes.
+//! that all 22 migrations produce the expected tables, columns, and indexes.
 
 use rusqlite::{Connection, params};
 use tempfile::TempDir;
@@ -47,7 +47,7 @@ fn fresh_install_version_numbers() {
     let user_version: i64 = conn
         .query_row("PRAGMA user_version", [], |r| r.get(0))
         .expect("user_version");
-    assert_eq!(user_version, 12, "12 migrations applied");
+    assert_eq!(user_version, 22, "22 migrations applied");
 
     let schema_version: String = conn
         .query_row(
@@ -56,7 +56,7 @@ fn fresh_install_version_numbers() {
             |r| r.get(0),
         )
         .expect("schema_version");
-    assert_eq!(schema_version, "12");
+    assert_eq!(schema_version, "22");
 }
 
 #[test]
@@ -65,13 +65,20 @@ fn fresh_install_all_tables_exist() {
 
     let expected_tables = [
         "activities",
+        "activity_best_efforts",
         "activity_heatmap",
         "activity_indicators",
         "activity_matches",
         "activity_metrics",
+        "activity_streams",
+        "activity_zone_times",
         "athlete_profile",
+        "best_effort_scans",
+        "climb_profiles",
+        "cp_history",
         "exercise_sets",
         "fit_file_status",
+        "fitness_curves",
         "ftp_history",
         "gps_tracks",
         "overlap_cache",
@@ -79,8 +86,10 @@ fn fresh_install_all_tables_exist() {
         "processed_activities",
         "route_groups",
         "route_names",
+        "route_overrides",
         "schema_info",
         "section_activities",
+        "section_checkpoints",
         "sections",
         "settings",
         "signatures",
@@ -107,6 +116,41 @@ fn fresh_install_v030_columns_exist() {
     assert!(column_exists(&conn, "activity_matches", "excluded"));
 }
 
+#[test]
+fn fresh_install_route_shape_columns_exist() {
+    let (_dir, conn) = open_fresh_db();
+
+    assert!(column_exists(&conn, "route_groups", "shape"));
+    assert!(column_exists(&conn, "route_groups", "shape_confidence"));
+}
+
+#[test]
+fn fresh_install_section_effort_metric_columns_exist() {
+    let (_dir, conn) = open_fresh_db();
+
+    for column in [
+        "avg_power",
+        "normalized_power",
+        "watts_per_kg",
+        "avg_cadence",
+        "hr_drift",
+        "vam",
+        "metrics_computed",
+    ] {
+        assert!(
+            column_exists(&conn, "section_activities", column),
+            "{column}"
+        );
+    }
+}
+
+#[test]
+fn fresh_install_zone_times_column_exists() {
+    let (_dir, conn) = open_fresh_db();
+
+    assert!(column_exists(&conn, "activity_metrics", "zones_computed"));
+}
+
 #[test]
 fn fresh_install_indexes_exist() {
     let (_dir, conn) = open_fresh_db();
@@ -118,8 +162,10 @@ fn fresh_install_indexes_exist() {
         "idx_sections_superseded",
         "idx_activity_indicators_activity",
         "idx_activity_indicators_target",
+        "idx_best_efforts_sport",
         "idx_exercise_sets_activity",
         "idx_wellness_date_desc",
+        "idx_route_overrides_key",
     ];
 
     for idx in &expected_indexes {
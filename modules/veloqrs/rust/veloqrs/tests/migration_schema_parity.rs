@@ -12,10 +12,10 @@
 //! How it works
 //! ------------
 //! Build two databases:
-//!   - `fresh`: empty file → `PersistentRouteEngine::new` runs all 12
+//!   - `fresh`: empty file → `PersistentRouteEngine::new` runs all 22
 //!     migrations in order.
 //!   - `migrated`: seed migrations 1–11 (the schema shipped at v0.2.0–v0.2.2)
-//!     then open `PersistentRouteEngine`, which applies migration 12.
+//!     then open `PersistentRouteEngine`, which applies migrations 12+.
 //! For every user-table in `sqlite_master`, diff:
 //!   - `PRAGMA table_info(<table>)` — column names, types, defaults, NOT NULL
 //!     flags, primary-key positions.
@@ -351,5 +351,5 @@ fn fresh_and_migrated_share_user_version() {
         .unwrap();
 
     assert_eq!(fresh_v, migrated_v, "user_version must match");
-    assert_eq!(fresh_v, 12, "user_version is 12 after all migrations");
+    assert_eq!(fresh_v, 22, "user_version is 22 after all migrations");
 }
@@ -382,18 +382,18 @@ fn sql_level_custom_section_survives_forward_migration() {
         )
         .expect("schema_version present");
     assert_eq!(
-        schema_version, "12",
-        "schema version should be bumped to 12"
+        schema_version, "22",
+        "schema version should be bumped to 22"
     );
 
     // rusqlite_migration tracks progress via SQLite's PRAGMA user_version,
-    // so applying 12 migrations leaves user_version = 12.
+    // so applying 22 migrations leaves user_version = 22.
     let pragma_user_version: i64 = conn
         .query_row("PRAGMA user_version", [], |r| r.get(0))
         .expect("PRAGMA user_version readable");
     assert_eq!(
-        pragma_user_version, 12,
-        "rusqlite_migration should have advanced PRAGMA user_version to 12"
+        pragma_user_version, 22,
+        "rusqlite_migration should have advanced PRAGMA user_version to 22"
     );
 
     // Section row preserved.
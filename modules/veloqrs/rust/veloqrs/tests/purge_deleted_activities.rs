@@ -0,0 +1,146 @@
+//! `purge_deleted_activities` must clear every row keyed by a deleted
+//! activity, not just the `activities` cascade. Section traversals and
+//! indicators have no foreign key to `activities`, so before the purge a
+//! server-side deletion left ghost efforts on section leaderboards.
+//!
+//! Run: `cargo test --test purge_deleted_activities -p veloqrs`
+
+use rusqlite::{Connection, params};
+use tempfile::TempDir;
+use tracematch::GpsPoint;
+use veloqrs::{FfiActivityMetrics, PersistentRouteEngine};
+
+fn track() -> Vec<GpsPoint> {
+    (0..3)
+        .map(|i| GpsPoint {
+            latitude: 46.20 + i as f64 * 0.01,
+            longitude: 7.30 + i as f64 * 0.01,
+            elevation: None,
+        })
+        .collect()
+}
+
+fn metrics(id: &str, date: i64) -> FfiActivityMetrics {
+    FfiActivityMetrics {
+        activity_id: id.to_string(),
+        name: id.to_string(),
+        date,
+        distance: 2500.0,
+        moving_time: 600,
+        elapsed_time: 600,
+        elevation_gain: 10.0,
+        avg_hr: None,
+        avg_power: None,
+        sport_type: "Ride".to_string(),
+        training_load: None,
+        ftp: Some(250),
+        power_zone_times: None,
+        hr_zone_times: None,
+    }
+}
+
+fn count(raw: &Connection, table: &str, id: &str) -> i64 {
+    raw.query_row(
+        &format!("SELECT COUNT(*) FROM {} WHERE activity_id = ?1", table),
+        params![id],
+        |r| r.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn purge_removes_all_rows_for_deleted_activity() {
+    let tmp = TempDir::new().expect("temp dir");
+    let db_path = tmp.path().join("test.db");
+    let mut engine = PersistentRouteEngine::new(db_path.to_str().unwrap()).expect("engine new");
+
+    for id in ["keep", "gone"] {
+        engine
+            .add_activity(id.to_string(), track(), "Ride".to_string())
+            .expect("add activity");
+    }
+    engine
+        .set_activity_metrics_extended(vec![
+            metrics("keep", 1_750_000_000),
+            metrics("gone", 1_750_086_400),
+        ])
+        .expect("metrics");
+    engine.set_time_streams_flat(&["gone".to_string()], &[0, 10, 20], &[0]);
+    engine
+        .store_activity_streams(
+            "gone",
+            &veloqrs::net::types::ParsedStreams {
+                heartrate: vec![120.0, 121.0, 122.0],
+                ..Default::default()
+            },
+        )
+        .expect("streams");
+
+    {
+        let raw = Connection::open(&db_path).expect("raw open");
+        raw.execute(
+            "INSERT INTO sections (id, section_type, sport_type, polyline_json, distance_meters)
+             VALUES ('sec-1', 'auto', 'Ride', '[]', 250.0)",
+            [],
+        )
+        .expect("insert section");
+        for (id, lap) in [("keep", 120.0), ("gone", 100.0)] {
+            raw.execute(
+                "INSERT INTO section_activities
+                 (section_id, activity_id, direction, start_index, end_index, distance_meters, lap_time)
+                 VALUES ('sec-1', ?1, 'same', 0, 2, 250.0, ?2)",
+                params![id, lap],
+            )
+            .expect("insert section_activity");
+        }
+    }
+
+    let purged = engine
+        .purge_deleted_activities(&["gone".to_string(), "never-seen".to_string()])
+        .expect("purge");
+    assert_eq!(purged, 1, "unknown ids are ignored");
+
+    assert!(!engine.has_activity("gone"));
+    assert!(engine.get_activity_metrics("gone").is_none());
+    assert!(!engine.has_time_stream("gone"));
+
+    let raw = Connection::open(&db_path).expect("raw open");
+    for table in [
+        "gps_tracks",
+        "section_activities",
+        "activity_metrics",
+        "time_streams",
+        "activity_streams",
+        "activity_indicators",
+        "ftp_history",
+    ] {
+        assert_eq!(count(&raw, table, "gone"), 0, "{} row survived", table);
+    }
+    assert_eq!(count(&raw, "section_activities", "keep"), 1);
+    assert!(engine.has_activity("keep"));
+
+    // The surviving effort is now the section PR.
+    let pr: Option<String> = raw
+        .query_row(
+            "SELECT activity_id FROM activity_indicators WHERE indicator_type = 'section_pr'",
+            [],
+            |r| r.get(0),
+        )
+        .ok();
+    assert_ne!(pr.as_deref(), Some("gone"));
+}
+
+#[test]
+fn activity_ids_between_uses_metric_dates() {
+    let mut engine = PersistentRouteEngine::in_memory().expect("engine");
+    engine
+        .set_activity_metrics_extended(vec![
+            metrics("a", 1_000),
+            metrics("b", 2_000),
+            metrics("c", 3_000),
+        ])
+        .expect("metrics");
+    let mut ids = engine.get_activity_ids_between(1_000, 3_000);
+    ids.sort();
+    assert_eq!(ids, vec!["a".to_string(), "b".to_string()]);
+}
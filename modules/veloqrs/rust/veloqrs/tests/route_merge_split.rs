@@ -0,0 +1,133 @@
+use tempfile::TempDir;
+use tracematch::GpsPoint;
+use veloqrs::PersistentRouteEngine;
+
+fn make_track(base_lat: f64, base_lng: f64, points: usize, jitter: f64) -> Vec<GpsPoint> {
+    (0..points)
+        .map(|i| {
+            let frac = i as f64 / points as f64;
+            GpsPoint::new(
+                base_lat + frac * 0.01 + jitter * (i % 3) as f64 * 0.00001,
+                base_lng + frac * 0.01 + jitter * (i % 5) as f64 * 0.00001,
+            )
+        })
+        .collect()
+}
+
+/// Two well-separated loops, three activities each.
+fn setup_engine() -> (PersistentRouteEngine, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("test.db");
+    let mut engine = PersistentRouteEngine::new(db_path.to_str().unwrap()).unwrap();
+    for i in 0..3 {
+        let jitter = i as f64 * 0.5;
+        engine
+            .add_activity(
+                format!("north_{}", i),
+                make_track(47.0, 7.0, 200, jitter),
+                "Ride".into(),
+            )
+            .unwrap();
+        engine
+            .add_activity(
+                format!("south_{}", i),
+                make_track(46.0, 7.0, 200, jitter),
+                "Ride".into(),
+            )
+            .unwrap();
+    }
+    (engine, dir)
+}
+
+fn route_of(engine: &mut PersistentRouteEngine, activity_id: &str) -> String {
+    engine
+        .get_groups()
+        .iter()
+        .find(|g| g.activity_ids.iter().any(|id| id == activity_id))
+        .map(|g| g.group_id.clone())
+        .expect("activity should be grouped")
+}
+
+#[test]
+fn merged_routes_survive_regrouping_and_absorb_new_activities() {
+    let (mut engine, dir) = setup_engine();
+    let north = route_of(&mut engine, "north_0");
+    let south = route_of(&mut engine, "south_0");
+    assert_ne!(north, south);
+    engine.set_route_name(&north, Some("Commute")).unwrap();
+
+    let merged = engine
+        .merge_routes(&[north.clone(), south.clone()])
+        .expect("merge should succeed");
+    assert_eq!(route_of(&mut engine, "south_2"), merged);
+    assert_eq!(engine.get_route_name(&merged).as_deref(), Some("Commute"));
+    assert!(engine.get_groups().iter().all(|g| g.group_id != north));
+
+    // A new ride on the southern loop triggers a regroup and joins the merge.
+    engine
+        .add_activity(
+            "south_3".into(),
+            make_track(46.0, 7.0, 200, 1.5),
+            "Ride".into(),
+        )
+        .unwrap();
+    assert_eq!(route_of(&mut engine, "south_3"), merged);
+    assert_eq!(route_of(&mut engine, "north_1"), merged);
+
+    // Reopening reads the merged group back from disk.
+    drop(engine);
+    let db_path = dir.path().join("test.db");
+    let mut engine = PersistentRouteEngine::new(db_path.to_str().unwrap()).unwrap();
+    assert_eq!(route_of(&mut engine, "north_2"), merged);
+}
+
+#[test]
+fn split_route_stays_split_after_regrouping() {
+    let (mut engine, _dir) = setup_engine();
+    let north = route_of(&mut engine, "north_0");
+    let south = route_of(&mut engine, "south_0");
+    let merged = engine.merge_routes(&[north, south]).unwrap();
+
+    let split = engine
+        .split_route(
+            &merged,
+            &["south_0".into(), "south_1".into(), "south_2".into()],
+        )
+        .expect("split should succeed");
+    assert_eq!(route_of(&mut engine, "south_1"), split);
+    let kept = route_of(&mut engine, "north_0");
+    assert_ne!(kept, split);
+
+    engine
+        .add_activity(
+            "north_3".into(),
+            make_track(47.0, 7.0, 200, 1.5),
+            "Ride".into(),
+        )
+        .unwrap();
+    assert_eq!(route_of(&mut engine, "south_0"), split);
+    assert_eq!(route_of(&mut engine, "north_0"), kept);
+    assert_eq!(route_of(&mut engine, "north_3"), kept);
+}
+
+#[test]
+fn invalid_merge_and_split_are_rejected() {
+    let (mut engine, _dir) = setup_engine();
+    let north = route_of(&mut engine, "north_0");
+
+    assert!(engine.merge_routes(&[north.clone()]).is_err());
+    assert!(
+        engine
+            .merge_routes(&[north.clone(), "missing".into()])
+            .is_err()
+    );
+    assert!(engine.split_route(&north, &["south_0".into()]).is_err());
+    let everything: Vec<String> = engine
+        .get_groups()
+        .iter()
+        .find(|g| g.group_id == north)
+        .unwrap()
+        .activity_ids
+        .clone();
+    assert!(engine.split_route(&north, &everything).is_err());
+}
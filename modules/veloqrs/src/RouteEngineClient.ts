@@ -61,7 +61,7 @@ import * as sectionDelegates from './delegates/sections';
 import * as settingsDelegates from './delegates/settings';
 import * as strengthDelegates from './delegates/strength';
 import * as syncDelegates from './delegates/sync';
-import type { SyncAuthMethod, SyncStatus } from './delegates/sync';
+import type { SyncAuthMethod, SyncOAuthTokens, SyncStatus } from './delegates/sync';
 import type {
   FfiActivityIndicator,
   FfiActivityRouteHighlight,
@@ -262,6 +262,11 @@ class RouteEngineClient implements DelegateHost {
   setSyncCredentials = (method: SyncAuthMethod, secret: string, athleteId: string): void =>
     syncDelegates.setSyncCredentials(this, method, secret, athleteId);
 
+  setSyncOAuthRefresh = (refreshToken: string, tokenEndpoint: string): void =>
+    syncDelegates.setSyncOAuthRefresh(this, refreshToken, tokenEndpoint);
+
+  getSyncOAuthTokens = (): SyncOAuthTokens | null => syncDelegates.getSyncOAuthTokens(this);
+
   clearSyncCredentials = (): void => syncDelegates.clearSyncCredentials(this);
 
   syncNow = (): boolean => syncDelegates.syncNow(this);
@@ -288,9 +293,10 @@ class RouteEngineClient implements DelegateHost {
   getFilteredSectionSummaries = (
     sportType: string | undefined,
     minVisits: number,
-    sortKey: sectionDelegates.SectionSortKey
+    sortKey: sectionDelegates.SectionSortKey,
+    climbsOnly?: boolean
   ): { totalCount: number; summaries: SectionSummary[] } =>
-    sectionDelegates.getFilteredSectionSummaries(this, sportType, minVisits, sortKey);
+    sectionDelegates.getFilteredSectionSummaries(this, sportType, minVisits, sortKey, climbsOnly);
 
   getRankedSections = (sportType: string, limit: number): FfiRankedSection[] =>
     sectionDelegates.getRankedSections(this, sportType, limit);
@@ -306,9 +312,11 @@ class RouteEngineClient implements DelegateHost {
 
   getFilteredGroupSummaries = (
     minActivities: number,
-    sortKey: routeDelegates.GroupSortKey
+    sortKey: routeDelegates.GroupSortKey,
+    shape?: routeDelegates.RouteShape,
+    climbsOnly?: boolean
   ): { totalCount: number; summaries: GroupSummary[] } =>
-    routeDelegates.getFilteredGroupSummaries(this, minActivities, sortKey);
+    routeDelegates.getFilteredGroupSummaries(this, minActivities, sortKey, shape, climbsOnly);
 
   getSectionById = (sectionId: string): FfiFrequentSection | null =>
     sectionDelegates.getSectionById(this, sectionId);
@@ -43,19 +43,23 @@ export function getGroupSummaries(host: DelegateHost): {
 
 export type GroupSortKey = 'count' | 'name';
 
+export type RouteShape = 'loop' | 'out_and_back' | 'point_to_point' | 'lollipop';
+
 /**
  * Filtered + sorted group summaries in a single FFI call. Activity-count
- * threshold and sort key are applied in Rust so `useGroupSummaries` /
- * `useRouteGroups` stop re-iterating in TS.
+ * threshold, shape, climb filter and sort key are applied in Rust so
+ * `useGroupSummaries` / `useRouteGroups` stop re-iterating in TS.
  */
 export function getFilteredGroupSummaries(
   host: DelegateHost,
   minActivities: number,
-  sortKey: GroupSortKey
+  sortKey: GroupSortKey,
+  shape?: RouteShape,
+  climbsOnly = false
 ): { totalCount: number; summaries: GroupSummary[] } {
   if (!host.ready) return { totalCount: 0, summaries: [] };
   return host.timed('getFilteredGroupSummaries', () =>
-    host.engine.routes().getFilteredSummaries(minActivities, sortKey)
+    host.engine.routes().getFilteredSummaries(minActivities, sortKey, shape, climbsOnly)
   );
 }
 
@@ -89,18 +89,19 @@ export type SectionSortKey = 'visits' | 'distance' | 'name';
 
 /**
  * Filtered + sorted section summaries in a single FFI call. Visit-count
- * threshold and sort key are applied in Rust so `useSectionSummaries` /
- * `useFrequentSections` stop re-iterating in TS.
+ * threshold, climb filter and sort key are applied in Rust so
+ * `useSectionSummaries` / `useFrequentSections` stop re-iterating in TS.
  */
 export function getFilteredSectionSummaries(
   host: DelegateHost,
   sportType: string | undefined,
   minVisits: number,
-  sortKey: SectionSortKey
+  sortKey: SectionSortKey,
+  climbsOnly = false
 ): { totalCount: number; summaries: SectionSummary[] } {
   if (!host.ready) return { totalCount: 0, summaries: [] };
   return host.timed('getFilteredSectionSummaries', () =>
-    host.engine.sections().getFilteredSummaries(sportType, minVisits, sortKey)
+    host.engine.sections().getFilteredSummaries(sportType, minVisits, sortKey, climbsOnly)
   );
 }
 
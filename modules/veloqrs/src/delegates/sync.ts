@@ -31,6 +31,13 @@ export interface SyncStatus {
   lastError?: string;
 }
 
+/** Mirror of the Rust `FfiOAuthTokens` record: the token pair in use after
+ *  any refresh. Replace with the generated type once bindings are regenerated. */
+export interface SyncOAuthTokens {
+  accessToken: string;
+  refreshToken: string;
+}
+
 /** Set the credential once. Never passed per request. */
 export function setSyncCredentials(
   host: DelegateHost,
@@ -44,6 +51,26 @@ export function setSyncCredentials(
   );
 }
 
+/** Let the engine refresh the OAuth token through the proxy's
+ *  `/oauth/refresh` route. Call after `setSyncCredentials('oauth', ...)`. */
+export function setSyncOAuthRefresh(
+  host: DelegateHost,
+  refreshToken: string,
+  tokenEndpoint: string
+): void {
+  if (!host.ready) return;
+  host.timed('setSyncOAuthRefresh', () =>
+    host.engine.sync().setOauthRefresh(refreshToken, tokenEndpoint)
+  );
+}
+
+/** The current token pair, or null without a refresh-enabled session. */
+export function getSyncOAuthTokens(host: DelegateHost): SyncOAuthTokens | null {
+  if (!host.ready) return null;
+  return (host.timed('getSyncOAuthTokens', () => host.engine.sync().getOauthTokens()) ??
+    null) as SyncOAuthTokens | null;
+}
+
 /** Forget the credential (logout). */
 export function clearSyncCredentials(host: DelegateHost): void {
   if (!host.ready) return;
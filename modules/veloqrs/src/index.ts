@@ -39,7 +39,7 @@ export type {
 export { RouteEngineClient, type HeatmapDay, type SectionEncounter } from "./RouteEngineClient";
 
 // Sync service (SyncManager) consumer types
-export type { SyncStatus, SyncAuthMethod } from "./delegates/sync";
+export type { SyncStatus, SyncAuthMethod, SyncOAuthTokens } from "./delegates/sync";
 
 // Import generated functions for top-level aliases
 import {
@@ -2,7 +2,7 @@
  * Veloq API Worker - Cloudflare Worker
  *
  * Handles:
- *   1. OAuth token exchange for intervals.icu (existing)
+ *   1. OAuth token exchange and refresh for intervals.icu
  *   2. Webhook relay: receives intervals.icu webhooks, sends silent push notifications
  *   3. Device token management: register/unregister push tokens
  *
@@ -37,6 +37,7 @@ const RATE_LIMIT_WINDOW_SECONDS = 60;
 interface IntervalsTokenResponse {
   token_type: string;
   access_token: string;
+  refresh_token?: string;
   scope: string;
   athlete: {
     id: string;
@@ -76,6 +77,11 @@ export default {
         return handleOAuthCallback(url, env);
       }
 
+      // Refresh an access token (engine calls this when a request gets 401)
+      if (path === "/oauth/refresh" && request.method === "POST") {
+        return handleOAuthRefresh(request, env);
+      }
+
       // --- Push notification endpoints (additive, backwards-compatible) ---
 
       // Register device push token
@@ -261,6 +267,87 @@ async function handleOAuthCallback(url: URL, env: Env): Promise<Response> {
   return redirectToAppWithToken(tokenData, state);
 }
 
+/**
+ * Exchange a refresh token for a new token pair
+ * The engine posts {grant_type: "refresh_token", refresh_token} and treats any
+ * 4xx as a dead session, so upstream rejections map to 400 invalid_grant
+ */
+async function handleOAuthRefresh(
+  request: Request,
+  env: Env
+): Promise<Response> {
+  const ip =
+    request.headers.get("CF-Connecting-IP") ||
+    request.headers.get("X-Forwarded-For")?.split(",")[0]?.trim() ||
+    "unknown";
+
+  const allowed = await checkRateLimit(ip, env);
+  if (!allowed) {
+    return new Response(
+      JSON.stringify({ error: "Too many requests. Please try again later." }),
+      {
+        status: 429,
+        headers: {
+          "Content-Type": "application/json",
+          "Retry-After": String(RATE_LIMIT_WINDOW_SECONDS),
+        },
+      }
+    );
+  }
+
+  let body: { grant_type?: unknown; refresh_token?: unknown };
+  try {
+    body = await request.json();
+  } catch {
+    return jsonResponse({ error: "Invalid request body" }, 400);
+  }
+
+  if (body.grant_type !== "refresh_token") {
+    return jsonResponse({ error: "unsupported_grant_type" }, 400);
+  }
+  const refreshToken = body.refresh_token;
+  if (!refreshToken || typeof refreshToken !== "string") {
+    return jsonResponse({ error: "invalid_request" }, 400);
+  }
+
+  const formData = new URLSearchParams({
+    client_id: env.INTERVALS_CLIENT_ID,
+    client_secret: env.INTERVALS_CLIENT_SECRET,
+    grant_type: "refresh_token",
+    refresh_token: refreshToken,
+  });
+
+  const tokenResponse = await fetch(INTERVALS_TOKEN_URL, {
+    method: "POST",
+    headers: {
+      "Content-Type": "application/x-www-form-urlencoded",
+    },
+    body: formData.toString(),
+  });
+
+  if (!tokenResponse.ok) {
+    const errorText = await tokenResponse.text();
+    console.error("Token refresh failed:", tokenResponse.status, errorText);
+    if (tokenResponse.status >= 400 && tokenResponse.status < 500) {
+      return jsonResponse({ error: "invalid_grant" }, 400);
+    }
+    return jsonResponse({ error: "token_refresh_failed" }, 502);
+  }
+
+  const tokenData: IntervalsTokenResponse = await tokenResponse.json();
+  if (!tokenData.access_token) {
+    console.error("Invalid refresh response");
+    return jsonResponse({ error: "invalid_response" }, 502);
+  }
+
+  return jsonResponse({
+    access_token: tokenData.access_token,
+    refresh_token: tokenData.refresh_token,
+    token_type: tokenData.token_type,
+    scope: tokenData.scope,
+  });
+}
+
 /**
  * Redirect to app with successful token
  * Uses HTML page with JavaScript redirect since 302 redirects don't work for custom URL schemes
@@ -275,6 +362,9 @@ function redirectToAppWithToken(token: IntervalsTokenResponse, state: string): R
     athlete_name: token.athlete.name,
     state: state,
   });
+  if (token.refresh_token) {
+    params.set("refresh_token", token.refresh_token);
+  }
 
   const redirectUrl = `${APP_SCHEME}://oauth/callback?${params.toString()}`;
 
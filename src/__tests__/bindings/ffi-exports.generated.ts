@@ -6,7 +6,7 @@
  * Used by tests to validate TypeScript bindings match Rust exports.
  *
  * 6 standalone `#[uniffi::export]` functions plus
- * 191 methods inside `#[uniffi::export] impl` blocks across
+ * 228 methods inside `#[uniffi::export] impl` blocks across
  * 11 UniFFI Objects.
  */
 
@@ -29,7 +29,7 @@ export interface FfiExportInfo {
 
 /**
  * All FFI exports from Rust source.
- * Total: 197 exports (6 standalone + 191 methods)
+ * Total: 234 exports (6 standalone + 228 methods)
  */
 export const FFI_EXPORTS: FfiExportInfo[] = [
   {
@@ -153,11 +153,20 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     returnType: 'Result<Vec<crate::FfiGpsPoint>, VeloqError>',
     object: 'ActivityManager',
   },
+  {
+    name: 'get_streams',
+    camelName: 'getStreams',
+    file: 'objects/activities.rs',
+    line: 126,
+    paramCount: 1,
+    returnType: 'Result<Option<crate::FfiActivityStreams>, VeloqError>',
+    object: 'ActivityManager',
+  },
   {
     name: 'remove',
     camelName: 'remove',
     file: 'objects/activities.rs',
-    line: 125,
+    line: 139,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'ActivityManager',
@@ -166,7 +175,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'debug_clone',
     camelName: 'debugClone',
     file: 'objects/activities.rs',
-    line: 134,
+    line: 148,
     paramCount: 2,
     returnType: 'Result<u32, VeloqError>',
     object: 'ActivityManager',
@@ -175,7 +184,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_highlights_bundle',
     camelName: 'getHighlightsBundle',
     file: 'objects/activities.rs',
-    line: 141,
+    line: 155,
     paramCount: 1,
     returnType: 'Result<crate::FfiActivityHighlightsBundle, VeloqError>',
     object: 'ActivityManager',
@@ -283,7 +292,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'is_initialized',
     camelName: 'isInitialized',
     file: 'objects/engine.rs',
-    line: 33,
+    line: 34,
     paramCount: 0,
     returnType: 'bool',
     object: 'VeloqEngine',
@@ -292,7 +301,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_stats',
     camelName: 'getStats',
     file: 'objects/engine.rs',
-    line: 40,
+    line: 41,
     paramCount: 0,
     returnType: 'Result<PersistentEngineStats, VeloqError>',
     object: 'VeloqEngine',
@@ -301,7 +310,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_count',
     camelName: 'getActivityCount',
     file: 'objects/engine.rs',
-    line: 44,
+    line: 45,
     paramCount: 0,
     returnType: 'Result<u32, VeloqError>',
     object: 'VeloqEngine',
@@ -310,7 +319,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activities_needing_time_streams',
     camelName: 'getActivitiesNeedingTimeStreams',
     file: 'objects/engine.rs',
-    line: 50,
+    line: 51,
     paramCount: 0,
     returnType: 'Result<Vec<String>, VeloqError>',
     object: 'VeloqEngine',
@@ -319,7 +328,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'clear',
     camelName: 'clear',
     file: 'objects/engine.rs',
-    line: 54,
+    line: 55,
     paramCount: 0,
     returnType: 'Result<(), VeloqError>',
     object: 'VeloqEngine',
@@ -328,7 +337,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'clear_routes_and_sections',
     camelName: 'clearRoutesAndSections',
     file: 'objects/engine.rs',
-    line: 64,
+    line: 65,
     paramCount: 0,
     returnType: 'Result<(), VeloqError>',
     object: 'VeloqEngine',
@@ -337,7 +346,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'destroy',
     camelName: 'destroy',
     file: 'objects/engine.rs',
-    line: 75,
+    line: 76,
     paramCount: 0,
     returnType: 'void',
     object: 'VeloqEngine',
@@ -346,7 +355,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'cleanup_old_activities',
     camelName: 'cleanupOldActivities',
     file: 'objects/engine.rs',
-    line: 81,
+    line: 82,
     paramCount: 1,
     returnType: 'Result<u32, VeloqError>',
     object: 'VeloqEngine',
@@ -355,7 +364,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'mark_for_recomputation',
     camelName: 'markForRecomputation',
     file: 'objects/engine.rs',
-    line: 95,
+    line: 96,
     paramCount: 0,
     returnType: 'Result<(), VeloqError>',
     object: 'VeloqEngine',
@@ -364,7 +373,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'set_name_translations',
     camelName: 'setNameTranslations',
     file: 'objects/engine.rs',
-    line: 102,
+    line: 103,
     paramCount: 2,
     returnType: 'void',
     object: 'VeloqEngine',
@@ -373,7 +382,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'sections',
     camelName: 'sections',
     file: 'objects/engine.rs',
-    line: 109,
+    line: 110,
     paramCount: 0,
     returnType: 'Arc<super::sections::SectionManager>',
     object: 'VeloqEngine',
@@ -382,7 +391,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'activities',
     camelName: 'activities',
     file: 'objects/engine.rs',
-    line: 113,
+    line: 114,
     paramCount: 0,
     returnType: 'Arc<super::activities::ActivityManager>',
     object: 'VeloqEngine',
@@ -391,7 +400,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'routes',
     camelName: 'routes',
     file: 'objects/engine.rs',
-    line: 117,
+    line: 118,
     paramCount: 0,
     returnType: 'Arc<super::routes::RouteManager>',
     object: 'VeloqEngine',
@@ -400,7 +409,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'maps',
     camelName: 'maps',
     file: 'objects/engine.rs',
-    line: 121,
+    line: 122,
     paramCount: 0,
     returnType: 'Arc<super::maps::MapManager>',
     object: 'VeloqEngine',
@@ -409,7 +418,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'fitness',
     camelName: 'fitness',
     file: 'objects/engine.rs',
-    line: 125,
+    line: 126,
     paramCount: 0,
     returnType: 'Arc<super::fitness::FitnessManager>',
     object: 'VeloqEngine',
@@ -418,7 +427,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'settings',
     camelName: 'settings',
     file: 'objects/engine.rs',
-    line: 129,
+    line: 130,
     paramCount: 0,
     returnType: 'Arc<super::settings::SettingsManager>',
     object: 'VeloqEngine',
@@ -427,7 +436,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'detection',
     camelName: 'detection',
     file: 'objects/engine.rs',
-    line: 133,
+    line: 134,
     paramCount: 0,
     returnType: 'Arc<super::detection::DetectionManager>',
     object: 'VeloqEngine',
@@ -436,7 +445,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'strength',
     camelName: 'strength',
     file: 'objects/engine.rs',
-    line: 137,
+    line: 138,
     paramCount: 0,
     returnType: 'Arc<super::strength::StrengthManager>',
     object: 'VeloqEngine',
@@ -445,7 +454,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'heatmap',
     camelName: 'heatmap',
     file: 'objects/engine.rs',
-    line: 141,
+    line: 142,
     paramCount: 0,
     returnType: 'Arc<super::tiles::HeatmapManager>',
     object: 'VeloqEngine',
@@ -454,7 +463,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'sync',
     camelName: 'sync',
     file: 'objects/engine.rs',
-    line: 145,
+    line: 146,
     paramCount: 0,
     returnType: 'Arc<super::sync::SyncManager>',
     object: 'VeloqEngine',
@@ -463,7 +472,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'backup_database',
     camelName: 'backupDatabase',
     file: 'objects/engine.rs',
-    line: 151,
+    line: 152,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'VeloqEngine',
@@ -472,7 +481,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_backup_metadata',
     camelName: 'getBackupMetadata',
     file: 'objects/engine.rs',
-    line: 171,
+    line: 172,
     paramCount: 0,
     returnType: 'Result<String, VeloqError>',
     object: 'VeloqEngine',
@@ -481,7 +490,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'bulk_export_gpx',
     camelName: 'bulkExportGpx',
     file: 'objects/engine.rs',
-    line: 198,
+    line: 199,
     paramCount: 1,
     returnType: 'Result<crate::persistence::export::BulkExportResult, VeloqError>',
     object: 'VeloqEngine',
@@ -490,11 +499,38 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'bulk_export_geojson',
     camelName: 'bulkExportGeojson',
     file: 'objects/engine.rs',
-    line: 209,
+    line: 210,
     paramCount: 1,
     returnType: 'Result<crate::persistence::export::BulkExportResult, VeloqError>',
     object: 'VeloqEngine',
   },
+  {
+    name: 'export_route_course',
+    camelName: 'exportRouteCourse',
+    file: 'objects/engine.rs',
+    line: 222,
+    paramCount: 3,
+    returnType: 'Result<crate::persistence::export::CourseExportResult, VeloqError>',
+    object: 'VeloqEngine',
+  },
+  {
+    name: 'export_section_course',
+    camelName: 'exportSectionCourse',
+    file: 'objects/engine.rs',
+    line: 236,
+    paramCount: 3,
+    returnType: 'Result<crate::persistence::export::CourseExportResult, VeloqError>',
+    object: 'VeloqEngine',
+  },
+  {
+    name: 'export_course',
+    camelName: 'exportCourse',
+    file: 'objects/engine.rs',
+    line: 250,
+    paramCount: 5,
+    returnType: 'Result<crate::persistence::export::CourseExportResult, VeloqError>',
+    object: 'VeloqEngine',
+  },
   {
     name: 'new',
     camelName: 'new',
@@ -531,11 +567,20 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     returnType: 'Result<Vec<f64>, VeloqError>',
     object: 'FitnessManager',
   },
+  {
+    name: 'get_activity_zone_times',
+    camelName: 'getActivityZoneTimes',
+    file: 'objects/fitness.rs',
+    line: 106,
+    paramCount: 1,
+    returnType: 'Result<crate::FfiActivityZoneTimes, VeloqError>',
+    object: 'FitnessManager',
+  },
   {
     name: 'get_ftp_trend',
     camelName: 'getFtpTrend',
     file: 'objects/fitness.rs',
-    line: 105,
+    line: 118,
     paramCount: 0,
     returnType: 'Result<crate::FfiFtpTrend, VeloqError>',
     object: 'FitnessManager',
@@ -544,7 +589,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'save_pace_snapshot',
     camelName: 'savePaceSnapshot',
     file: 'objects/fitness.rs',
-    line: 109,
+    line: 122,
     paramCount: 5,
     returnType: 'Result<(), VeloqError>',
     object: 'FitnessManager',
@@ -553,16 +598,124 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_pace_trend',
     camelName: 'getPaceTrend',
     file: 'objects/fitness.rs',
-    line: 122,
+    line: 135,
     paramCount: 1,
     returnType: 'Result<crate::FfiPaceTrend, VeloqError>',
     object: 'FitnessManager',
   },
+  {
+    name: 'fit_critical_power',
+    camelName: 'fitCriticalPower',
+    file: 'objects/fitness.rs',
+    line: 142,
+    paramCount: 2,
+    returnType: 'Result<Option<crate::FfiCriticalPowerFit>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_cp_trend',
+    camelName: 'getCpTrend',
+    file: 'objects/fitness.rs',
+    line: 155,
+    paramCount: 1,
+    returnType: 'Result<crate::FfiCpTrend, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_race_predictions',
+    camelName: 'getRacePredictions',
+    file: 'objects/fitness.rs',
+    line: 161,
+    paramCount: 0,
+    returnType: 'Result<Vec<crate::FfiRacePrediction>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_best_effort_targets',
+    camelName: 'getBestEffortTargets',
+    file: 'objects/fitness.rs',
+    line: 166,
+    paramCount: 0,
+    returnType: 'Result<crate::FfiBestEffortTargets, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'set_best_effort_targets',
+    camelName: 'setBestEffortTargets',
+    file: 'objects/fitness.rs',
+    line: 177,
+    paramCount: 2,
+    returnType: 'Result<u32, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_best_effort_records',
+    camelName: 'getBestEffortRecords',
+    file: 'objects/fitness.rs',
+    line: 191,
+    paramCount: 1,
+    returnType: 'Result<Vec<crate::FfiBestEffortRecord>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_activity_best_efforts',
+    camelName: 'getActivityBestEfforts',
+    file: 'objects/fitness.rs',
+    line: 204,
+    paramCount: 1,
+    returnType: 'Result<Vec<crate::FfiBestEffort>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_power_curve',
+    camelName: 'getPowerCurve',
+    file: 'objects/fitness.rs',
+    line: 217,
+    paramCount: 2,
+    returnType: 'Result<Option<crate::FfiPowerCurve>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_pace_curve',
+    camelName: 'getPaceCurve',
+    file: 'objects/fitness.rs',
+    line: 241,
+    paramCount: 2,
+    returnType: 'Result<Option<crate::FfiPaceCurve>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_best_power',
+    camelName: 'getBestPower',
+    file: 'objects/fitness.rs',
+    line: 272,
+    paramCount: 3,
+    returnType: 'Result<Option<f64>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'get_best_pace',
+    camelName: 'getBestPace',
+    file: 'objects/fitness.rs',
+    line: 287,
+    paramCount: 3,
+    returnType: 'Result<Option<f64>, VeloqError>',
+    object: 'FitnessManager',
+  },
+  {
+    name: 'compare_curve_windows',
+    camelName: 'compareCurveWindows',
+    file: 'objects/fitness.rs',
+    line: 302,
+    paramCount: 4,
+    returnType: 'Result<crate::FfiCurveComparison, VeloqError>',
+    object: 'FitnessManager',
+  },
   {
     name: 'get_available_sport_types',
     camelName: 'getAvailableSportTypes',
     file: 'objects/fitness.rs',
-    line: 126,
+    line: 317,
     paramCount: 0,
     returnType: 'Result<Vec<String>, VeloqError>',
     object: 'FitnessManager',
@@ -571,7 +724,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_heatmap',
     camelName: 'getActivityHeatmap',
     file: 'objects/fitness.rs',
-    line: 130,
+    line: 321,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiHeatmapDay>, VeloqError>',
     object: 'FitnessManager',
@@ -580,7 +733,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_summary_card_data',
     camelName: 'getSummaryCardData',
     file: 'objects/fitness.rs',
-    line: 138,
+    line: 329,
     paramCount: 4,
     returnType: 'Result<crate::FfiSummaryCardData, VeloqError>',
     object: 'FitnessManager',
@@ -589,7 +742,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_patterns',
     camelName: 'getActivityPatterns',
     file: 'objects/fitness.rs',
-    line: 154,
+    line: 345,
     paramCount: 0,
     returnType: 'Result<Vec<crate::FfiActivityPattern>, VeloqError>',
     object: 'FitnessManager',
@@ -598,7 +751,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_pattern_for_today',
     camelName: 'getPatternForToday',
     file: 'objects/fitness.rs',
-    line: 158,
+    line: 349,
     paramCount: 0,
     returnType: 'Result<Option<crate::FfiActivityPattern>, VeloqError>',
     object: 'FitnessManager',
@@ -607,7 +760,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_patterns_with_today',
     camelName: 'getActivityPatternsWithToday',
     file: 'objects/fitness.rs',
-    line: 164,
+    line: 355,
     paramCount: 0,
     returnType: 'Result<crate::FfiActivityPatternsBundle, VeloqError>',
     object: 'FitnessManager',
@@ -616,7 +769,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'upsert_wellness',
     camelName: 'upsertWellness',
     file: 'objects/fitness.rs',
-    line: 175,
+    line: 366,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'FitnessManager',
@@ -625,16 +778,25 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_wellness_sparklines',
     camelName: 'getWellnessSparklines',
     file: 'objects/fitness.rs',
-    line: 207,
+    line: 398,
     paramCount: 1,
     returnType: 'Result<Option<crate::FfiWellnessSparklines>, VeloqError>',
     object: 'FitnessManager',
   },
+  {
+    name: 'get_fitness_model',
+    camelName: 'getFitnessModel',
+    file: 'objects/fitness.rs',
+    line: 414,
+    paramCount: 4,
+    returnType: 'Result<crate::FfiFitnessModel, VeloqError>',
+    object: 'FitnessManager',
+  },
   {
     name: 'compute_hrv_trend',
     camelName: 'computeHrvTrend',
     file: 'objects/fitness.rs',
-    line: 222,
+    line: 432,
     paramCount: 1,
     returnType: 'Result<Option<crate::FfiHrvTrend>, VeloqError>',
     object: 'FitnessManager',
@@ -643,7 +805,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'find_stale_pr_opportunities',
     camelName: 'findStalePrOpportunities',
     file: 'objects/fitness.rs',
-    line: 246,
+    line: 456,
     paramCount: 4,
     returnType: 'Result<Vec<crate::FfiStalePrOpportunity>, VeloqError>',
     object: 'FitnessManager',
@@ -652,7 +814,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_insights_data',
     camelName: 'getInsightsData',
     file: 'objects/fitness.rs',
-    line: 310,
+    line: 520,
     paramCount: 6,
     returnType: 'Result<crate::FfiInsightsData, VeloqError>',
     object: 'FitnessManager',
@@ -661,7 +823,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_startup_data',
     camelName: 'getStartupData',
     file: 'objects/fitness.rs',
-    line: 400,
+    line: 612,
     paramCount: 7,
     returnType: 'Result<crate::FfiStartupData, VeloqError>',
     object: 'FitnessManager',
@@ -724,7 +886,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'new',
     camelName: 'new',
     file: 'objects/routes.rs',
-    line: 12,
+    line: 48,
     paramCount: 0,
     returnType: 'Arc<Self>',
     object: 'RouteManager',
@@ -733,7 +895,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_all',
     camelName: 'getAll',
     file: 'objects/routes.rs',
-    line: 16,
+    line: 52,
     paramCount: 0,
     returnType: 'Result<Vec<crate::FfiRouteGroup>, VeloqError>',
     object: 'RouteManager',
@@ -742,7 +904,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_by_id',
     camelName: 'getById',
     file: 'objects/routes.rs',
-    line: 26,
+    line: 63,
     paramCount: 1,
     returnType: 'Result<Option<crate::FfiRouteGroup>, VeloqError>',
     object: 'RouteManager',
@@ -751,7 +913,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_summaries',
     camelName: 'getSummaries',
     file: 'objects/routes.rs',
-    line: 30,
+    line: 73,
     paramCount: 0,
     returnType: 'Result<Vec<crate::GroupSummary>, VeloqError>',
     object: 'RouteManager',
@@ -760,7 +922,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_summaries_with_count',
     camelName: 'getSummariesWithCount',
     file: 'objects/routes.rs',
-    line: 34,
+    line: 77,
     paramCount: 0,
     returnType: 'Result<crate::FfiGroupSummariesResult, VeloqError>',
     object: 'RouteManager',
@@ -769,8 +931,8 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_filtered_summaries',
     camelName: 'getFilteredSummaries',
     file: 'objects/routes.rs',
-    line: 44,
-    paramCount: 2,
+    line: 90,
+    paramCount: 4,
     returnType: 'Result<crate::FfiGroupSummariesResult, VeloqError>',
     object: 'RouteManager',
   },
@@ -778,25 +940,52 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_consensus_route',
     camelName: 'getConsensusRoute',
     file: 'objects/routes.rs',
-    line: 64,
+    line: 118,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiGpsPoint>, VeloqError>',
     object: 'RouteManager',
   },
+  {
+    name: 'get_route_deviation',
+    camelName: 'getRouteDeviation',
+    file: 'objects/routes.rs',
+    line: 135,
+    paramCount: 3,
+    returnType: 'Result<Option<crate::FfiRouteDeviation>, VeloqError>',
+    object: 'RouteManager',
+  },
+  {
+    name: 'get_climb_profile',
+    camelName: 'getClimbProfile',
+    file: 'objects/routes.rs',
+    line: 151,
+    paramCount: 1,
+    returnType: 'Result<Option<crate::FfiClimbProfile>, VeloqError>',
+    object: 'RouteManager',
+  },
   {
     name: 'get_performances',
     camelName: 'getPerformances',
     file: 'objects/routes.rs',
-    line: 78,
+    line: 158,
     paramCount: 3,
     returnType: 'Result<crate::FfiRoutePerformanceResult, VeloqError>',
     object: 'RouteManager',
   },
+  {
+    name: 'predict_time',
+    camelName: 'predictTime',
+    file: 'objects/routes.rs',
+    line: 176,
+    paramCount: 1,
+    returnType: 'Result<Option<crate::FfiFinishTimePrediction>, VeloqError>',
+    object: 'RouteManager',
+  },
   {
     name: 'get_screen_data',
     camelName: 'getScreenData',
     file: 'objects/routes.rs',
-    line: 94,
+    line: 183,
     paramCount: 9,
     returnType: 'Result<crate::FfiRoutesScreenData, VeloqError>',
     object: 'RouteManager',
@@ -805,7 +994,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'set_name',
     camelName: 'setName',
     file: 'objects/routes.rs',
-    line: 121,
+    line: 210,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'RouteManager',
@@ -814,7 +1003,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_all_names',
     camelName: 'getAllNames',
     file: 'objects/routes.rs',
-    line: 135,
+    line: 224,
     paramCount: 0,
     returnType: 'Result<std::collections::HashMap<String, String>, VeloqError>',
     object: 'RouteManager',
@@ -823,7 +1012,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'exclude_activity',
     camelName: 'excludeActivity',
     file: 'objects/routes.rs',
-    line: 139,
+    line: 228,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'RouteManager',
@@ -832,7 +1021,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'include_activity',
     camelName: 'includeActivity',
     file: 'objects/routes.rs',
-    line: 153,
+    line: 242,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'RouteManager',
@@ -841,7 +1030,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_excluded_activities',
     camelName: 'getExcludedActivities',
     file: 'objects/routes.rs',
-    line: 167,
+    line: 256,
     paramCount: 1,
     returnType: 'Result<Vec<String>, VeloqError>',
     object: 'RouteManager',
@@ -850,7 +1039,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_excluded_performances',
     camelName: 'getExcludedPerformances',
     file: 'objects/routes.rs',
-    line: 171,
+    line: 260,
     paramCount: 2,
     returnType: 'Result<crate::FfiRoutePerformanceResult, VeloqError>',
     object: 'RouteManager',
@@ -859,7 +1048,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_route_highlights',
     camelName: 'getActivityRouteHighlights',
     file: 'objects/routes.rs',
-    line: 184,
+    line: 273,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiActivityRouteHighlight>, VeloqError>',
     object: 'RouteManager',
@@ -868,16 +1057,43 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'set_representative',
     camelName: 'setRepresentative',
     file: 'objects/routes.rs',
-    line: 191,
+    line: 280,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'RouteManager',
   },
+  {
+    name: 'suggest_routes',
+    camelName: 'suggestRoutes',
+    file: 'objects/routes.rs',
+    line: 291,
+    paramCount: 5,
+    returnType: 'Result<Vec<crate::FfiRouteSuggestion>, VeloqError>',
+    object: 'RouteManager',
+  },
+  {
+    name: 'merge_routes',
+    camelName: 'mergeRoutes',
+    file: 'objects/routes.rs',
+    line: 312,
+    paramCount: 1,
+    returnType: 'Result<String, VeloqError>',
+    object: 'RouteManager',
+  },
+  {
+    name: 'split_route',
+    camelName: 'splitRoute',
+    file: 'objects/routes.rs',
+    line: 321,
+    paramCount: 2,
+    returnType: 'Result<String, VeloqError>',
+    object: 'RouteManager',
+  },
   {
     name: 'new',
     camelName: 'new',
     file: 'objects/sections.rs',
-    line: 13,
+    line: 39,
     paramCount: 0,
     returnType: 'Arc<Self>',
     object: 'SectionManager',
@@ -886,7 +1102,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_all',
     camelName: 'getAll',
     file: 'objects/sections.rs',
-    line: 17,
+    line: 43,
     paramCount: 0,
     returnType: 'Result<Vec<crate::FfiFrequentSection>, VeloqError>',
     object: 'SectionManager',
@@ -895,7 +1111,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_filtered',
     camelName: 'getFiltered',
     file: 'objects/sections.rs',
-    line: 29,
+    line: 55,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiFrequentSection>, VeloqError>',
     object: 'SectionManager',
@@ -904,7 +1120,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_by_type',
     camelName: 'getByType',
     file: 'objects/sections.rs',
-    line: 43,
+    line: 69,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiSection>, VeloqError>',
     object: 'SectionManager',
@@ -913,7 +1129,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_for_activity',
     camelName: 'getForActivity',
     file: 'objects/sections.rs',
-    line: 56,
+    line: 77,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiSection>, VeloqError>',
     object: 'SectionManager',
@@ -922,7 +1138,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_by_id',
     camelName: 'getById',
     file: 'objects/sections.rs',
-    line: 65,
+    line: 81,
     paramCount: 1,
     returnType: 'Result<Option<crate::FfiFrequentSection>, VeloqError>',
     object: 'SectionManager',
@@ -931,7 +1147,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_summaries',
     camelName: 'getSummaries',
     file: 'objects/sections.rs',
-    line: 75,
+    line: 91,
     paramCount: 1,
     returnType: 'Result<Vec<crate::SectionSummary>, VeloqError>',
     object: 'SectionManager',
@@ -940,7 +1156,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_ranked',
     camelName: 'getRanked',
     file: 'objects/sections.rs',
-    line: 85,
+    line: 101,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiRankedSection>, VeloqError>',
     object: 'SectionManager',
@@ -949,7 +1165,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_ranked_batch',
     camelName: 'getRankedBatch',
     file: 'objects/sections.rs',
-    line: 95,
+    line: 111,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiRankedSectionsBySport>, VeloqError>',
     object: 'SectionManager',
@@ -958,7 +1174,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_count',
     camelName: 'getCount',
     file: 'objects/sections.rs',
-    line: 116,
+    line: 132,
     paramCount: 0,
     returnType: 'Result<u32, VeloqError>',
     object: 'SectionManager',
@@ -967,7 +1183,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_summaries_with_count',
     camelName: 'getSummariesWithCount',
     file: 'objects/sections.rs',
-    line: 120,
+    line: 136,
     paramCount: 1,
     returnType: 'Result<crate::FfiSectionSummariesResult, VeloqError>',
     object: 'SectionManager',
@@ -976,8 +1192,8 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_filtered_summaries',
     camelName: 'getFilteredSummaries',
     file: 'objects/sections.rs',
-    line: 141,
-    paramCount: 3,
+    line: 157,
+    paramCount: 4,
     returnType: 'Result<crate::FfiSectionSummariesResult, VeloqError>',
     object: 'SectionManager',
   },
@@ -985,7 +1201,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'prune_overlapping',
     camelName: 'pruneOverlapping',
     file: 'objects/sections.rs',
-    line: 170,
+    line: 190,
     paramCount: 0,
     returnType: 'Result<u32, VeloqError>',
     object: 'SectionManager',
@@ -994,7 +1210,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_polyline',
     camelName: 'getPolyline',
     file: 'objects/sections.rs',
-    line: 201,
+    line: 221,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiGpsPoint>, VeloqError>',
     object: 'SectionManager',
@@ -1003,16 +1219,79 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_performances',
     camelName: 'getPerformances',
     file: 'objects/sections.rs',
-    line: 214,
+    line: 234,
     paramCount: 2,
     returnType: 'Result<crate::FfiSectionPerformanceResult, VeloqError>',
     object: 'SectionManager',
   },
+  {
+    name: 'predict_time',
+    camelName: 'predictTime',
+    file: 'objects/sections.rs',
+    line: 248,
+    paramCount: 1,
+    returnType: 'Result<Option<crate::FfiFinishTimePrediction>, VeloqError>',
+    object: 'SectionManager',
+  },
+  {
+    name: 'compare_efforts',
+    camelName: 'compareEfforts',
+    file: 'objects/sections.rs',
+    line: 258,
+    paramCount: 3,
+    returnType: 'Result<Option<crate::FfiGhostComparison>, VeloqError>',
+    object: 'SectionManager',
+  },
+  {
+    name: 'get_climb_profile',
+    camelName: 'getClimbProfile',
+    file: 'objects/sections.rs',
+    line: 269,
+    paramCount: 1,
+    returnType: 'Result<Option<crate::FfiClimbProfile>, VeloqError>',
+    object: 'SectionManager',
+  },
+  {
+    name: 'get_checkpoints',
+    camelName: 'getCheckpoints',
+    file: 'objects/sections.rs',
+    line: 277,
+    paramCount: 1,
+    returnType: 'Result<Vec<crate::FfiSectionCheckpoint>, VeloqError>',
+    object: 'SectionManager',
+  },
+  {
+    name: 'set_checkpoints',
+    camelName: 'setCheckpoints',
+    file: 'objects/sections.rs',
+    line: 286,
+    paramCount: 2,
+    returnType: 'Result<Vec<crate::FfiSectionCheckpoint>, VeloqError>',
+    object: 'SectionManager',
+  },
+  {
+    name: 'generate_checkpoints',
+    camelName: 'generateCheckpoints',
+    file: 'objects/sections.rs',
+    line: 299,
+    paramCount: 3,
+    returnType: 'Result<Vec<crate::FfiSectionCheckpoint>, VeloqError>',
+    object: 'SectionManager',
+  },
+  {
+    name: 'get_split_table',
+    camelName: 'getSplitTable',
+    file: 'objects/sections.rs',
+    line: 313,
+    paramCount: 2,
+    returnType: 'Result<Option<crate::FfiSectionSplitTable>, VeloqError>',
+    object: 'SectionManager',
+  },
   {
     name: 'get_performances_batch',
     camelName: 'getPerformancesBatch',
     file: 'objects/sections.rs',
-    line: 230,
+    line: 325,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiSectionPerformanceBatchEntry>, VeloqError>',
     object: 'SectionManager',
@@ -1021,7 +1300,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'recalculate_polyline',
     camelName: 'recalculatePolyline',
     file: 'objects/sections.rs',
-    line: 257,
+    line: 352,
     paramCount: 1,
     returnType: 'Result<Option<crate::FfiSectionRecalcResult>, VeloqError>',
     object: 'SectionManager',
@@ -1030,7 +1309,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_excluded_performances',
     camelName: 'getExcludedPerformances',
     file: 'objects/sections.rs',
-    line: 264,
+    line: 359,
     paramCount: 1,
     returnType: 'Result<crate::FfiSectionPerformanceResult, VeloqError>',
     object: 'SectionManager',
@@ -1039,7 +1318,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_calendar_summary',
     camelName: 'getCalendarSummary',
     file: 'objects/sections.rs',
-    line: 281,
+    line: 376,
     paramCount: 1,
     returnType: 'Result<Option<crate::FfiCalendarSummary>, VeloqError>',
     object: 'SectionManager',
@@ -1048,7 +1327,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_reference_info',
     camelName: 'getReferenceInfo',
     file: 'objects/sections.rs',
-    line: 291,
+    line: 386,
     paramCount: 1,
     returnType: 'Result<crate::FfiSectionReferenceInfo, VeloqError>',
     object: 'SectionManager',
@@ -1057,7 +1336,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'set_reference',
     camelName: 'setReference',
     file: 'objects/sections.rs',
-    line: 308,
+    line: 403,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1066,7 +1345,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'reset_reference',
     camelName: 'resetReference',
     file: 'objects/sections.rs',
-    line: 315,
+    line: 410,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1075,7 +1354,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'accept',
     camelName: 'accept',
     file: 'objects/sections.rs',
-    line: 322,
+    line: 417,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1084,7 +1363,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'accept_all',
     camelName: 'acceptAll',
     file: 'objects/sections.rs',
-    line: 331,
+    line: 426,
     paramCount: 0,
     returnType: 'Result<u32, VeloqError>',
     object: 'SectionManager',
@@ -1093,7 +1372,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'set_name',
     camelName: 'setName',
     file: 'objects/sections.rs',
-    line: 339,
+    line: 434,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1102,7 +1381,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_all_names',
     camelName: 'getAllNames',
     file: 'objects/sections.rs',
-    line: 353,
+    line: 448,
     paramCount: 0,
     returnType: 'Result<std::collections::HashMap<String, String>, VeloqError>',
     object: 'SectionManager',
@@ -1111,7 +1390,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'create',
     camelName: 'create',
     file: 'objects/sections.rs',
-    line: 357,
+    line: 452,
     paramCount: 7,
     returnType: 'Result<String, VeloqError>',
     object: 'SectionManager',
@@ -1120,7 +1399,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'exclude_activity',
     camelName: 'excludeActivity',
     file: 'objects/sections.rs',
-    line: 390,
+    line: 485,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1129,7 +1408,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'include_activity',
     camelName: 'includeActivity',
     file: 'objects/sections.rs',
-    line: 405,
+    line: 500,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1138,7 +1417,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_excluded_activities',
     camelName: 'getExcludedActivities',
     file: 'objects/sections.rs',
-    line: 420,
+    line: 515,
     paramCount: 1,
     returnType: 'Result<Vec<String>, VeloqError>',
     object: 'SectionManager',
@@ -1147,7 +1426,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'delete',
     camelName: 'delete',
     file: 'objects/sections.rs',
-    line: 424,
+    line: 519,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1156,7 +1435,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'extract_trace',
     camelName: 'extractTrace',
     file: 'objects/sections.rs',
-    line: 431,
+    line: 526,
     paramCount: 2,
     returnType: 'Result<Vec<u8>, VeloqError>',
     object: 'SectionManager',
@@ -1165,7 +1444,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'trim',
     camelName: 'trim',
     file: 'objects/sections.rs',
-    line: 467,
+    line: 562,
     paramCount: 3,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1174,7 +1453,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'reset_bounds',
     camelName: 'resetBounds',
     file: 'objects/sections.rs',
-    line: 474,
+    line: 569,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1183,7 +1462,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'has_original_bounds',
     camelName: 'hasOriginalBounds',
     file: 'objects/sections.rs',
-    line: 481,
+    line: 576,
     paramCount: 1,
     returnType: 'Result<bool, VeloqError>',
     object: 'SectionManager',
@@ -1192,7 +1471,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_extension_track',
     camelName: 'getExtensionTrack',
     file: 'objects/sections.rs',
-    line: 485,
+    line: 580,
     paramCount: 1,
     returnType: 'Result<crate::FfiSectionExtensionTrack, VeloqError>',
     object: 'SectionManager',
@@ -1201,7 +1480,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'expand_bounds',
     camelName: 'expandBounds',
     file: 'objects/sections.rs',
-    line: 501,
+    line: 596,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1210,7 +1489,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_efficiency_trend',
     camelName: 'getEfficiencyTrend',
     file: 'objects/sections.rs',
-    line: 517,
+    line: 612,
     paramCount: 1,
     returnType: 'Result<Option<crate::FfiEfficiencyTrend>, VeloqError>',
     object: 'SectionManager',
@@ -1219,7 +1498,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'disable',
     camelName: 'disable',
     file: 'objects/sections.rs',
-    line: 524,
+    line: 619,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1228,7 +1507,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'enable',
     camelName: 'enable',
     file: 'objects/sections.rs',
-    line: 531,
+    line: 626,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1237,7 +1516,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'set_superseded',
     camelName: 'setSuperseded',
     file: 'objects/sections.rs',
-    line: 538,
+    line: 633,
     paramCount: 2,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1246,7 +1525,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'clear_superseded',
     camelName: 'clearSuperseded',
     file: 'objects/sections.rs',
-    line: 549,
+    line: 644,
     paramCount: 1,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1255,7 +1534,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'import_disabled_ids',
     camelName: 'importDisabledIds',
     file: 'objects/sections.rs',
-    line: 556,
+    line: 651,
     paramCount: 1,
     returnType: 'Result<u32, VeloqError>',
     object: 'SectionManager',
@@ -1264,7 +1543,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'import_superseded_map',
     camelName: 'importSupersededMap',
     file: 'objects/sections.rs',
-    line: 563,
+    line: 658,
     paramCount: 1,
     returnType: 'Result<u32, VeloqError>',
     object: 'SectionManager',
@@ -1273,7 +1552,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_all_summaries_including_hidden',
     camelName: 'getAllSummariesIncludingHidden',
     file: 'objects/sections.rs',
-    line: 578,
+    line: 673,
     paramCount: 1,
     returnType: 'Result<Vec<crate::SectionSummary>, VeloqError>',
     object: 'SectionManager',
@@ -1282,7 +1561,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'extract_traces_batch',
     camelName: 'extractTracesBatch',
     file: 'objects/sections.rs',
-    line: 594,
+    line: 689,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiBatchTrace>, VeloqError>',
     object: 'SectionManager',
@@ -1291,7 +1570,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'match_activity_to_sections',
     camelName: 'matchActivityToSections',
     file: 'objects/sections.rs',
-    line: 635,
+    line: 730,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiSectionMatch>, VeloqError>',
     object: 'SectionManager',
@@ -1300,7 +1579,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'index_new_activity',
     camelName: 'indexNewActivity',
     file: 'objects/sections.rs',
-    line: 680,
+    line: 775,
     paramCount: 1,
     returnType: 'Result<crate::FfiIndexActivitySummary, VeloqError>',
     object: 'SectionManager',
@@ -1309,7 +1588,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'rematch_activity_to_section',
     camelName: 'rematchActivityToSection',
     file: 'objects/sections.rs',
-    line: 694,
+    line: 789,
     paramCount: 2,
     returnType: 'Result<bool, VeloqError>',
     object: 'SectionManager',
@@ -1318,7 +1597,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_nearby_sections',
     camelName: 'getNearbySections',
     file: 'objects/sections.rs',
-    line: 767,
+    line: 862,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiNearbySectionSummary>, VeloqError>',
     object: 'SectionManager',
@@ -1327,7 +1606,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_merge_candidates',
     camelName: 'getMergeCandidates',
     file: 'objects/sections.rs',
-    line: 777,
+    line: 872,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiMergeCandidate>, VeloqError>',
     object: 'SectionManager',
@@ -1336,7 +1615,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'merge_sections',
     camelName: 'mergeSections',
     file: 'objects/sections.rs',
-    line: 786,
+    line: 881,
     paramCount: 2,
     returnType: 'Result<String, VeloqError>',
     object: 'SectionManager',
@@ -1345,7 +1624,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_section_highlights',
     camelName: 'getActivitySectionHighlights',
     file: 'objects/sections.rs',
-    line: 801,
+    line: 896,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiActivitySectionHighlight>, VeloqError>',
     object: 'SectionManager',
@@ -1354,7 +1633,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_indicators',
     camelName: 'getActivityIndicators',
     file: 'objects/sections.rs',
-    line: 811,
+    line: 906,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiActivityIndicator>, VeloqError>',
     object: 'SectionManager',
@@ -1363,7 +1642,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_indicators_for_activity',
     camelName: 'getIndicatorsForActivity',
     file: 'objects/sections.rs',
-    line: 819,
+    line: 914,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiActivityIndicator>, VeloqError>',
     object: 'SectionManager',
@@ -1372,7 +1651,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_section_encounters',
     camelName: 'getActivitySectionEncounters',
     file: 'objects/sections.rs',
-    line: 828,
+    line: 923,
     paramCount: 1,
     returnType: 'Result<Vec<crate::FfiSectionEncounter>, VeloqError>',
     object: 'SectionManager',
@@ -1381,7 +1660,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'recompute_indicators',
     camelName: 'recomputeIndicators',
     file: 'objects/sections.rs',
-    line: 837,
+    line: 932,
     paramCount: 0,
     returnType: 'Result<(), VeloqError>',
     object: 'SectionManager',
@@ -1390,7 +1669,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_activity_pr_sections',
     camelName: 'getActivityPrSections',
     file: 'objects/sections.rs',
-    line: 849,
+    line: 944,
     paramCount: 2,
     returnType: 'Result<Vec<String>, VeloqError>',
     object: 'SectionManager',
@@ -1399,7 +1678,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_workout_sections',
     camelName: 'getWorkoutSections',
     file: 'objects/sections.rs',
-    line: 870,
+    line: 965,
     paramCount: 2,
     returnType: 'Result<Vec<crate::FfiWorkoutSection>, VeloqError>',
     object: 'SectionManager',
@@ -1408,7 +1687,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'get_chart_data',
     camelName: 'getChartData',
     file: 'objects/sections.rs',
-    line: 881,
+    line: 976,
     paramCount: 3,
     returnType: 'Result<crate::FfiSectionChartData, VeloqError>',
     object: 'SectionManager',
@@ -1659,8 +1938,8 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
   {
     name: 'new',
     camelName: 'new',
-    file: 'objects/sync.rs',
-    line: 246,
+    file: 'objects/sync/mod.rs',
+    line: 1140,
     paramCount: 0,
     returnType: 'Arc<Self>',
     object: 'SyncManager',
@@ -1668,17 +1947,62 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
   {
     name: 'set_credentials',
     camelName: 'setCredentials',
-    file: 'objects/sync.rs',
-    line: 251,
+    file: 'objects/sync/mod.rs',
+    line: 1145,
     paramCount: 3,
     returnType: 'Result<(), VeloqError>',
     object: 'SyncManager',
   },
+  {
+    name: 'set_oauth_refresh',
+    camelName: 'setOauthRefresh',
+    file: 'objects/sync/mod.rs',
+    line: 1162,
+    paramCount: 2,
+    returnType: 'Result<(), VeloqError>',
+    object: 'SyncManager',
+  },
+  {
+    name: 'get_oauth_tokens',
+    camelName: 'getOauthTokens',
+    file: 'objects/sync/mod.rs',
+    line: 1173,
+    paramCount: 0,
+    returnType: 'Option<FfiOAuthTokens>',
+    object: 'SyncManager',
+  },
+  {
+    name: 'enable_demo_mode',
+    camelName: 'enableDemoMode',
+    file: 'objects/sync/mod.rs',
+    line: 1185,
+    paramCount: 2,
+    returnType: 'Result<(), VeloqError>',
+    object: 'SyncManager',
+  },
+  {
+    name: 'disable_demo_mode',
+    camelName: 'disableDemoMode',
+    file: 'objects/sync/mod.rs',
+    line: 1207,
+    paramCount: 0,
+    returnType: 'Result<(), VeloqError>',
+    object: 'SyncManager',
+  },
+  {
+    name: 'set_recording_dir',
+    camelName: 'setRecordingDir',
+    file: 'objects/sync/mod.rs',
+    line: 1215,
+    paramCount: 1,
+    returnType: 'Result<(), VeloqError>',
+    object: 'SyncManager',
+  },
   {
     name: 'clear_credentials',
     camelName: 'clearCredentials',
-    file: 'objects/sync.rs',
-    line: 265,
+    file: 'objects/sync/mod.rs',
+    line: 1227,
     paramCount: 0,
     returnType: 'void',
     object: 'SyncManager',
@@ -1686,26 +2010,35 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
   {
     name: 'sync_now',
     camelName: 'syncNow',
-    file: 'objects/sync.rs',
-    line: 272,
+    file: 'objects/sync/mod.rs',
+    line: 1238,
     paramCount: 0,
     returnType: 'Result<bool, VeloqError>',
     object: 'SyncManager',
   },
   {
-    name: 'drop',
-    camelName: 'drop',
-    file: 'objects/sync.rs',
-    line: 285,
+    name: 'start_backfill',
+    camelName: 'startBackfill',
+    file: 'objects/sync/mod.rs',
+    line: 1251,
     paramCount: 0,
-    returnType: 'void',
+    returnType: 'Result<bool, VeloqError>',
+    object: 'SyncManager',
+  },
+  {
+    name: 'set_wellness_overlap_days',
+    camelName: 'setWellnessOverlapDays',
+    file: 'objects/sync/mod.rs',
+    line: 1262,
+    paramCount: 1,
+    returnType: 'Result<(), VeloqError>',
     object: 'SyncManager',
   },
   {
     name: 'cancel',
     camelName: 'cancel',
-    file: 'objects/sync.rs',
-    line: 308,
+    file: 'objects/sync/mod.rs',
+    line: 1272,
     paramCount: 0,
     returnType: 'void',
     object: 'SyncManager',
@@ -1713,8 +2046,8 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
   {
     name: 'get_sync_status',
     camelName: 'getSyncStatus',
-    file: 'objects/sync.rs',
-    line: 313,
+    file: 'objects/sync/mod.rs',
+    line: 1277,
     paramCount: 0,
     returnType: 'FfiSyncStatus',
     object: 'SyncManager',
@@ -1795,7 +2128,7 @@ export const FFI_EXPORTS: FfiExportInfo[] = [
     name: 'compute_polyline_overlap',
     camelName: 'computePolylineOverlap',
     file: 'persistence/mod.rs',
-    line: 1477,
+    line: 1577,
     paramCount: 3,
     returnType: 'f64',
   },
@@ -1819,6 +2152,7 @@ export const EXPECTED_TS_FUNCTIONS = new Set<string>([
   'setTimeStreams',
   'getMissingTimeStreams',
   'getGpsTrack',
+  'getStreams',
   'remove',
   'debugClone',
   'getHighlightsBundle',
@@ -1857,13 +2191,29 @@ export const EXPECTED_TS_FUNCTIONS = new Set<string>([
   'getBackupMetadata',
   'bulkExportGpx',
   'bulkExportGeojson',
+  'exportRouteCourse',
+  'exportSectionCourse',
+  'exportCourse',
   'new',
   'getActivityMetricIds',
   'getPeriodStats',
   'getZoneDistribution',
+  'getActivityZoneTimes',
   'getFtpTrend',
   'savePaceSnapshot',
   'getPaceTrend',
+  'fitCriticalPower',
+  'getCpTrend',
+  'getRacePredictions',
+  'getBestEffortTargets',
+  'setBestEffortTargets',
+  'getBestEffortRecords',
+  'getActivityBestEfforts',
+  'getPowerCurve',
+  'getPaceCurve',
+  'getBestPower',
+  'getBestPace',
+  'compareCurveWindows',
   'getAvailableSportTypes',
   'getActivityHeatmap',
   'getSummaryCardData',
@@ -1872,6 +2222,7 @@ export const EXPECTED_TS_FUNCTIONS = new Set<string>([
   'getActivityPatternsWithToday',
   'upsertWellness',
   'getWellnessSparklines',
+  'getFitnessModel',
   'computeHrvTrend',
   'findStalePrOpportunities',
   'getInsightsData',
@@ -1889,7 +2240,10 @@ export const EXPECTED_TS_FUNCTIONS = new Set<string>([
   'getSummariesWithCount',
   'getFilteredSummaries',
   'getConsensusRoute',
+  'getRouteDeviation',
+  'getClimbProfile',
   'getPerformances',
+  'predictTime',
   'getScreenData',
   'setName',
   'getAllNames',
@@ -1899,6 +2253,9 @@ export const EXPECTED_TS_FUNCTIONS = new Set<string>([
   'getExcludedPerformances',
   'getActivityRouteHighlights',
   'setRepresentative',
+  'suggestRoutes',
+  'mergeRoutes',
+  'splitRoute',
   'new',
   'getAll',
   'getFiltered',
@@ -1914,6 +2271,13 @@ export const EXPECTED_TS_FUNCTIONS = new Set<string>([
   'pruneOverlapping',
   'getPolyline',
   'getPerformances',
+  'predictTime',
+  'compareEfforts',
+  'getClimbProfile',
+  'getCheckpoints',
+  'setCheckpoints',
+  'generateCheckpoints',
+  'getSplitTable',
   'getPerformancesBatch',
   'recalculatePolyline',
   'getExcludedPerformances',
@@ -1988,9 +2352,15 @@ export const EXPECTED_TS_FUNCTIONS = new Set<string>([
   'getMuscleDetail',
   'new',
   'setCredentials',
+  'setOauthRefresh',
+  'getOauthTokens',
+  'enableDemoMode',
+  'disableDemoMode',
+  'setRecordingDir',
   'clearCredentials',
   'syncNow',
-  'drop',
+  'startBackfill',
+  'setWellnessOverlapDays',
   'cancel',
   'getSyncStatus',
   'new',
@@ -2026,6 +2396,7 @@ export const RUST_TO_TS_NAME: Record<string, string> = {
   set_time_streams: 'setTimeStreams',
   get_missing_time_streams: 'getMissingTimeStreams',
   get_gps_track: 'getGpsTrack',
+  get_streams: 'getStreams',
   remove: 'remove',
   debug_clone: 'debugClone',
   get_highlights_bundle: 'getHighlightsBundle',
@@ -2063,12 +2434,28 @@ export const RUST_TO_TS_NAME: Record<string, string> = {
   get_backup_metadata: 'getBackupMetadata',
   bulk_export_gpx: 'bulkExportGpx',
   bulk_export_geojson: 'bulkExportGeojson',
+  export_route_course: 'exportRouteCourse',
+  export_section_course: 'exportSectionCourse',
+  export_course: 'exportCourse',
   get_activity_metric_ids: 'getActivityMetricIds',
   get_period_stats: 'getPeriodStats',
   get_zone_distribution: 'getZoneDistribution',
+  get_activity_zone_times: 'getActivityZoneTimes',
   get_ftp_trend: 'getFtpTrend',
   save_pace_snapshot: 'savePaceSnapshot',
   get_pace_trend: 'getPaceTrend',
+  fit_critical_power: 'fitCriticalPower',
+  get_cp_trend: 'getCpTrend',
+  get_race_predictions: 'getRacePredictions',
+  get_best_effort_targets: 'getBestEffortTargets',
+  set_best_effort_targets: 'setBestEffortTargets',
+  get_best_effort_records: 'getBestEffortRecords',
+  get_activity_best_efforts: 'getActivityBestEfforts',
+  get_power_curve: 'getPowerCurve',
+  get_pace_curve: 'getPaceCurve',
+  get_best_power: 'getBestPower',
+  get_best_pace: 'getBestPace',
+  compare_curve_windows: 'compareCurveWindows',
   get_available_sport_types: 'getAvailableSportTypes',
   get_activity_heatmap: 'getActivityHeatmap',
   get_summary_card_data: 'getSummaryCardData',
@@ -2077,6 +2464,7 @@ export const RUST_TO_TS_NAME: Record<string, string> = {
   get_activity_patterns_with_today: 'getActivityPatternsWithToday',
   upsert_wellness: 'upsertWellness',
   get_wellness_sparklines: 'getWellnessSparklines',
+  get_fitness_model: 'getFitnessModel',
   compute_hrv_trend: 'computeHrvTrend',
   find_stale_pr_opportunities: 'findStalePrOpportunities',
   get_insights_data: 'getInsightsData',
@@ -2092,7 +2480,10 @@ export const RUST_TO_TS_NAME: Record<string, string> = {
   get_summaries_with_count: 'getSummariesWithCount',
   get_filtered_summaries: 'getFilteredSummaries',
   get_consensus_route: 'getConsensusRoute',
+  get_route_deviation: 'getRouteDeviation',
+  get_climb_profile: 'getClimbProfile',
   get_performances: 'getPerformances',
+  predict_time: 'predictTime',
   get_screen_data: 'getScreenData',
   set_name: 'setName',
   get_all_names: 'getAllNames',
@@ -2102,12 +2493,20 @@ export const RUST_TO_TS_NAME: Record<string, string> = {
   get_excluded_performances: 'getExcludedPerformances',
   get_activity_route_highlights: 'getActivityRouteHighlights',
   set_representative: 'setRepresentative',
+  suggest_routes: 'suggestRoutes',
+  merge_routes: 'mergeRoutes',
+  split_route: 'splitRoute',
   get_by_type: 'getByType',
   get_for_activity: 'getForActivity',
   get_ranked: 'getRanked',
   get_ranked_batch: 'getRankedBatch',
   prune_overlapping: 'pruneOverlapping',
   get_polyline: 'getPolyline',
+  compare_efforts: 'compareEfforts',
+  get_checkpoints: 'getCheckpoints',
+  set_checkpoints: 'setCheckpoints',
+  generate_checkpoints: 'generateCheckpoints',
+  get_split_table: 'getSplitTable',
   get_performances_batch: 'getPerformancesBatch',
   recalculate_polyline: 'recalculatePolyline',
   get_calendar_summary: 'getCalendarSummary',
@@ -2172,9 +2571,15 @@ export const RUST_TO_TS_NAME: Record<string, string> = {
   get_muscle_groups: 'getMuscleGroups',
   get_muscle_detail: 'getMuscleDetail',
   set_credentials: 'setCredentials',
+  set_oauth_refresh: 'setOauthRefresh',
+  get_oauth_tokens: 'getOauthTokens',
+  enable_demo_mode: 'enableDemoMode',
+  disable_demo_mode: 'disableDemoMode',
+  set_recording_dir: 'setRecordingDir',
   clear_credentials: 'clearCredentials',
   sync_now: 'syncNow',
-  drop: 'drop',
+  start_backfill: 'startBackfill',
+  set_wellness_overlap_days: 'setWellnessOverlapDays',
   cancel: 'cancel',
   get_sync_status: 'getSyncStatus',
   set_tiles_path: 'setTilesPath',
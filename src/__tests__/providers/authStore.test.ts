@@ -27,6 +27,7 @@ const mockDeleteItemAsync = SecureStore.deleteItemAsync as jest.MockedFunction<
 const API_KEY_STORAGE_KEY = 'intervals_api_key';
 const ATHLETE_ID_STORAGE_KEY = 'intervals_athlete_id';
 const ACCESS_TOKEN_STORAGE_KEY = 'intervals_access_token';
+const REFRESH_TOKEN_STORAGE_KEY = 'intervals_refresh_token';
 
 describe('AuthStore', () => {
   beforeEach(() => {
@@ -151,6 +152,24 @@ describe('AuthStore', () => {
       expect(state.athlete?.name).toBe('John Doe');
     });
 
+    it('stores the refresh token when the proxy issued one', async () => {
+      await useAuthStore
+        .getState()
+        .setOAuthCredentials('oauth-abc', 'i55555', undefined, 'refresh-abc');
+
+      expect(mockSetItemAsync).toHaveBeenCalledWith(
+        REFRESH_TOKEN_STORAGE_KEY,
+        'refresh-abc',
+        expect.anything()
+      );
+    });
+
+    it('drops a stale refresh token on a login without one', async () => {
+      await useAuthStore.getState().setOAuthCredentials('oauth-abc', 'i55555');
+
+      expect(mockDeleteItemAsync).toHaveBeenCalledWith(REFRESH_TOKEN_STORAGE_KEY);
+    });
+
     it('updates state correctly after OAuth login', async () => {
       await useAuthStore.getState().setOAuthCredentials('oauth-xyz', 'i99999');
 
@@ -177,6 +196,7 @@ describe('AuthStore', () => {
       expect(mockDeleteItemAsync).toHaveBeenCalledWith(API_KEY_STORAGE_KEY);
       expect(mockDeleteItemAsync).toHaveBeenCalledWith(ATHLETE_ID_STORAGE_KEY);
       expect(mockDeleteItemAsync).toHaveBeenCalledWith(ACCESS_TOKEN_STORAGE_KEY);
+      expect(mockDeleteItemAsync).toHaveBeenCalledWith(REFRESH_TOKEN_STORAGE_KEY);
     });
 
     it('resets all auth state', async () => {
@@ -173,6 +173,13 @@ describe('OAuth service', () => {
       expect(result!.state).toBe('statexyz');
     });
 
+    it('parses the refresh token when the proxy passes one', () => {
+      const url =
+        'veloq://oauth/callback?success=true&access_token=abc123&refresh_token=ref456&athlete_id=i12345';
+      const result = parseCallbackUrl(url);
+      expect(result!.refresh_token).toBe('ref456');
+    });
+
     it('applies defaults when optional fields are missing', () => {
       const url = 'veloq://oauth/callback?success=true&access_token=tok&athlete_id=iABC';
       const result = parseCallbackUrl(url);
@@ -180,6 +187,7 @@ describe('OAuth service', () => {
       expect(result!.token_type).toBe('Bearer');
       expect(result!.scope).toBe('');
       expect(result!.athlete_name).toBe('');
+      expect(result!.refresh_token).toBeUndefined();
       expect(result!.state).toBeUndefined();
     });
 
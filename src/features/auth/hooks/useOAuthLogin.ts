@@ -72,7 +72,8 @@ export function useOAuthLogin({ setError }: UseOAuthLoginParams) {
         await setOAuthCredentials(
           tokenResponse.access_token,
           tokenResponse.athlete_id,
-          tokenResponse.athlete_name
+          tokenResponse.athlete_name,
+          tokenResponse.refresh_token
         );
 
         if (tokenResponse.scope) {
@@ -28,6 +28,8 @@ function generateState(): string {
 
 export interface OAuthTokenResponse {
   access_token: string;
+  /** Present when intervals.icu issued one; lets the engine renew the session */
+  refresh_token?: string;
   token_type: string;
   scope: string;
   athlete_id: string;
@@ -162,6 +164,7 @@ export function parseCallbackUrl(url: string): OAuthTokenResponse | null {
     if (params.success === 'true' && params.access_token && params.athlete_id) {
       return {
         access_token: params.access_token as string,
+        refresh_token: (params.refresh_token as string) || undefined,
         token_type: (params.token_type as string) || 'Bearer',
         scope: (params.scope as string) || '',
         athlete_id: params.athlete_id as string,
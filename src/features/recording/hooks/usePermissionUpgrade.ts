@@ -51,7 +51,8 @@ export function usePermissionUpgrade(): UsePermissionUpgrade {
           .setOAuthCredentials(
             tokenResponse.access_token,
             tokenResponse.athlete_id,
-            tokenResponse.athlete_name
+            tokenResponse.athlete_name,
+            tokenResponse.refresh_token
           );
 
         // Trust only the scope the server actually returned - a missing scope
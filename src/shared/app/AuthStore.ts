@@ -3,10 +3,12 @@ import * as SecureStore from 'expo-secure-store';
 
 import type { Athlete } from '@/types';
 import { getRouteEngine } from '@/shared/native/routeEngine';
+import { OAUTH } from '@/features/auth/constants';
 
 const API_KEY_STORAGE_KEY = 'intervals_api_key';
 const ATHLETE_ID_STORAGE_KEY = 'intervals_athlete_id';
 const ACCESS_TOKEN_STORAGE_KEY = 'intervals_access_token';
+const REFRESH_TOKEN_STORAGE_KEY = 'intervals_refresh_token';
 
 /**
  * Validates that a credential is non-null and non-empty after trimming.
@@ -16,6 +18,37 @@ function isValidCredential(credential: string | null): credential is string {
   return credential !== null && credential.trim().length > 0;
 }
 
+/**
+ * Hand an OAuth session to the engine's sync service. With a refresh token the
+ * engine renews an expired access token through the proxy on its own.
+ * Best-effort - the engine may not be loaded yet.
+ */
+function configureEngineOAuth(
+  accessToken: string,
+  athleteId: string,
+  refreshToken: string | null
+): void {
+  const engine = getRouteEngine();
+  if (!engine) return;
+  try {
+    engine.setSyncCredentials('oauth', accessToken, athleteId);
+    if (refreshToken) {
+      engine.setSyncOAuthRefresh(refreshToken, `${OAUTH.PROXY_URL}/oauth/refresh`);
+    }
+  } catch {
+    // Engine sync is optional until the bindings carry SyncManager
+  }
+}
+
+/** Drop the engine's sync credentials (and its refresher). Best-effort. */
+function clearEngineCredentials(): void {
+  try {
+    getRouteEngine()?.clearSyncCredentials();
+  } catch {
+    // Engine may have been cleared already
+  }
+}
+
 // Demo mode athlete ID
 export const DEMO_ATHLETE_ID = 'demo';
 
@@ -44,8 +77,11 @@ interface AuthState {
   setOAuthCredentials: (
     accessToken: string,
     athleteId: string,
-    athleteName?: string
+    athleteName?: string,
+    refreshToken?: string
   ) => Promise<void>;
+  /** Persist the token pair the engine holds after a refresh rotated it */
+  persistRefreshedTokens: () => Promise<void>;
   clearCredentials: () => Promise<void>;
   setAthlete: (athlete: Athlete) => void;
   enterDemoMode: () => void;
@@ -71,10 +107,11 @@ export const useAuthStore = create<AuthState>((set, get) => ({
 
   initialize: async () => {
     try {
-      const [apiKey, athleteId, accessToken] = await Promise.all([
+      const [apiKey, athleteId, accessToken, refreshToken] = await Promise.all([
         SecureStore.getItemAsync(API_KEY_STORAGE_KEY),
         SecureStore.getItemAsync(ATHLETE_ID_STORAGE_KEY),
         SecureStore.getItemAsync(ACCESS_TOKEN_STORAGE_KEY),
+        SecureStore.getItemAsync(REFRESH_TOKEN_STORAGE_KEY),
       ]);
 
       // Determine auth method: OAuth takes priority over API key
@@ -84,6 +121,11 @@ export const useAuthStore = create<AuthState>((set, get) => ({
       if (isValidCredential(accessToken) && isValidCredential(athleteId)) {
         authMethod = 'oauth';
         isAuthenticated = true;
+        configureEngineOAuth(
+          accessToken.trim(),
+          athleteId.trim(),
+          isValidCredential(refreshToken) ? refreshToken.trim() : null
+        );
       } else if (isValidCredential(apiKey) && isValidCredential(athleteId)) {
         authMethod = 'apiKey';
         isAuthenticated = true;
@@ -138,8 +180,9 @@ export const useAuthStore = create<AuthState>((set, get) => ({
       SecureStore.setItemAsync(ATHLETE_ID_STORAGE_KEY, trimmedAthleteId, {
         keychainAccessible: SecureStore.WHEN_UNLOCKED_THIS_DEVICE_ONLY,
       }),
-      // Clear OAuth token when using API key auth
+      // Clear OAuth tokens when using API key auth
       SecureStore.deleteItemAsync(ACCESS_TOKEN_STORAGE_KEY),
+      SecureStore.deleteItemAsync(REFRESH_TOKEN_STORAGE_KEY),
     ]);
 
     set({
@@ -152,10 +195,16 @@ export const useAuthStore = create<AuthState>((set, get) => ({
     });
   },
 
-  setOAuthCredentials: async (accessToken: string, athleteId: string, athleteName?: string) => {
+  setOAuthCredentials: async (
+    accessToken: string,
+    athleteId: string,
+    athleteName?: string,
+    refreshToken?: string
+  ) => {
     // Trim and validate credentials
     const trimmedAccessToken = accessToken?.trim() ?? '';
     const trimmedAthleteId = athleteId?.trim() ?? '';
+    const trimmedRefreshToken = refreshToken?.trim() ?? '';
 
     if (!isValidCredential(trimmedAccessToken) || !isValidCredential(trimmedAthleteId)) {
       if (__DEV__) {
@@ -171,10 +220,22 @@ export const useAuthStore = create<AuthState>((set, get) => ({
       SecureStore.setItemAsync(ATHLETE_ID_STORAGE_KEY, trimmedAthleteId, {
         keychainAccessible: SecureStore.WHEN_UNLOCKED_THIS_DEVICE_ONLY,
       }),
+      // A login without a refresh token must not leave an older one behind
+      isValidCredential(trimmedRefreshToken)
+        ? SecureStore.setItemAsync(REFRESH_TOKEN_STORAGE_KEY, trimmedRefreshToken, {
+            keychainAccessible: SecureStore.WHEN_UNLOCKED_THIS_DEVICE_ONLY,
+          })
+        : SecureStore.deleteItemAsync(REFRESH_TOKEN_STORAGE_KEY),
       // Clear API key when using OAuth
       SecureStore.deleteItemAsync(API_KEY_STORAGE_KEY),
     ]);
 
+    configureEngineOAuth(
+      trimmedAccessToken,
+      trimmedAthleteId,
+      isValidCredential(trimmedRefreshToken) ? trimmedRefreshToken : null
+    );
+
     set({
       accessToken: trimmedAccessToken,
       apiKey: null,
@@ -204,6 +265,27 @@ export const useAuthStore = create<AuthState>((set, get) => ({
     }
   },
 
+  persistRefreshedTokens: async () => {
+    if (get().authMethod !== 'oauth') return;
+    let tokens: { accessToken: string; refreshToken: string } | null = null;
+    try {
+      tokens = getRouteEngine()?.getSyncOAuthTokens() ?? null;
+    } catch {
+      return;
+    }
+    if (!tokens || tokens.accessToken === get().accessToken) return;
+
+    await Promise.all([
+      SecureStore.setItemAsync(ACCESS_TOKEN_STORAGE_KEY, tokens.accessToken, {
+        keychainAccessible: SecureStore.WHEN_UNLOCKED_THIS_DEVICE_ONLY,
+      }),
+      SecureStore.setItemAsync(REFRESH_TOKEN_STORAGE_KEY, tokens.refreshToken, {
+        keychainAccessible: SecureStore.WHEN_UNLOCKED_THIS_DEVICE_ONLY,
+      }),
+    ]);
+    set({ accessToken: tokens.accessToken });
+  },
+
   clearCredentials: async () => {
     // Unregister push token before clearing credentials (fire-and-forget)
     try {
@@ -226,7 +308,9 @@ export const useAuthStore = create<AuthState>((set, get) => ({
       SecureStore.deleteItemAsync(API_KEY_STORAGE_KEY),
       SecureStore.deleteItemAsync(ATHLETE_ID_STORAGE_KEY),
       SecureStore.deleteItemAsync(ACCESS_TOKEN_STORAGE_KEY),
+      SecureStore.deleteItemAsync(REFRESH_TOKEN_STORAGE_KEY),
     ]);
+    clearEngineCredentials();
 
     set({
       apiKey: null,
@@ -297,8 +381,10 @@ export const useAuthStore = create<AuthState>((set, get) => ({
     // Clear OAuth credentials from storage
     await Promise.all([
       SecureStore.deleteItemAsync(ACCESS_TOKEN_STORAGE_KEY),
+      SecureStore.deleteItemAsync(REFRESH_TOKEN_STORAGE_KEY),
       SecureStore.deleteItemAsync(ATHLETE_ID_STORAGE_KEY),
     ]);
+    clearEngineCredentials();
 
     // Update state to logged out with session expired reason
     set({
@@ -4,10 +4,12 @@
  * Reads `SyncManager.get_sync_status()` via the engine, refreshing on the `sync`
  * notify channel and polling only while a sync is in flight. The command +
  * status boundary means the JS thread never blocks on I/O, so this hook only
- * ever reads a cheap snapshot.
+ * ever reads a cheap snapshot. When a sync settles, any OAuth tokens the
+ * engine rotated on the way are persisted through the auth store.
  */
 import { useEffect, useState } from 'react';
 import { getRouteEngine } from './routeEngine';
+import { useAuthStore } from '@/shared/app/AuthStore';
 import type { SyncStatus } from 'veloqrs';
 
 export function useSyncStatus(pollMs = 1500): SyncStatus | null {
@@ -37,5 +39,12 @@ export function useSyncStatus(pollMs = 1500): SyncStatus | null {
     return () => clearInterval(interval);
   }, [status?.state, pollMs]);
 
+  // A refresh during the sync rotated the token pair held by the engine.
+  // Persist it so the next launch doesn't start from a revoked refresh token.
+  useEffect(() => {
+    if (!status || status.state === 'syncing') return;
+    useAuthStore.getState().persistRefreshedTokens();
+  }, [status?.state]);
+
   return status;
 }